@@ -0,0 +1,3 @@
+DROP TABLE legal_acceptances;
+
+DROP TYPE LEGAL_DOCUMENT;
@@ -0,0 +1,13 @@
+DO $$ BEGIN
+    CREATE TYPE LEGAL_DOCUMENT AS ENUM ('TermsAndConditions', 'PrivacyPolicy');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+CREATE TABLE IF NOT EXISTS legal_acceptances (
+    user_id UUID NOT NULL REFERENCES users (user_id) ON DELETE CASCADE,
+    document LEGAL_DOCUMENT NOT NULL,
+    version DATE NOT NULL,
+    accepted TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (user_id, document, version)
+);
@@ -0,0 +1,6 @@
+DROP TABLE comments;
+
+DROP TABLE posts;
+
+ALTER TABLE users DROP COLUMN bio_html;
+ALTER TABLE users DROP COLUMN bio;
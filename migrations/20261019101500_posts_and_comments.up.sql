@@ -0,0 +1,35 @@
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS bio TEXT NOT NULL DEFAULT '' CHECK (octet_length(bio) <= 40000),
+    -- the rendered and sanitized bio
+    ADD COLUMN IF NOT EXISTS bio_html TEXT NOT NULL DEFAULT '';
+
+CREATE TABLE IF NOT EXISTS posts (
+    post_id UUID PRIMARY KEY,
+    created TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated TIMESTAMP WITH TIME ZONE NOT NULL,
+    author UUID NOT NULL REFERENCES users (user_id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    CHECK (octet_length(title) <= 300),
+    url TEXT,
+    body TEXT NOT NULL,
+    CHECK (octet_length(body) <= 40000),
+    -- the rendered and sanitized body
+    body_html TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS posts_created ON posts (created DESC);
+
+CREATE TABLE IF NOT EXISTS comments (
+    comment_id UUID PRIMARY KEY,
+    created TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated TIMESTAMP WITH TIME ZONE NOT NULL,
+    post_id UUID NOT NULL REFERENCES posts (post_id) ON DELETE CASCADE,
+    parent_id UUID REFERENCES comments (comment_id) ON DELETE CASCADE,
+    author UUID NOT NULL REFERENCES users (user_id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    CHECK (octet_length(body) <= 40000),
+    -- the rendered and sanitized body
+    body_html TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS comments_post_id ON comments (post_id, created);
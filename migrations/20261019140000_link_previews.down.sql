@@ -0,0 +1,3 @@
+DROP TABLE link_previews;
+
+ALTER TABLE posts DROP COLUMN domain;
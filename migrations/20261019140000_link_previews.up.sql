@@ -0,0 +1,13 @@
+-- the registrable domain of the url, e.g. `example.co.uk` for `https://www.example.co.uk/a`
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS domain TEXT;
+
+-- posts with a url but without a row here still have to be fetched
+CREATE TABLE IF NOT EXISTS link_previews (
+    post_id UUID PRIMARY KEY REFERENCES posts (post_id) ON DELETE CASCADE,
+    fetched TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- all null if the page couldn't be fetched or had no metadata
+    title TEXT,
+    description TEXT,
+    site_name TEXT,
+    image_url TEXT
+);
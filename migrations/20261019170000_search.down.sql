@@ -0,0 +1,10 @@
+DROP TRIGGER comments_search_update ON comments;
+DROP TRIGGER posts_search_update ON posts;
+
+DROP FUNCTION comments_search_update;
+DROP FUNCTION posts_search_update;
+
+ALTER TABLE comments DROP COLUMN search, DROP COLUMN search_config;
+ALTER TABLE posts DROP COLUMN search, DROP COLUMN search_config;
+
+DROP FUNCTION language_search_config;
@@ -0,0 +1,44 @@
+-- the text search configuration used for text written in a language
+CREATE OR REPLACE FUNCTION language_search_config(language LANGUAGE) RETURNS REGCONFIG AS $$
+    SELECT CASE language
+        WHEN 'English' THEN 'english'
+        WHEN 'Spanish' THEN 'spanish'
+        WHEN 'German' THEN 'german'
+        WHEN 'French' THEN 'french'
+    END::REGCONFIG
+$$ LANGUAGE SQL IMMUTABLE;
+
+-- the configuration is the one of the author's language at the time of writing
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS search_config REGCONFIG,
+    ADD COLUMN IF NOT EXISTS search TSVECTOR;
+
+ALTER TABLE comments
+    ADD COLUMN IF NOT EXISTS search_config REGCONFIG,
+    ADD COLUMN IF NOT EXISTS search TSVECTOR;
+
+CREATE OR REPLACE FUNCTION posts_search_update() RETURNS TRIGGER AS $$ BEGIN
+    NEW.search_config := (SELECT language_search_config(language) FROM users WHERE user_id = NEW.author);
+    NEW.search := setweight(to_tsvector(NEW.search_config, NEW.title), 'A')
+        || setweight(to_tsvector(NEW.search_config, NEW.body), 'B');
+    RETURN NEW;
+END $$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION comments_search_update() RETURNS TRIGGER AS $$ BEGIN
+    NEW.search_config := (SELECT language_search_config(language) FROM users WHERE user_id = NEW.author);
+    NEW.search := to_tsvector(NEW.search_config, NEW.body);
+    RETURN NEW;
+END $$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER posts_search_update BEFORE INSERT OR UPDATE OF title, body ON posts
+    FOR EACH ROW EXECUTE FUNCTION posts_search_update();
+
+CREATE OR REPLACE TRIGGER comments_search_update BEFORE INSERT OR UPDATE OF body ON comments
+    FOR EACH ROW EXECUTE FUNCTION comments_search_update();
+
+-- fill in the existing rows
+UPDATE posts SET title = title WHERE search IS NULL;
+UPDATE comments SET body = body WHERE search IS NULL;
+
+CREATE INDEX IF NOT EXISTS posts_search ON posts USING GIN (search);
+CREATE INDEX IF NOT EXISTS comments_search ON comments USING GIN (search);
@@ -0,0 +1,13 @@
+ALTER TABLE posts
+    DROP COLUMN removed,
+    DROP COLUMN locked,
+    DROP COLUMN pinned,
+    DROP COLUMN community_id;
+
+DROP TABLE moderators;
+
+DROP TABLE subscriptions;
+
+DROP TABLE community_rules;
+
+DROP TABLE communities;
@@ -0,0 +1,51 @@
+CREATE TABLE IF NOT EXISTS communities (
+    community_id UUID PRIMARY KEY,
+    created TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated TIMESTAMP WITH TIME ZONE NOT NULL,
+    name TEXT NOT NULL,
+    CHECK (octet_length(name) <= 21),
+    CHECK (octet_length(name) >= 3),
+    description TEXT NOT NULL,
+    CHECK (octet_length(description) <= 500),
+    creator UUID NOT NULL REFERENCES users (user_id)
+);
+
+-- names are unique regardless of case
+CREATE UNIQUE INDEX IF NOT EXISTS communities_name ON communities (lower(name));
+
+-- the rules shown in the sidebar, in order
+CREATE TABLE IF NOT EXISTS community_rules (
+    community_id UUID NOT NULL REFERENCES communities (community_id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    rule TEXT NOT NULL,
+    CHECK (octet_length(rule) <= 300),
+    PRIMARY KEY (community_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS subscriptions (
+    community_id UUID NOT NULL REFERENCES communities (community_id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (user_id) ON DELETE CASCADE,
+    created TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (community_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS subscriptions_user_id ON subscriptions (user_id);
+
+CREATE TABLE IF NOT EXISTS moderators (
+    community_id UUID NOT NULL REFERENCES communities (community_id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (user_id) ON DELETE CASCADE,
+    created TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (community_id, user_id)
+);
+
+-- posts from before communities existed don't belong to one
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS community_id UUID REFERENCES communities (community_id),
+    -- pinned posts are shown first on the community front page
+    ADD COLUMN IF NOT EXISTS pinned BOOLEAN NOT NULL DEFAULT false,
+    -- locked posts can't be commented on
+    ADD COLUMN IF NOT EXISTS locked BOOLEAN NOT NULL DEFAULT false,
+    -- removed posts are hidden from lists and search
+    ADD COLUMN IF NOT EXISTS removed TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS posts_community_id ON posts (community_id, pinned DESC, created DESC);
@@ -0,0 +1,14 @@
+DROP TABLE moderation_log;
+
+DROP FUNCTION moderation_log_append_only;
+
+DROP TABLE community_bans;
+
+DROP TABLE reports;
+
+ALTER TABLE comments
+    DROP COLUMN removed;
+
+DROP TYPE MODERATION_ACTION;
+
+DROP TYPE REPORT_REASON;
@@ -0,0 +1,75 @@
+DO $$ BEGIN
+    CREATE TYPE REPORT_REASON AS ENUM ('Spam', 'Harassment', 'HateSpeech', 'Violence', 'Misinformation', 'RuleViolation', 'Other');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DO $$ BEGIN
+    CREATE TYPE MODERATION_ACTION AS ENUM ('Remove', 'Approve', 'Ban', 'Warn', 'Pin', 'Unpin', 'Lock', 'Unlock');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- removed comments are shown as a placeholder so their replies stay threaded
+ALTER TABLE comments
+    ADD COLUMN IF NOT EXISTS removed TIMESTAMP WITH TIME ZONE;
+
+-- a report is about exactly one post, comment or user
+CREATE TABLE IF NOT EXISTS reports (
+    report_id UUID PRIMARY KEY,
+    created TIMESTAMP WITH TIME ZONE NOT NULL,
+    reporter UUID NOT NULL REFERENCES users (user_id) ON DELETE CASCADE,
+    reason REPORT_REASON NOT NULL,
+    details TEXT NOT NULL,
+    CHECK (octet_length(details) <= 1000),
+    -- the community whose moderators handle the report
+    community_id UUID REFERENCES communities (community_id) ON DELETE CASCADE,
+    post_id UUID REFERENCES posts (post_id) ON DELETE CASCADE,
+    comment_id UUID REFERENCES comments (comment_id) ON DELETE CASCADE,
+    user_id UUID REFERENCES users (user_id) ON DELETE CASCADE,
+    CHECK (num_nonnulls(post_id, comment_id, user_id) = 1),
+    resolved TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS reports_open ON reports (community_id, created) WHERE resolved IS NULL;
+
+-- users banned from posting and commenting in a community
+CREATE TABLE IF NOT EXISTS community_bans (
+    community_id UUID NOT NULL REFERENCES communities (community_id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (user_id) ON DELETE CASCADE,
+    created TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (community_id, user_id)
+);
+
+-- every moderation action, never updated or deleted
+CREATE TABLE IF NOT EXISTS moderation_log (
+    entry_id UUID PRIMARY KEY,
+    created TIMESTAMP WITH TIME ZONE NOT NULL,
+    moderator UUID NOT NULL REFERENCES users (user_id),
+    action MODERATION_ACTION NOT NULL,
+    community_id UUID REFERENCES communities (community_id),
+    -- the user whose content or account the action was taken on
+    target_user_id UUID NOT NULL REFERENCES users (user_id),
+    post_id UUID REFERENCES posts (post_id),
+    comment_id UUID REFERENCES comments (comment_id),
+    report_id UUID REFERENCES reports (report_id),
+    reason TEXT NOT NULL,
+    CHECK (octet_length(reason) <= 1000)
+);
+
+CREATE INDEX IF NOT EXISTS moderation_log_target_user_id ON moderation_log (target_user_id, created DESC);
+CREATE INDEX IF NOT EXISTS moderation_log_community_id ON moderation_log (community_id, created DESC);
+
+CREATE OR REPLACE FUNCTION moderation_log_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'the moderation log is append-only';
+END
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER moderation_log_append_only
+    BEFORE UPDATE OR DELETE ON moderation_log
+    FOR EACH ROW EXECUTE FUNCTION moderation_log_append_only();
+
+CREATE OR REPLACE TRIGGER moderation_log_no_truncate
+    BEFORE TRUNCATE ON moderation_log
+    FOR EACH STATEMENT EXECUTE FUNCTION moderation_log_append_only();
@@ -0,0 +1,9 @@
+DROP FUNCTION has_permission;
+
+DROP TABLE user_roles;
+
+DROP TABLE role_permissions;
+
+DROP TABLE roles;
+
+DROP TYPE PERMISSION;
@@ -0,0 +1,45 @@
+DO $$ BEGIN
+    CREATE TYPE PERMISSION AS ENUM ('AccessAdmin', 'ManageRoles', 'ModerateAllCommunities');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+CREATE TABLE IF NOT EXISTS roles (
+    name TEXT PRIMARY KEY,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS role_permissions (
+    role TEXT NOT NULL REFERENCES roles (name) ON DELETE CASCADE,
+    permission PERMISSION NOT NULL,
+    PRIMARY KEY (role, permission)
+);
+
+-- users without a role are regular users
+CREATE TABLE IF NOT EXISTS user_roles (
+    user_id UUID NOT NULL REFERENCES users (user_id) ON DELETE CASCADE,
+    role TEXT NOT NULL REFERENCES roles (name) ON DELETE CASCADE,
+    created TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- null if granted from the command line
+    granted_by UUID REFERENCES users (user_id) ON DELETE SET NULL,
+    PRIMARY KEY (user_id, role)
+);
+
+INSERT INTO roles VALUES
+    ('admin', 'Runs the site.'),
+    ('moderator', 'Moderates every community.')
+ON CONFLICT DO NOTHING;
+
+INSERT INTO role_permissions VALUES
+    ('admin', 'AccessAdmin'),
+    ('admin', 'ManageRoles'),
+    ('admin', 'ModerateAllCommunities'),
+    ('moderator', 'ModerateAllCommunities')
+ON CONFLICT DO NOTHING;
+
+CREATE OR REPLACE FUNCTION has_permission(user_id UUID, permission PERMISSION) RETURNS BOOLEAN AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM user_roles JOIN role_permissions USING (role)
+        WHERE user_roles.user_id = $1 AND role_permissions.permission = $2
+    )
+$$ LANGUAGE SQL STABLE;
@@ -0,0 +1 @@
+-- enum values can't be dropped, they're dropped with their types
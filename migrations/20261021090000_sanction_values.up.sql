@@ -0,0 +1,7 @@
+-- new enum values can't be used in the transaction adding them so they're
+-- added before the sanctions migration
+ALTER TYPE MODERATION_ACTION ADD VALUE IF NOT EXISTS 'Suspend';
+ALTER TYPE MODERATION_ACTION ADD VALUE IF NOT EXISTS 'ShadowBan';
+ALTER TYPE MODERATION_ACTION ADD VALUE IF NOT EXISTS 'Lift';
+
+ALTER TYPE PERMISSION ADD VALUE IF NOT EXISTS 'SanctionUsers';
@@ -0,0 +1,19 @@
+DROP FUNCTION is_shadow_banned;
+
+DELETE FROM role_permissions WHERE permission = 'SanctionUsers';
+
+CREATE TABLE IF NOT EXISTS community_bans (
+    community_id UUID NOT NULL REFERENCES communities (community_id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (user_id) ON DELETE CASCADE,
+    created TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (community_id, user_id)
+);
+
+INSERT INTO community_bans
+SELECT community_id, user_id, min(created) FROM sanctions
+WHERE kind = 'Ban' AND community_id IS NOT NULL AND lifted IS NULL
+GROUP BY community_id, user_id;
+
+DROP TABLE sanctions;
+
+DROP TYPE SANCTION_KIND;
@@ -0,0 +1,65 @@
+DO $$ BEGIN
+    CREATE TYPE SANCTION_KIND AS ENUM ('Suspension', 'Ban', 'ShadowBan');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- suspensions and bans block signing in when site-wide and posting and
+-- commenting when in a community, shadow-bans hide the user's content from
+-- everyone else
+CREATE TABLE IF NOT EXISTS sanctions (
+    sanction_id UUID PRIMARY KEY,
+    created TIMESTAMP WITH TIME ZONE NOT NULL,
+    user_id UUID NOT NULL REFERENCES users (user_id) ON DELETE CASCADE,
+    -- null for site-wide sanctions
+    community_id UUID REFERENCES communities (community_id) ON DELETE CASCADE,
+    kind SANCTION_KIND NOT NULL,
+    -- only suspensions expire
+    expires TIMESTAMP WITH TIME ZONE,
+    CHECK ((kind = 'Suspension') = (expires IS NOT NULL)),
+    reason TEXT NOT NULL,
+    CHECK (octet_length(reason) <= 1000),
+    issued_by UUID REFERENCES users (user_id) ON DELETE SET NULL,
+    lifted TIMESTAMP WITH TIME ZONE,
+    lifted_by UUID REFERENCES users (user_id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS sanctions_user_id ON sanctions (user_id) WHERE lifted IS NULL;
+CREATE INDEX IF NOT EXISTS sanctions_community_id ON sanctions (community_id) WHERE lifted IS NULL;
+
+-- community bans are sanctions now
+DO $$ BEGIN
+    IF to_regclass('community_bans') IS NOT NULL THEN
+        INSERT INTO sanctions (sanction_id, created, user_id, community_id, kind, reason, issued_by)
+        SELECT
+            gen_random_uuid(),
+            community_bans.created,
+            community_bans.user_id,
+            community_bans.community_id,
+            'Ban',
+            COALESCE(log.reason, ''),
+            log.moderator
+        FROM community_bans
+        LEFT JOIN LATERAL (
+            SELECT reason, moderator FROM moderation_log
+            WHERE action = 'Ban'
+                AND target_user_id = community_bans.user_id
+                AND community_id = community_bans.community_id
+            ORDER BY created DESC
+            LIMIT 1
+        ) AS log ON true;
+        DROP TABLE community_bans;
+    END IF;
+END $$;
+
+INSERT INTO role_permissions VALUES ('admin', 'SanctionUsers') ON CONFLICT DO NOTHING;
+
+CREATE OR REPLACE FUNCTION is_shadow_banned(user_id UUID, community_id UUID) RETURNS BOOLEAN AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM sanctions
+        WHERE sanctions.user_id = $1
+            AND (sanctions.community_id IS NULL OR sanctions.community_id = $2)
+            AND kind = 'ShadowBan'
+            AND lifted IS NULL
+    )
+$$ LANGUAGE SQL STABLE;
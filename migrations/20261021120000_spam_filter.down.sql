@@ -0,0 +1,11 @@
+DROP FUNCTION IF EXISTS reputation;
+DROP TABLE IF EXISTS filter_verdicts;
+DROP INDEX IF EXISTS posts_held;
+DROP INDEX IF EXISTS comments_held;
+DROP INDEX IF EXISTS posts_content_hash;
+DROP INDEX IF EXISTS comments_content_hash;
+ALTER TABLE comments DROP COLUMN IF EXISTS held;
+ALTER TABLE comments DROP COLUMN IF EXISTS content_hash;
+ALTER TABLE posts DROP COLUMN IF EXISTS held;
+ALTER TABLE posts DROP COLUMN IF EXISTS content_hash;
+DROP TYPE IF EXISTS FILTER_RULE;
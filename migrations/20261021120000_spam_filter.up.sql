@@ -0,0 +1,44 @@
+DO $$ BEGIN
+    CREATE TYPE FILTER_RULE AS ENUM ('BannedWord', 'BannedPattern', 'LinkLimit', 'Duplicate', 'Reputation');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+-- the hash of the normalized text is used to detect the same content being
+-- posted over and over, held content is only shown to its author until a
+-- moderator approves it
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS content_hash BYTEA;
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS held BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE comments ADD COLUMN IF NOT EXISTS content_hash BYTEA;
+ALTER TABLE comments ADD COLUMN IF NOT EXISTS held BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX IF NOT EXISTS posts_content_hash ON posts (content_hash, created);
+CREATE INDEX IF NOT EXISTS comments_content_hash ON comments (content_hash, created);
+CREATE INDEX IF NOT EXISTS posts_held ON posts (community_id) WHERE held AND removed IS NULL;
+CREATE INDEX IF NOT EXISTS comments_held ON comments (post_id) WHERE held AND removed IS NULL;
+
+-- the verdict of every rule of the spam filter on every post and comment
+CREATE TABLE IF NOT EXISTS filter_verdicts (
+    post_id UUID REFERENCES posts (post_id) ON DELETE CASCADE,
+    comment_id UUID REFERENCES comments (comment_id) ON DELETE CASCADE,
+    CHECK (num_nonnulls(post_id, comment_id) = 1),
+    rule FILTER_RULE NOT NULL,
+    passed BOOLEAN NOT NULL,
+    details TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS filter_verdicts_post_id ON filter_verdicts (post_id);
+CREATE INDEX IF NOT EXISTS filter_verdicts_comment_id ON filter_verdicts (comment_id);
+
+-- content that stayed up counts for a user, removed content and sanctions
+-- count against them
+CREATE OR REPLACE FUNCTION reputation(user_id UUID) RETURNS BIGINT AS $$
+    SELECT
+        (SELECT count(*) FILTER (WHERE removed IS NULL AND NOT held)
+            - 5 * count(*) FILTER (WHERE removed IS NOT NULL)
+        FROM posts WHERE author = $1)
+        + (SELECT count(*) FILTER (WHERE removed IS NULL AND NOT held)
+            - 5 * count(*) FILTER (WHERE removed IS NOT NULL)
+        FROM comments WHERE author = $1)
+        - 10 * (SELECT count(*) FROM sanctions WHERE sanctions.user_id = $1)
+$$ LANGUAGE SQL STABLE;
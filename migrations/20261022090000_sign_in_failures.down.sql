@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS sign_in_lockouts;
+DROP TABLE IF EXISTS sign_in_failures;
@@ -0,0 +1,17 @@
+-- failed sign-in attempts are keyed by the username as it was typed rather
+-- than the user id, so attempts on usernames that don't exist are throttled
+-- the same way and don't reveal whether the username exists
+CREATE TABLE IF NOT EXISTS sign_in_failures (
+    username TEXT NOT NULL,
+    ip_address TEXT NOT NULL,
+    attempted TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS sign_in_failures_username ON sign_in_failures (username, attempted);
+CREATE INDEX IF NOT EXISTS sign_in_failures_ip_address ON sign_in_failures (ip_address, attempted);
+CREATE INDEX IF NOT EXISTS sign_in_failures_attempted ON sign_in_failures (attempted);
+
+CREATE TABLE IF NOT EXISTS sign_in_lockouts (
+    username TEXT PRIMARY KEY,
+    locked_until TIMESTAMP WITH TIME ZONE NOT NULL
+);
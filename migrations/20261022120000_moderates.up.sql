@@ -0,0 +1,9 @@
+-- whether the user moderates the community, or the content and users outside
+-- of communities if community_id is null; the single check of moderation
+-- rights, so the ModerateAllCommunities permission is honored everywhere
+CREATE OR REPLACE FUNCTION moderates(user_id UUID, community_id UUID) RETURNS BOOLEAN AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM moderators
+        WHERE moderators.user_id = $1 AND moderators.community_id = $2
+    ) OR has_permission($1, 'ModerateAllCommunities')
+$$ LANGUAGE SQL STABLE;
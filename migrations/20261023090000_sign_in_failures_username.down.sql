@@ -0,0 +1,2 @@
+DELETE FROM sign_in_failures WHERE username IS NULL;
+ALTER TABLE sign_in_failures ALTER COLUMN username SET NOT NULL;
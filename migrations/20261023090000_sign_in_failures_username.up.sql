@@ -0,0 +1,3 @@
+-- failures that no longer count against the username, because the user signed
+-- in or the username was locked, keep counting against the ip address
+ALTER TABLE sign_in_failures ALTER COLUMN username DROP NOT NULL;
@@ -0,0 +1,388 @@
+//! Tls certificates obtained and renewed automatically from an ACME server,
+//! like Let's Encrypt.
+//!
+//! Challenges are answered over HTTP-01 on port 80, so the domains must point
+//! at the server. The account, the certificate and its private key are stored
+//! in the storage directory and reused across restarts. A self-signed
+//! certificate is served until the first certificate is obtained.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::{
+        config::{self, AcmeConfig, TlsCertificate},
+        tls,
+    },
+    anyhow::{Context, Result},
+    instant_acme::{
+        Account, AccountCredentials, AuthorizationStatus, ChallengeType, Identifier, NewAccount,
+        NewOrder, OrderStatus,
+    },
+    rcgen::{Certificate, CertificateParams, DistinguishedName},
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::BTreeMap,
+        io::Write,
+        os::unix::fs::OpenOptionsExt,
+        path::{Path, PathBuf},
+        sync::Mutex,
+        time::Duration,
+    },
+};
+
+/// The path the ACME server requests the HTTP-01 challenges at, followed by
+/// their token.
+pub const CHALLENGE_PATH: &str = "/.well-known/acme-challenge/";
+
+/// How long to wait before trying again after failing to obtain a certificate.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The longest time between checks of whether the certificate needs renewing.
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The number of times the order is polled while the ACME server validates the
+/// challenges or issues the certificate.
+const POLLS: u32 = 10;
+
+/// The key authorizations of the challenges being validated, by token.
+static CHALLENGES: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Returns the key authorization answering the challenge with the token, if
+/// it's being validated.
+pub fn key_authorization(token: &str) -> Option<String> {
+    CHALLENGES
+        .lock()
+        .expect("poisoned lock")
+        .get(token)
+        .cloned()
+}
+
+/// Returns the stored certificate, or a self-signed one if none is stored yet,
+/// and obtains and renews the certificate in the background, replacing the
+/// served one with [`tls::reload`].
+pub fn init(config: AcmeConfig) -> Result<TlsCertificate> {
+    anyhow::ensure!(!config.domains.is_empty(), "no acme domains configured");
+    std::fs::create_dir_all(&config.storage_directory)
+        .with_context(|| format!("failed to create {:?}", config.storage_directory))?;
+    let certificate = match stored_certificate(&config) {
+        Ok(certificate) => certificate,
+        Err(error) => {
+            tracing::info!(
+                error = %format_args!("{error:#}"),
+                "no stored tls certificate, serving a self-signed one until one is obtained"
+            );
+            self_signed(&config.domains)?
+        }
+    };
+    tokio::spawn(renew(config));
+    Ok(certificate)
+}
+
+/// Obtains a certificate whenever the stored one is missing or due for renewal.
+async fn renew(config: AcmeConfig) {
+    let renew_before = chrono::Duration::days(config.renew_days_before_expiry.get().into());
+    loop {
+        let expiry = stored_certificate(&config)
+            .ok()
+            .and_then(|certificate| tls::not_after(certificate.end_entity_cert().ok()?));
+        if let Some(wait) =
+            expiry.and_then(|expiry| (expiry - renew_before - chrono::Utc::now()).to_std().ok())
+        {
+            tokio::time::sleep(wait.min(CHECK_INTERVAL)).await;
+            continue;
+        }
+
+        tracing::info!(domains = ?config.domains, "obtaining tls certificate");
+        match obtain(&config).await {
+            Ok(certificate) => {
+                let expiry = certificate.end_entity_cert().ok().and_then(tls::not_after);
+                tls::reload(certificate);
+                tracing::info!(?expiry, "obtained tls certificate");
+            }
+            Err(error) => {
+                tracing::error!(
+                    error = %format_args!("{error:#}"),
+                    "failed to obtain tls certificate, retrying in {} minutes",
+                    RETRY_INTERVAL.as_secs() / 60
+                );
+                tokio::time::sleep(RETRY_INTERVAL).await;
+            }
+        }
+    }
+}
+
+/// Returns the paths of the certificate and its private key for the domains.
+/// Changing the domains obtains a new certificate.
+fn certificate_paths(config: &AcmeConfig) -> (PathBuf, PathBuf) {
+    let name = config.domains.join(",");
+    (
+        config.storage_directory.join(format!("{name}.pem")),
+        config.storage_directory.join(format!("{name}.key")),
+    )
+}
+
+/// Returns the stored certificate. A private key that doesn't match it is
+/// rejected, so a certificate whose files were only partly replaced is
+/// obtained again.
+fn stored_certificate(config: &AcmeConfig) -> Result<TlsCertificate> {
+    let (cert, cert_key) = certificate_paths(config);
+    let certificate = config::read_tls_certificate(&cert, &cert_key)?;
+    anyhow::ensure!(
+        config::key_matches(&certificate) != Some(false),
+        "the stored private key doesn't match the certificate"
+    );
+    Ok(certificate)
+}
+
+fn self_signed(domains: &[String]) -> Result<TlsCertificate> {
+    let certificate = rcgen::generate_simple_self_signed(domains.to_owned())?;
+    let key = rustls::PrivateKey(certificate.serialize_private_key_der());
+    let key = rustls::sign::any_supported_type(&key)?;
+    Ok(TlsCertificate::new(
+        vec![rustls::Certificate(certificate.serialize_der()?)],
+        key,
+    ))
+}
+
+/// The account at an ACME server, as it's stored.
+#[derive(Serialize, Deserialize)]
+struct StoredAccount {
+    directory_url: String,
+    credentials: serde_json::Value,
+}
+
+/// Returns the stored account at the ACME server, creating one if there's
+/// none.
+async fn account(config: &AcmeConfig) -> Result<Account> {
+    let path = config.storage_directory.join("account.json");
+    if let Ok(stored) = std::fs::read_to_string(&path) {
+        let stored: StoredAccount =
+            serde_json::from_str(&stored).with_context(|| format!("invalid {path:?}"))?;
+        if stored.directory_url == config.directory_url {
+            let credentials: AccountCredentials<'_> = serde_json::from_value(stored.credentials)
+                .with_context(|| format!("invalid {path:?}"))?;
+            return Account::from_credentials(credentials).context("invalid acme account");
+        }
+    }
+
+    let contact: Vec<&str> = config.contact.iter().map(String::as_str).collect();
+    let account = Account::create(
+        &NewAccount {
+            contact: &contact,
+            terms_of_service_agreed: true,
+            only_return_existing: false,
+        },
+        &config.directory_url,
+        None,
+    )
+    .await
+    .context("failed to create acme account")?;
+    let stored = StoredAccount {
+        directory_url: config.directory_url.clone(),
+        credentials: serde_json::to_value(account.credentials())?,
+    };
+    write_private(&path, serde_json::to_string(&stored)?.as_bytes())?;
+    tracing::info!(directory_url = config.directory_url, "created acme account");
+    Ok(account)
+}
+
+/// Orders a certificate for the domains and stores it.
+async fn obtain(config: &AcmeConfig) -> Result<TlsCertificate> {
+    let account = account(config).await?;
+    let identifiers: Vec<_> = config
+        .domains
+        .iter()
+        .map(|domain| Identifier::Dns(domain.clone()))
+        .collect();
+    let mut order = account
+        .new_order(&NewOrder {
+            identifiers: &identifiers,
+        })
+        .await
+        .context("failed to create order")?;
+
+    let mut tokens = Vec::new();
+    let validated = async {
+        let mut challenges = Vec::new();
+        for authorization in order.authorizations().await? {
+            let Identifier::Dns(domain) = &authorization.identifier;
+            match authorization.status {
+                AuthorizationStatus::Pending => (),
+                AuthorizationStatus::Valid => continue,
+                status => anyhow::bail!("the authorization of {domain} is {status:?}"),
+            }
+            let challenge = authorization
+                .challenges
+                .iter()
+                .find(|challenge| challenge.r#type == ChallengeType::Http01)
+                .with_context(|| format!("no http-01 challenge offered for {domain}"))?;
+            CHALLENGES.lock().expect("poisoned lock").insert(
+                challenge.token.clone(),
+                order.key_authorization(challenge).as_str().to_owned(),
+            );
+            tokens.push(challenge.token.clone());
+            challenges.push(challenge.url.clone());
+        }
+        for challenge in challenges {
+            order.set_challenge_ready(&challenge).await?;
+        }
+
+        let mut delay = Duration::from_millis(250);
+        for _ in 0..POLLS {
+            tokio::time::sleep(delay).await;
+            let state = order.refresh().await?;
+            match state.status {
+                OrderStatus::Ready => return Ok(()),
+                OrderStatus::Invalid => {
+                    anyhow::bail!("the order is invalid: {:?}", state.error)
+                }
+                _ => delay = (delay * 2).min(Duration::from_secs(10)),
+            }
+        }
+        anyhow::bail!("the challenges weren't validated in time")
+    }
+    .await;
+    {
+        let mut challenges = CHALLENGES.lock().expect("poisoned lock");
+        for token in tokens {
+            challenges.remove(&token);
+        }
+    }
+    validated?;
+
+    let mut params = CertificateParams::new(config.domains.clone());
+    params.distinguished_name = DistinguishedName::new();
+    let key = Certificate::from_params(params)?;
+    order
+        .finalize(&key.serialize_request_der()?)
+        .await
+        .context("failed to finalize order")?;
+    let mut chain = None;
+    for _ in 0..POLLS {
+        chain = order.certificate().await?;
+        if chain.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    let chain = chain.context("the certificate wasn't issued in time")?;
+
+    // both files are written before either is replaced, so a failed write
+    // leaves the stored pair intact
+    let (cert, cert_key) = certificate_paths(config);
+    let temporary_key = write_temporary(&cert_key, key.serialize_private_key_pem().as_bytes())?;
+    let temporary_cert = write_temporary(&cert, chain.as_bytes())?;
+    replace(&temporary_key, &cert_key)?;
+    replace(&temporary_cert, &cert)?;
+    config::read_tls_certificate(&cert, &cert_key)
+}
+
+/// Writes a file only the user running the server can read. The contents are
+/// written to a temporary file next to it first and moved over the file, so a
+/// crash never leaves it half written.
+fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    replace(&write_temporary(path, contents)?, path)
+}
+
+/// Writes the contents to a temporary file next to the path only the user
+/// running the server can read, and returns its path.
+fn write_temporary(path: &Path, contents: &[u8]) -> Result<PathBuf> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&temporary)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .with_context(|| format!("failed to write {temporary:?}"))?;
+    Ok(temporary)
+}
+
+/// Moves the temporary file over the path.
+fn replace(temporary: &Path, path: &Path) -> Result<()> {
+    std::fs::rename(temporary, path).with_context(|| format!("failed to write {path:?}"))
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, std::num::NonZeroU32};
+
+    fn config(storage_directory: PathBuf) -> AcmeConfig {
+        AcmeConfig {
+            directory_url: std::env::var("PEBBLE_DIRECTORY_URL")
+                .unwrap_or_else(|_| "https://localhost:14000/dir".to_owned()),
+            domains: vec!["marzichat.localhost".to_owned()],
+            contact: vec![],
+            storage_directory,
+            renew_days_before_expiry: NonZeroU32::new(30).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_self_signed() {
+        let certificate = self_signed(&["marzichat.localhost".to_owned()]).unwrap();
+        let expiry = tls::not_after(certificate.end_entity_cert().unwrap()).unwrap();
+        assert!(expiry > chrono::Utc::now());
+    }
+
+    #[test]
+    fn test_write_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory =
+            std::env::temp_dir().join(format!("marzichat-write-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("cert.pem");
+        write_private(&path, b"old").unwrap();
+        write_private(&path, b"new").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"new");
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+        assert!(!directory.join("cert.pem.tmp").exists());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_stored_certificate() {
+        let testdata = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/config/testdata");
+        let directory =
+            std::env::temp_dir().join(format!("marzichat-stored-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let config = config(directory.clone());
+        let (cert, cert_key) = certificate_paths(&config);
+        std::fs::copy(testdata.join("rsa.pem"), cert).unwrap();
+        std::fs::copy(testdata.join("rsa.key"), &cert_key).unwrap();
+        assert!(stored_certificate(&config).is_ok());
+        std::fs::copy(testdata.join("ec.key"), cert_key).unwrap();
+        assert!(stored_certificate(&config).is_err());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    /// Obtains a certificate from a local Pebble instance. Run Pebble with
+    /// `PEBBLE_VA_ALWAYS_VALID=1`, so the challenges don't have to be
+    /// reachable, and this test with `SSL_CERT_FILE` set to Pebble's
+    /// `pebble.minica.pem`.
+    #[tokio::test]
+    #[ignore = "requires a local Pebble instance"]
+    async fn test_pebble() {
+        let directory = std::env::temp_dir().join(format!("marzichat-acme-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let config = config(directory.clone());
+
+        let certificate = obtain(&config).await.unwrap();
+        let expiry = tls::not_after(certificate.end_entity_cert().unwrap()).unwrap();
+        assert!(expiry > chrono::Utc::now());
+        assert!(stored_certificate(&config).is_ok());
+        assert!(CHALLENGES.lock().unwrap().is_empty());
+        // the stored account is reused
+        account(&config).await.unwrap();
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,9 +1,24 @@
 //! Client ip address parser.
+//!
+//! Behind a load balancer or reverse proxy the peer of every connection is the
+//! proxy, so the client ip address is taken from the `Forwarded` or
+//! `X-Forwarded-For` header instead, whichever the proxies are configured to
+//! use. The header is only read when the peer is a trusted proxy, and only the
+//! addresses appended by trusted proxies are skipped so clients can't spoof
+//! their address by sending the headers themselves.
 #![cfg(feature = "ssr")]
 
 use {
-    actix_web::HttpRequest,
-    std::net::{AddrParseError, IpAddr},
+    crate::config::ForwardedHeader,
+    actix_web::{
+        http::header::{HeaderMap, HeaderName, FORWARDED, X_FORWARDED_FOR},
+        HttpRequest,
+    },
+    ipnet::IpNet,
+    std::{
+        net::{AddrParseError, IpAddr, SocketAddr},
+        sync::OnceLock,
+    },
 };
 
 /// The error type for the client ip address parser.
@@ -18,11 +33,327 @@ pub enum Error {
     InvalidClientIpAddress(#[from] AddrParseError),
 }
 
+/// The proxies trusted to forward the client ip address, and the header they
+/// forward it in.
+struct TrustedProxies {
+    networks: Vec<IpNet>,
+    header: ForwardedHeader,
+}
+
+impl TrustedProxies {
+    fn contains(&self, address: &IpAddr) -> bool {
+        self.networks.iter().any(|net| net.contains(address))
+    }
+}
+
+static TRUSTED_PROXIES: OnceLock<TrustedProxies> = OnceLock::new();
+
+/// Used until the trusted proxies are initialized.
+static NO_PROXIES: TrustedProxies = TrustedProxies {
+    networks: Vec::new(),
+    header: ForwardedHeader::XForwardedFor,
+};
+
+/// Sets the networks of the proxies trusted to forward the client ip address
+/// and the header they forward it in.
+pub fn init(networks: Vec<IpNet>, header: ForwardedHeader) {
+    if TRUSTED_PROXIES
+        .set(TrustedProxies { networks, header })
+        .is_err()
+    {
+        panic!("trusted proxies already initialized");
+    }
+}
+
 /// Parse the client ip address from the request.
 pub fn parse(request: &HttpRequest) -> Result<IpAddr, Error> {
-    Ok(request
-        .connection_info()
-        .peer_addr()
-        .ok_or(Error::MissingClientIpAddress)?
-        .parse()?)
+    let trusted_proxies = TRUSTED_PROXIES.get().unwrap_or(&NO_PROXIES);
+    match request.peer_addr() {
+        Some(peer) => client(peer.ip(), request.headers(), trusted_proxies),
+        // connections over a unix domain socket have no peer address, they
+        // come from a reverse proxy on the same machine which is trusted
+        None => forwarded_client(request.headers(), trusted_proxies)?
+            .ok_or(Error::MissingClientIpAddress),
+    }
+}
+
+/// Returns true if the request came from a trusted proxy, so the headers the
+/// proxy sets can be trusted.
+pub fn from_trusted_proxy(request: &HttpRequest) -> bool {
+    let trusted_proxies = TRUSTED_PROXIES.get().unwrap_or(&NO_PROXIES);
+    is_trusted_peer(request.peer_addr().map(|peer| peer.ip()), trusted_proxies)
+}
+
+/// Returns true if the peer is a trusted proxy. Like in [`parse`], peers over
+/// a unix domain socket, which have no address, are trusted.
+fn is_trusted_peer(peer: Option<IpAddr>, trusted_proxies: &TrustedProxies) -> bool {
+    peer.map_or(true, |peer| trusted_proxies.contains(&peer))
+}
+
+/// Returns the client ip address of a request from the peer.
+///
+/// If the peer is a trusted proxy the client is taken from the forwarded
+/// addresses, see [`forwarded_client`], or is the peer if there are none.
+fn client(
+    peer: IpAddr,
+    headers: &HeaderMap,
+    trusted_proxies: &TrustedProxies,
+) -> Result<IpAddr, Error> {
+    if !trusted_proxies.contains(&peer) {
+        return Ok(peer);
+    }
+    Ok(forwarded_client(headers, trusted_proxies)?.unwrap_or(peer))
+}
+
+/// Returns the client ip address forwarded by a trusted proxy.
+///
+/// The forwarded addresses are walked from the last to the first and the first
+/// address that isn't a trusted proxy is the client. If all of them are trusted
+/// proxies, the first one is the client. A trusted proxy may hide the address
+/// it forwards for, see [`is_hidden`], and the walk stops there with the last
+/// trusted proxy as the client. Only the configured header is read.
+fn forwarded_client(
+    headers: &HeaderMap,
+    trusted_proxies: &TrustedProxies,
+) -> Result<Option<IpAddr>, Error> {
+    let forwarded = match trusted_proxies.header {
+        ForwardedHeader::XForwardedFor => header_values(headers, X_FORWARDED_FOR),
+        ForwardedHeader::Forwarded => forwarded_for(headers),
+    };
+
+    let mut client = None;
+    for address in forwarded.iter().rev() {
+        if is_hidden(address) {
+            break;
+        }
+        let address = parse_address(address)?;
+        client = Some(address);
+        if !trusted_proxies.contains(&address) {
+            break;
+        }
+    }
+    Ok(client)
+}
+
+/// Returns the comma separated values of all the header's lines in order.
+fn header_values(headers: &HeaderMap, name: HeaderName) -> Vec<String> {
+    headers
+        .get_all(name)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|value| value.trim().to_owned())
+        .collect()
+}
+
+/// Returns the `for` parameters of the `Forwarded` header's elements in order.
+fn forwarded_for(headers: &HeaderMap) -> Vec<String> {
+    header_values(headers, FORWARDED)
+        .iter()
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                key.trim()
+                    .eq_ignore_ascii_case("for")
+                    .then(|| value.trim().trim_matches('"').to_owned())
+            })
+        })
+        .collect()
+}
+
+/// Returns true if the forwarded node is `unknown` or an obfuscated identifier
+/// like `_hidden`, which RFC 7239 allows in place of an address. Ipv6
+/// addresses with a port are in brackets, so the node ends at the first colon.
+fn is_hidden(address: &str) -> bool {
+    let node = address.split(':').next().unwrap_or_default();
+    node.eq_ignore_ascii_case("unknown") || node.starts_with('_')
+}
+
+/// Parses a forwarded address which may include a port, with ipv6 addresses
+/// in brackets if so.
+fn parse_address(address: &str) -> Result<IpAddr, Error> {
+    address
+        .parse::<IpAddr>()
+        .or_else(|error| {
+            address
+                .parse::<SocketAddr>()
+                .map(|address| address.ip())
+                .map_err(|_| error)
+        })
+        .or_else(|error| {
+            address
+                .strip_prefix('[')
+                .and_then(|address| address.strip_suffix(']'))
+                .ok_or(error)?
+                .parse()
+        })
+        .map_err(Error::from)
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, actix_web::http::header::HeaderValue};
+
+    fn header_map(lines: &[(HeaderName, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in lines {
+            headers.append(name.clone(), HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().unwrap()
+    }
+
+    fn trusted_with(header: ForwardedHeader) -> TrustedProxies {
+        TrustedProxies {
+            networks: vec!["10.0.0.0/8".parse().unwrap(), "fd00::/8".parse().unwrap()],
+            header,
+        }
+    }
+
+    fn trusted() -> TrustedProxies {
+        trusted_with(ForwardedHeader::XForwardedFor)
+    }
+
+    #[test]
+    fn test_untrusted_peer() {
+        let headers = header_map(&[(X_FORWARDED_FOR, "1.1.1.1"), (FORWARDED, "for=1.1.1.1")]);
+        assert_eq!(
+            client(ip("203.0.113.7"), &headers, &trusted()).unwrap(),
+            ip("203.0.113.7")
+        );
+        assert_eq!(
+            client(ip("10.0.0.1"), &headers, &NO_PROXIES).unwrap(),
+            ip("10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn test_is_trusted_peer() {
+        assert!(is_trusted_peer(Some(ip("10.0.0.1")), &trusted()));
+        assert!(!is_trusted_peer(Some(ip("203.0.113.7")), &trusted()));
+        assert!(!is_trusted_peer(Some(ip("10.0.0.1")), &NO_PROXIES));
+        assert!(is_trusted_peer(None, &NO_PROXIES));
+    }
+
+    #[test]
+    fn test_x_forwarded_for() {
+        let headers = header_map(&[(X_FORWARDED_FOR, "203.0.113.7, 10.0.0.2")]);
+        assert_eq!(
+            client(ip("10.0.0.1"), &headers, &trusted()).unwrap(),
+            ip("203.0.113.7")
+        );
+        assert_eq!(
+            client(ip("10.0.0.1"), &HeaderMap::new(), &trusted()).unwrap(),
+            ip("10.0.0.1")
+        );
+
+        let headers = header_map(&[(X_FORWARDED_FOR, "10.0.0.3, 10.0.0.2")]);
+        assert_eq!(
+            client(ip("10.0.0.1"), &headers, &trusted()).unwrap(),
+            ip("10.0.0.3")
+        );
+
+        // proxies connecting over a unix domain socket have no address
+        let headers = header_map(&[(X_FORWARDED_FOR, "203.0.113.7")]);
+        assert_eq!(
+            forwarded_client(&headers, &NO_PROXIES).unwrap(),
+            Some(ip("203.0.113.7"))
+        );
+        assert_eq!(
+            forwarded_client(&HeaderMap::new(), &NO_PROXIES).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_forwarded() {
+        let headers = header_map(&[
+            (FORWARDED, r#"for="[2001:db8:cafe::17]:4711";proto=https"#),
+            (FORWARDED, "For=192.0.2.60:8080;by=10.0.0.2, for=10.0.0.2"),
+            (X_FORWARDED_FOR, "198.51.100.1"),
+        ]);
+        assert_eq!(
+            client(
+                ip("fd00::1"),
+                &headers,
+                &trusted_with(ForwardedHeader::Forwarded)
+            )
+            .unwrap(),
+            ip("192.0.2.60")
+        );
+        assert_eq!(
+            parse_address("[2001:db8:cafe::17]").unwrap(),
+            ip("2001:db8:cafe::17")
+        );
+
+        // a trusted proxy hiding the address it forwards for is the client
+        let forwarded = trusted_with(ForwardedHeader::Forwarded);
+        let headers = header_map(&[(FORWARDED, "for=192.0.2.60, for=unknown, for=10.0.0.2")]);
+        assert_eq!(
+            client(ip("10.0.0.1"), &headers, &forwarded).unwrap(),
+            ip("10.0.0.2")
+        );
+        let headers = header_map(&[(FORWARDED, r#"for="_hidden:_port""#)]);
+        assert_eq!(
+            client(ip("10.0.0.1"), &headers, &forwarded).unwrap(),
+            ip("10.0.0.1")
+        );
+        // an untrusted address before it is still the client
+        let headers = header_map(&[(FORWARDED, "for=_hidden, for=203.0.113.7")]);
+        assert_eq!(
+            client(ip("10.0.0.1"), &headers, &forwarded).unwrap(),
+            ip("203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn test_spoofing() {
+        // the client sends its own header which the proxy appends to
+        let headers = header_map(&[(X_FORWARDED_FOR, "10.0.0.5, 1.1.1.1, 203.0.113.7")]);
+        assert_eq!(
+            client(ip("10.0.0.1"), &headers, &trusted()).unwrap(),
+            ip("203.0.113.7")
+        );
+
+        // garbage sent by the client is never reached
+        let headers = header_map(&[(X_FORWARDED_FOR, "not an ip, 203.0.113.7")]);
+        assert_eq!(
+            client(ip("10.0.0.1"), &headers, &trusted()).unwrap(),
+            ip("203.0.113.7")
+        );
+
+        // a client connecting directly can't claim to be someone else
+        let headers = header_map(&[(FORWARDED, "for=10.0.0.5")]);
+        assert_eq!(
+            client(ip("203.0.113.7"), &headers, &trusted()).unwrap(),
+            ip("203.0.113.7")
+        );
+
+        // a proxy appending only X-Forwarded-For passes a Forwarded header sent
+        // by the client on untouched
+        let headers = header_map(&[(FORWARDED, "for=1.2.3.4"), (X_FORWARDED_FOR, "203.0.113.7")]);
+        assert_eq!(
+            client(ip("10.0.0.1"), &headers, &trusted()).unwrap(),
+            ip("203.0.113.7")
+        );
+        // and the other way around
+        let headers = header_map(&[(X_FORWARDED_FOR, "1.2.3.4"), (FORWARDED, "for=203.0.113.7")]);
+        assert_eq!(
+            client(
+                ip("10.0.0.1"),
+                &headers,
+                &trusted_with(ForwardedHeader::Forwarded)
+            )
+            .unwrap(),
+            ip("203.0.113.7")
+        );
+
+        let headers = header_map(&[(X_FORWARDED_FOR, "not an ip")]);
+        assert!(matches!(
+            client(ip("10.0.0.1"), &headers, &trusted()),
+            Err(Error::InvalidClientIpAddress(_))
+        ));
+    }
 }
@@ -1,5 +1,7 @@
-use leptos::{Scope, Serializable};
-use serde::{Deserialize, Serialize};
+use {
+    leptos::*,
+    serde::{Deserialize, Serialize},
+};
 
 pub fn story(path: &str) -> String {
     format!("https://node-hnapi.herokuapp.com/{path}")
@@ -29,7 +31,7 @@ where
 
     // abort in-flight requests if the Scope is disposed
     // i.e., if we've navigated away from this page
-    leptos::on_cleanup(cx, move || {
+    on_cleanup(cx, move || {
         if let Some(abort_controller) = abort_controller {
             abort_controller.abort()
         }
@@ -52,6 +54,42 @@ where
     T::de(&json).map_err(|e| log::error!("{e}")).ok()
 }
 
+/// Returns the story with its comments, their html sanitized on the server so
+/// the sanitizer isn't shipped to browsers.
+#[tracing::instrument(skip_all)]
+#[server(GetStory, "/api")]
+pub async fn get_story(cx: Scope, id: usize) -> Result<Option<Story>, ServerFnError> {
+    fn sanitize(comments: &mut [Comment]) {
+        for comment in comments {
+            comment.content = comment.content.as_deref().map(crate::markdown::sanitize);
+            sanitize(&mut comment.comments);
+        }
+    }
+
+    let mut story = fetch_api::<Story>(cx, &story(&format!("item/{id}"))).await;
+    if let Some(comments) = story.as_mut().and_then(|story| story.comments.as_mut()) {
+        sanitize(comments);
+    }
+    Ok(story)
+}
+
+/// Returns the user with the html of their about sanitized. Ids that can't
+/// be usernames aren't requested, so they can't reach other endpoints.
+#[tracing::instrument(skip_all)]
+#[server(GetUser, "/api")]
+pub async fn get_user(cx: Scope, id: String) -> Result<Option<User>, ServerFnError> {
+    let is_username =
+        |character: char| character.is_ascii_alphanumeric() || character == '_' || character == '-';
+    if !id.chars().all(is_username) {
+        return Ok(None);
+    }
+    let mut user = fetch_api::<User>(cx, &user(&id)).await;
+    if let Some(user) = &mut user {
+        user.about = user.about.as_deref().map(crate::markdown::sanitize);
+    }
+    Ok(user)
+}
+
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 pub struct Story {
     pub id: usize,
@@ -0,0 +1,68 @@
+//! `marzichat config check`.
+
+/// Validate the config and the connection to postgres.
+#[cfg(feature = "ssr")]
+#[fncmd::fncmd]
+#[tokio::main(flavor = "current_thread")]
+pub async fn main(
+    /// Config file path if not using CONFIG env var.
+    #[opt(short, long)]
+    config: Option<std::path::PathBuf>,
+
+    /// Skip checking the connection to postgres.
+    #[opt]
+    offline: bool,
+) -> anyhow::Result<()> {
+    use {
+        anyhow::Context,
+        marzichat::config::{self, ParseError, Problem, Severity},
+        std::time::Duration,
+    };
+
+    let path = config::path(config)?;
+    let text = std::fs::read_to_string(&path).context("failed to read config")?;
+    let config = match config::parse(&text) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("error: {path:?}: {error:#}");
+            if let Some(snippet) = error
+                .downcast_ref::<ParseError>()
+                .and_then(|error| error.snippet(&text))
+            {
+                eprintln!("{snippet}");
+            }
+            anyhow::bail!("invalid config");
+        }
+    };
+
+    let mut problems = config::check(&config, chrono::Utc::now());
+    if !offline {
+        let connect = sqlx::postgres::PgPool::connect(&config.postgres.url);
+        let error = match tokio::time::timeout(Duration::from_secs(5), connect).await {
+            Ok(Ok(_)) => None,
+            Ok(Err(error)) => Some(error.to_string()),
+            Err(_) => Some("timed out".to_owned()),
+        };
+        if let Some(error) = error {
+            problems.push(Problem {
+                severity: Severity::Error,
+                setting: "postgres.url".to_owned(),
+                message: format!("postgres unreachable: {error}"),
+            });
+        }
+    }
+
+    for problem in &problems {
+        eprintln!("{problem}");
+    }
+    let errors = problems
+        .iter()
+        .filter(|problem| problem.severity == Severity::Error)
+        .count();
+    anyhow::ensure!(errors == 0, "the config has {errors} error(s)");
+    println!("{path:?} is valid");
+    Ok(())
+}
+
+#[cfg(not(feature = "ssr"))]
+fn main() {}
@@ -0,0 +1,11 @@
+//! `marzichat config template`.
+
+/// Print a documented config with the defaults for production.
+#[cfg(feature = "ssr")]
+#[fncmd::fncmd]
+pub fn main() {
+    print!("{}", marzichat::config::TEMPLATE);
+}
+
+#[cfg(not(feature = "ssr"))]
+fn main() {}
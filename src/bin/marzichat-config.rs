@@ -0,0 +1,11 @@
+//! `marzichat config`, with the subcommands `check` and `template`.
+
+/// Check the config or print a template of it.
+#[cfg(feature = "ssr")]
+#[fncmd::fncmd]
+pub fn main() -> anyhow::Result<()> {
+    anyhow::bail!("expected a subcommand, see --help")
+}
+
+#[cfg(not(feature = "ssr"))]
+fn main() {}
@@ -0,0 +1,38 @@
+//! `marzichat passwords import`.
+
+/// Import the Have I Been Pwned password list ordered by hash for lookups.
+#[cfg(feature = "ssr")]
+#[fncmd::fncmd]
+pub fn main(
+    /// The downloaded list of SHA-1 hashes.
+    list: std::path::PathBuf,
+
+    /// The file to write, set as `breached_hashes` in the config.
+    output: std::path::PathBuf,
+) -> anyhow::Result<()> {
+    use {
+        anyhow::Context,
+        std::{
+            fs::File,
+            io::{BufReader, BufWriter},
+        },
+    };
+
+    let reader = File::open(&list).with_context(|| format!("failed to open {list:?}"))?;
+    // written next to the output and renamed, so a running server never reads
+    // a partial list
+    let partial = output.with_extension("partial");
+    let writer = File::create(&partial).with_context(|| format!("failed to create {partial:?}"))?;
+    let count = marzichat::breached_passwords::import(
+        BufReader::new(reader),
+        BufWriter::new(writer),
+    )
+    .with_context(|| format!("failed to import {list:?}"))?;
+    std::fs::rename(&partial, &output)
+        .with_context(|| format!("failed to rename {partial:?} to {output:?}"))?;
+    println!("imported {count} hashes into {output:?}");
+    Ok(())
+}
+
+#[cfg(not(feature = "ssr"))]
+fn main() {}
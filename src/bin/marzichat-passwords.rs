@@ -0,0 +1,11 @@
+//! `marzichat passwords`, with the subcommand `import`.
+
+/// Manage the breached password list.
+#[cfg(feature = "ssr")]
+#[fncmd::fncmd]
+pub fn main() -> anyhow::Result<()> {
+    anyhow::bail!("expected a subcommand, see --help")
+}
+
+#[cfg(not(feature = "ssr"))]
+fn main() {}
@@ -0,0 +1,193 @@
+//! Breached password list.
+//!
+//! Passwords that appeared in data breaches are the first ones tried when
+//! guessing passwords, so they aren't allowed. The Have I Been Pwned password
+//! list has the hex SHA-1 hash of a password per line, optionally followed by a
+//! colon and the number of times it was seen, and is too large to load into
+//! memory. It's imported once with `marzichat passwords import` into a file of
+//! the sorted 20 byte hashes, which passwords are looked up in with a binary
+//! search reading a few hashes. Passwords are never looked up with a third
+//! party, and only their hashes are compared.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::config::PasswordsConfig,
+    anyhow::{Context, Result},
+    sha1::{Digest, Sha1},
+    std::{
+        fs::File,
+        io::{BufRead, Write},
+        os::unix::fs::FileExt,
+        sync::OnceLock,
+    },
+};
+
+type Hash = [u8; 20];
+
+/// The bytes of a hash in the imported list.
+const HASH_BYTES: u64 = 20;
+
+/// The imported list of the sorted hashes of the breached passwords, or None
+/// if no list is configured.
+static HASHES: OnceLock<Option<Hashes>> = OnceLock::new();
+
+/// A file of sorted hashes.
+struct Hashes {
+    file: File,
+    len: u64,
+}
+
+impl Hashes {
+    fn open(file: File) -> Result<Self> {
+        let bytes = file.metadata()?.len();
+        anyhow::ensure!(
+            bytes % HASH_BYTES == 0,
+            "not a list imported with `marzichat passwords import`"
+        );
+        Ok(Self {
+            file,
+            len: bytes / HASH_BYTES,
+        })
+    }
+
+    fn hash(&self, index: u64) -> std::io::Result<Hash> {
+        let mut hash = Hash::default();
+        self.file.read_exact_at(&mut hash, index * HASH_BYTES)?;
+        Ok(hash)
+    }
+
+    /// Binary searches the hash, reading about 30 hashes of the full list,
+    /// mostly from the page cache.
+    fn contains(&self, hash: &Hash) -> std::io::Result<bool> {
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match self.hash(middle)?.cmp(hash) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return Ok(true),
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Opens the imported breached password list, if one is configured.
+pub fn init(config: PasswordsConfig) -> Result<()> {
+    let hashes = match config.breached_hashes {
+        Some(path) => {
+            let file =
+                File::open(&path).with_context(|| format!("failed to open {}", path.display()))?;
+            let hashes =
+                Hashes::open(file).with_context(|| format!("invalid {}", path.display()))?;
+            tracing::info!(hashes = hashes.len, "opened breached passwords");
+            Some(hashes)
+        }
+        None => None,
+    };
+    if HASHES.set(hashes).is_err() {
+        panic!("breached passwords already initialized");
+    }
+    Ok(())
+}
+
+/// Returns true if the password is in the breached password list. Passwords
+/// count as not breached if the list can't be read.
+pub fn contains(password: &str) -> bool {
+    let Some(Some(hashes)) = HASHES.get() else {
+        return false;
+    };
+    hashes
+        .contains(&Sha1::digest(password).into())
+        .unwrap_or_else(|error| {
+            tracing::error!(?error, "failed to read breached passwords");
+            false
+        })
+}
+
+/// Imports a password list in the format of Have I Been Pwned, sorted by hash,
+/// writing the hashes for lookups. Returns the number of hashes written.
+pub fn import(reader: impl BufRead, mut writer: impl Write) -> Result<u64> {
+    let mut previous: Option<Hash> = None;
+    let mut count = 0;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let hash = parse(&line).with_context(|| format!("invalid line {}", index + 1))?;
+        match previous {
+            Some(previous) if previous == hash => continue,
+            Some(previous) if previous > hash => anyhow::bail!(
+                "line {} isn't sorted, download the list ordered by hash",
+                index + 1
+            ),
+            _ => (),
+        }
+        writer.write_all(&hash)?;
+        previous = Some(hash);
+        count += 1;
+    }
+    writer.flush()?;
+    Ok(count)
+}
+
+/// Parses the hash of a line of a password list.
+fn parse(line: &str) -> Result<Hash> {
+    let hex = line.split(':').next().unwrap_or_default().trim();
+    anyhow::ensure!(
+        hex.len() == 40 && hex.is_ascii(),
+        "expected a 40 character hex SHA-1 hash"
+    );
+    let mut hash = Hash::default();
+    for (byte, index) in hash.iter_mut().zip((0..hex.len()).step_by(2)) {
+        *byte = u8::from_str_radix(&hex[index..index + 2], 16)?;
+    }
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_import() {
+        // the SHA-1 hashes of "password" and "123456"
+        let list = "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\n\
+                    5baa61e4c9b93f3f0682250b6cf8331b7ee68fd8:1\n\
+                    \n\
+                    7C4A8D09CA3762AF61E59520943DC26494F8941B:37359195\n";
+        let path = std::env::temp_dir().join(format!("marzichat-breached-{}", std::process::id()));
+        let count = import(list.as_bytes(), File::create(&path).unwrap()).unwrap();
+        assert_eq!(count, 2);
+
+        let hashes = Hashes::open(File::open(&path).unwrap()).unwrap();
+        assert_eq!(hashes.len, 2);
+        for (password, breached) in [
+            ("password", true),
+            ("123456", true),
+            ("correct horse", false),
+        ] {
+            assert_eq!(
+                hashes.contains(&Sha1::digest(password).into()).unwrap(),
+                breached
+            );
+        }
+        std::fs::remove_file(path).unwrap();
+
+        assert!(import("5BAA61E4C9B93F3F:1".as_bytes(), Vec::new()).is_err());
+        assert!(import(
+            "ZZAA61E4C9B93F3F0682250B6CF8331B7EE68FD8".as_bytes(),
+            Vec::new()
+        )
+        .is_err());
+        // unsorted lists are rejected
+        assert!(import(
+            "7c4a8d09ca3762af61e59520943dc26494f8941b\n\
+             5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8\n"
+                .as_bytes(),
+            Vec::new()
+        )
+        .is_err());
+    }
+}
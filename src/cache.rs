@@ -0,0 +1,196 @@
+//! Cache of hot data in keydb.
+//!
+//! Session lookups and the front page as seen by signed out readers are read
+//! through the cache. Writes that change them invalidate the cached copies
+//! explicitly, and every entry also expires after a configured time in case an
+//! invalidation is missed. Without a cache config, or while keydb is
+//! unavailable, everything is read from postgres.
+//!
+//! Deleted sessions are replaced with a tombstone rather than removed, and
+//! sessions are only cached if their key is free, so a lookup that read a
+//! session from postgres just before it was deleted can't cache it again.
+//!
+//! The front page is cached as its posts rather than as rendered html, as
+//! pages are rendered per request together with the signed in user's parts.
+//! Vote counts aren't cached because posts have no votes yet. Once they do,
+//! voting has to invalidate the front page, as its posts include the counts.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::{
+        config::CacheConfig,
+        content::{self, Post},
+        keydb, postgres,
+        types::{DateTime, UserId},
+    },
+    redis::AsyncCommands,
+    serde::{de::DeserializeOwned, Serialize},
+    std::sync::OnceLock,
+    uuid::Uuid,
+};
+
+/// The key of the first page of the newest posts as seen by signed out
+/// readers.
+const FRONT_PAGE_KEY: &str = "cache:front_page";
+
+/// Caches a session unless its key is taken, like by the tombstone of the
+/// deleted session, and adds it to the user's sessions, whose expiry is only
+/// ever extended so it outlives all of them.
+const CACHE_SESSION_SCRIPT: &str = r"
+if not redis.call('SET', KEYS[1], ARGV[1], 'NX', 'EX', ARGV[2]) then
+    return 0
+end
+redis.call('SADD', KEYS[2], KEYS[1])
+if redis.call('TTL', KEYS[2]) < tonumber(ARGV[2]) then
+    redis.call('EXPIRE', KEYS[2], ARGV[2])
+end
+return 1
+";
+
+static CONFIG: OnceLock<CacheConfig> = OnceLock::new();
+
+/// Enables the cache.
+pub fn init(config: CacheConfig) {
+    if !keydb::is_configured() {
+        tracing::warn!("the cache is configured but keydb isn't");
+    }
+    if CONFIG.set(config).is_err() {
+        panic!("cache already initialized");
+    }
+}
+
+fn session_key(session_id: Uuid) -> String {
+    format!("cache:session:{session_id}")
+}
+
+/// The key of the set of a user's cached sessions.
+fn user_sessions_key(user_id: UserId) -> String {
+    format!("cache:user_sessions:{user_id}")
+}
+
+/// Returns the cached value of the key, if any.
+async fn get<T: DeserializeOwned>(key: &str) -> Option<T> {
+    CONFIG.get()?;
+    let json =
+        keydb::run(|mut connection| async move { connection.get::<_, Option<String>>(key).await })
+            .await
+            .ok()??;
+    serde_json::from_str(&json)
+        .map_err(|error| tracing::error!(?error, key, "invalid cached value"))
+        .ok()
+}
+
+/// Caches the value of the key for the number of seconds.
+async fn set<T: Serialize>(key: &str, value: &T, seconds: u64) {
+    if CONFIG.get().is_none() || seconds == 0 {
+        return;
+    }
+    let json = match serde_json::to_string(value) {
+        Ok(json) => json,
+        Err(error) => {
+            tracing::error!(?error, key, "couldn't serialize the value to cache");
+            return;
+        }
+    };
+    drop(
+        keydb::run(|mut connection| async move {
+            connection
+                .set_ex::<_, _, ()>(key, json, seconds as usize)
+                .await
+        })
+        .await,
+    );
+}
+
+/// Returns the user of the session if the session exists and hasn't expired.
+pub async fn session_user(session_id: Uuid) -> Result<Option<UserId>, postgres::Error> {
+    let key = session_key(session_id);
+    // a cached None is the tombstone of a deleted session
+    if let Some(user_id) = get::<Option<UserId>>(&key).await {
+        return Ok(user_id);
+    }
+
+    let Some((user_id, expires)) = postgres::session_user(session_id).await? else {
+        return Ok(None);
+    };
+    if let Some(config) = CONFIG.get() {
+        // don't keep the session cached past its expiry
+        let seconds = (expires - chrono::Utc::now())
+            .num_seconds()
+            .clamp(0, config.session_seconds.get() as i64) as u64;
+        if seconds > 0 {
+            let user_sessions_key = user_sessions_key(user_id);
+            let value = serde_json::to_string(&Some(user_id)).expect("user ids serialize");
+            drop(
+                keydb::run(|mut connection| async move {
+                    redis::Script::new(CACHE_SESSION_SCRIPT)
+                        .key(&key)
+                        .key(&user_sessions_key)
+                        .arg(value)
+                        .arg(seconds)
+                        .invoke_async::<_, ()>(&mut connection)
+                        .await
+                })
+                .await,
+            );
+        }
+    }
+    Ok(Some(user_id))
+}
+
+/// Replaces the cached sessions of the user with tombstones. Must be called
+/// with the ids of the user's sessions whenever they are deleted.
+pub async fn invalidate_sessions(user_id: UserId, session_ids: &[Uuid]) {
+    let Some(config) = CONFIG.get() else {
+        return;
+    };
+    let user_sessions_key = user_sessions_key(user_id);
+    let tombstone = serde_json::to_string(&None::<UserId>).expect("user ids serialize");
+    let seconds = config.session_seconds.get() as usize;
+    drop(
+        keydb::run(|mut connection| async move {
+            let mut pipe = redis::pipe();
+            for session_id in session_ids {
+                pipe.set_ex(session_key(*session_id), &tombstone, seconds)
+                    .ignore();
+            }
+            pipe.del(&user_sessions_key)
+                .ignore()
+                .query_async::<_, ()>(&mut connection)
+                .await
+        })
+        .await,
+    );
+}
+
+/// Returns a page of the newest posts, created before the given time if any.
+/// The first page as seen by signed out readers is cached.
+pub async fn newest_posts(
+    before: Option<DateTime>,
+    viewer: Option<UserId>,
+) -> Result<Vec<Post>, postgres::Error> {
+    let is_front_page = before.is_none() && viewer.is_none();
+    if is_front_page {
+        if let Some(posts) = get(FRONT_PAGE_KEY).await {
+            return Ok(posts);
+        }
+    }
+
+    let posts = postgres::newest_posts(before, viewer, content::POSTS_PER_PAGE).await?;
+    if let (true, Some(config)) = (is_front_page, CONFIG.get()) {
+        set(FRONT_PAGE_KEY, &posts, config.front_page_seconds.get()).await;
+    }
+    Ok(posts)
+}
+
+/// Removes the cached front page. Must be called whenever a post is created,
+/// changed or hidden, or a comment is created.
+pub async fn invalidate_front_page() {
+    if CONFIG.get().is_none() {
+        return;
+    }
+    drop(
+        keydb::run(|mut connection| async move { connection.del::<_, ()>(FRONT_PAGE_KEY).await })
+            .await,
+    );
+}
@@ -0,0 +1,279 @@
+//! Communities module.
+//!
+//! Every post belongs to a community. Communities have a description and rules
+//! shown in the sidebar of their front page, subscribers, and moderators who
+//! can edit the sidebar, add other moderators and pin, lock and remove posts.
+
+use {
+    crate::{content::Post, types::*},
+    leptos::*,
+    serde::{Deserialize, Serialize},
+};
+
+/// The maximum number of bytes a community description can be.
+// Don't change without updating the communities table contraints.
+pub const MAX_DESCRIPTION_BYTES: usize = 500;
+
+/// The maximum number of bytes a rule can be.
+// Don't change without updating the community rules table contraints.
+pub const MAX_RULE_BYTES: usize = 300;
+
+/// The maximum number of rules a community can have.
+pub const MAX_RULES: usize = 15;
+
+/// A community and its sidebar.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Community {
+    pub name: CommunityName,
+    pub created: DateTime,
+    pub description: String,
+    pub rules: Vec<String>,
+    pub subscriber_count: i64,
+    pub moderators: Vec<Username>,
+}
+
+/// A community in the list of all communities.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct CommunitySummary {
+    pub name: CommunityName,
+    pub description: String,
+    pub subscriber_count: i64,
+}
+
+/// How the signed in user relates to a community.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub struct Membership {
+    pub subscribed: bool,
+    pub moderator: bool,
+}
+
+/// Returns a community.
+#[tracing::instrument(skip_all)]
+#[server(GetCommunity, "/api")]
+pub async fn get_community(name: CommunityName) -> Result<Option<Community>, ServerFnError> {
+    Ok(crate::postgres::community(&name).await?)
+}
+
+/// Returns all communities, the ones with the most subscribers first.
+#[tracing::instrument(skip_all)]
+#[server(GetCommunities, "/api")]
+pub async fn get_communities() -> Result<Vec<CommunitySummary>, ServerFnError> {
+    Ok(crate::postgres::communities().await?)
+}
+
+/// Returns a page of a community's front page: the pinned posts followed by
+/// the newest posts, or the posts created before the given time if any.
+#[tracing::instrument(skip_all)]
+#[server(GetCommunityPosts, "/api")]
+pub async fn get_community_posts(
+    cx: Scope,
+    name: CommunityName,
+    before: Option<DateTime>,
+) -> Result<Vec<Post>, ServerFnError> {
+    use crate::{content::POSTS_PER_PAGE, postgres, session};
+
+    let viewer = session::user_id(cx).await?;
+    let community_id = require_community_id(&name).await?;
+    Ok(postgres::community_posts(community_id, before, viewer, POSTS_PER_PAGE).await?)
+}
+
+/// Returns how the signed in user relates to a community.
+#[tracing::instrument(skip_all)]
+#[server(GetMembership, "/api")]
+pub async fn get_membership(cx: Scope, name: CommunityName) -> Result<Membership, ServerFnError> {
+    use crate::{postgres, session};
+
+    let Some(user_id) = session::user_id(cx).await? else {
+        return Ok(Membership::default());
+    };
+    let community_id = require_community_id(&name).await?;
+    Ok(postgres::membership(community_id, user_id).await?)
+}
+
+/// Creates a community. The signed in user becomes its first moderator and
+/// subscriber.
+#[tracing::instrument(skip_all)]
+#[server(CreateCommunity, "/api")]
+pub async fn create_community(
+    cx: Scope,
+    name: CommunityName,
+    description: String,
+    rules: String,
+) -> Result<(), ServerFnError> {
+    use crate::{postgres, session, types::validation::Validate};
+
+    let creator = session::require_user_id(cx).await?;
+    name.validate()
+        .map_err(|violations| ServerFnError::Args(violations.to_string()))?;
+    let description = validate_description(description)?;
+    let rules = parse_rules(&rules)?;
+
+    let community_id = CommunityId(uuid::Uuid::new_v4());
+    match postgres::create_community(community_id, &name, &description, &rules, creator).await {
+        Err(postgres::Error::UniqueViolation(_)) => Err(ServerFnError::Args(format!(
+            "the community {name} already exists"
+        ))),
+        result => {
+            result?;
+            leptos_actix::redirect(cx, &format!("/c/{name}"));
+            Ok(())
+        }
+    }
+}
+
+/// Updates the description and rules of a community.
+#[tracing::instrument(skip_all)]
+#[server(UpdateCommunity, "/api")]
+pub async fn update_community(
+    cx: Scope,
+    name: CommunityName,
+    description: String,
+    rules: String,
+) -> Result<(), ServerFnError> {
+    let (community_id, _) = require_moderator(cx, &name).await?;
+    let description = validate_description(description)?;
+    let rules = parse_rules(&rules)?;
+    crate::postgres::update_community(community_id, &description, &rules).await?;
+    Ok(())
+}
+
+/// Subscribes the signed in user to a community or unsubscribes them.
+#[tracing::instrument(skip_all)]
+#[server(SetSubscribed, "/api")]
+pub async fn set_subscribed(
+    cx: Scope,
+    name: CommunityName,
+    subscribed: bool,
+) -> Result<(), ServerFnError> {
+    let user_id = crate::session::require_user_id(cx).await?;
+    let community_id = require_community_id(&name).await?;
+    crate::postgres::set_subscribed(community_id, user_id, subscribed).await?;
+    Ok(())
+}
+
+/// Makes a user a moderator of a community.
+#[tracing::instrument(skip_all)]
+#[server(AddModerator, "/api")]
+pub async fn add_moderator(
+    cx: Scope,
+    name: CommunityName,
+    username: Username,
+) -> Result<(), ServerFnError> {
+    use crate::postgres;
+
+    let (community_id, _) = require_moderator(cx, &name).await?;
+    let user_id = postgres::user_id(&username)
+        .await?
+        .ok_or_else(|| ServerFnError::Args(format!("the user {username} doesn't exist")))?;
+    postgres::add_moderator(community_id, user_id).await?;
+    Ok(())
+}
+
+/// Pins a post to the top of its community's front page or unpins it.
+#[tracing::instrument(skip_all)]
+#[server(SetPinned, "/api")]
+pub async fn set_pinned(cx: Scope, post_id: PostId, pinned: bool) -> Result<(), ServerFnError> {
+    use crate::moderation::Action;
+
+    let (moderator, subject) = require_post_moderator(cx, post_id).await?;
+    let action = if pinned { Action::Pin } else { Action::Unpin };
+    crate::postgres::moderate(moderator, action, &subject, None, "").await?;
+    crate::cache::invalidate_front_page().await;
+    Ok(())
+}
+
+/// Locks a post so it can't be commented on or unlocks it.
+#[tracing::instrument(skip_all)]
+#[server(SetLocked, "/api")]
+pub async fn set_locked(cx: Scope, post_id: PostId, locked: bool) -> Result<(), ServerFnError> {
+    use crate::moderation::Action;
+
+    let (moderator, subject) = require_post_moderator(cx, post_id).await?;
+    let action = if locked { Action::Lock } else { Action::Unlock };
+    crate::postgres::moderate(moderator, action, &subject, None, "").await?;
+    crate::cache::invalidate_front_page().await;
+    Ok(())
+}
+
+/// Removes a post from its community. The reason is shown to the author.
+#[tracing::instrument(skip_all)]
+#[server(RemovePost, "/api")]
+pub async fn remove_post(cx: Scope, post_id: PostId, reason: String) -> Result<(), ServerFnError> {
+    use crate::moderation::{validate_reason, Action};
+
+    let (moderator, subject) = require_post_moderator(cx, post_id).await?;
+    let reason = validate_reason(Action::Remove, reason)?;
+    crate::postgres::moderate(moderator, Action::Remove, &subject, None, &reason).await?;
+    crate::cache::invalidate_front_page().await;
+    Ok(())
+}
+
+/// Returns the id of a community or an error if it doesn't exist.
+#[cfg(feature = "ssr")]
+pub async fn require_community_id(name: &CommunityName) -> Result<CommunityId, ServerFnError> {
+    crate::postgres::community_id(name)
+        .await?
+        .ok_or_else(|| ServerFnError::Args(format!("the community {name} doesn't exist")))
+}
+
+/// Returns the ids of the community and the signed in user or an error if the
+/// user isn't a moderator of the community.
+#[cfg(feature = "ssr")]
+pub async fn require_moderator(
+    cx: Scope,
+    name: &CommunityName,
+) -> Result<(CommunityId, UserId), ServerFnError> {
+    let user_id = crate::session::require_user_id(cx).await?;
+    let community_id = require_community_id(name).await?;
+    crate::permissions::require_moderator(user_id, Some(community_id)).await?;
+    Ok((community_id, user_id))
+}
+
+/// Returns the id of the signed in user and the post or an error if the user
+/// isn't a moderator of the post's community.
+#[cfg(feature = "ssr")]
+pub async fn require_post_moderator(
+    cx: Scope,
+    post_id: PostId,
+) -> Result<(UserId, crate::moderation::Subject), ServerFnError> {
+    let user_id = crate::session::require_user_id(cx).await?;
+    let subject = crate::postgres::post_subject(post_id)
+        .await?
+        .ok_or_else(|| ServerFnError::Args("the post doesn't exist".to_owned()))?;
+    let community_id = subject
+        .community_id
+        .ok_or_else(|| ServerFnError::Args("the post doesn't belong to a community".to_owned()))?;
+    crate::permissions::require_moderator(user_id, Some(community_id)).await?;
+    Ok((user_id, subject))
+}
+
+#[cfg(feature = "ssr")]
+fn validate_description(description: String) -> Result<String, ServerFnError> {
+    let description = description.trim().to_owned();
+    if description.len() > MAX_DESCRIPTION_BYTES {
+        return Err(ServerFnError::Args(
+            "the description is too long".to_owned(),
+        ));
+    }
+    Ok(description)
+}
+
+/// Parses rules written one per line.
+#[cfg(feature = "ssr")]
+fn parse_rules(rules: &str) -> Result<Vec<String>, ServerFnError> {
+    let rules: Vec<_> = rules
+        .lines()
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(str::to_owned)
+        .collect();
+    if rules.len() > MAX_RULES {
+        return Err(ServerFnError::Args(format!(
+            "a community can have at most {MAX_RULES} rules"
+        )));
+    }
+    if rules.iter().any(|rule| rule.len() > MAX_RULE_BYTES) {
+        return Err(ServerFnError::Args("a rule is too long".to_owned()));
+    }
+    Ok(rules)
+}
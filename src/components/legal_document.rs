@@ -0,0 +1,68 @@
+use {
+    crate::{
+        internationalization::{Language, Translations},
+        legal::{self, AcceptLegalDocument, Document},
+        scroll_to_top, PRODUCT_NAME,
+    },
+    leptos::*,
+    leptos_router::*,
+};
+
+/// Renders the current version of a legal document.
+#[component]
+pub fn LegalDocument(cx: Scope, document: Document) -> impl IntoView {
+    let language = Language::default();
+    let t = Translations::for_language(language);
+    let rendered = create_resource(
+        cx,
+        || (),
+        move |_| legal::get_legal_document(document, language),
+    );
+
+    scroll_to_top();
+    view! { cx,
+        <main class="p-4 lh-condensed">
+            <h1>{PRODUCT_NAME}{" "}{document.title(t)}</h1>
+            <Suspense fallback=|| ()>
+                {move || rendered.read(cx).and_then(Result::ok).map(|rendered| view! { cx,
+                    <p class="text-italic">
+                        {t.effective_as_of()}{" "}{rendered.version.effective.format("%Y-%m-%d").to_string()}
+                    </p>
+                    {(rendered.language != language).then(|| view! { cx,
+                        <div class="flash mb-3">{t.not_yet_translated()}</div>
+                    })}
+                    <div inner_html=rendered.html></div>
+                })}
+            </Suspense>
+        </main>
+    }
+}
+
+/// Asks the signed in user to accept new versions of the legal documents.
+#[component]
+pub fn LegalUpdates(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+    let accept = create_server_action::<AcceptLegalDocument>(cx);
+    let pending = create_resource(
+        cx,
+        move || accept.version().get(),
+        move |_| legal::pending_legal_documents(cx),
+    );
+
+    view! { cx,
+        <Transition fallback=|| ()>
+            {move || pending.read(cx).and_then(Result::ok).unwrap_or_default().into_iter().map(|version| view! { cx,
+                <div class="flash flash-warn flash-full">
+                    <ActionForm action=accept class="flash-action">
+                        <input type="hidden" name="document" value=version.document.to_string()/>
+                        <input type="hidden" name="effective" value=version.effective.to_string()/>
+                        <button type="submit" class="btn btn-sm btn-primary">{t.accept()}</button>
+                    </ActionForm>
+                    {t.new_version_published()}
+                    <A href=version.document.path()>{version.document.title(t)}</A>
+                    {t.please_review_and_accept()}
+                </div>
+            }).collect_view(cx)}
+        </Transition>
+    }
+}
@@ -1,3 +1,7 @@
+mod legal_document;
 mod nav;
+mod post_item;
+mod report_form;
+mod sanctions;
 
-pub use nav::*;
+pub use {legal_document::*, nav::*, post_item::*, report_form::*, sanctions::*};
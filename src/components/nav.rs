@@ -21,6 +21,15 @@ pub fn Nav(cx: Scope) -> impl IntoView {
                 <div class="Header-item Header-item--full">
                     <A href=HOME class="Header-link f3">{PRODUCT_NAME}</A>
                 </div>
+                <div class="Header-item">
+                    <A href=COMMUNITIES class="Header-link">{t.communities()}</A>
+                </div>
+                <div class="Header-item">
+                    <Form method="GET" action=SEARCH>
+                        <input class="form-control input-sm input-dark" type="search" name="q"
+                            placeholder=t.search() aria-label=t.search()/>
+                    </Form>
+                </div>
                 <div class="Header-item mr-0">
                     <div style="cursor: pointer;" class="dropdown details-reset details-overlay d-inline-block" on:click=move |_| set_show_modal.set(true)>
                         <Show when=move || show_modal.get() fallback=move |_| account_button() >
@@ -43,6 +52,8 @@ pub fn Nav(cx: Scope) -> impl IntoView {
                                             </svg>
                                             <span class="text-semibold ml-1">{t.sign_in()}</span>
                                         </A>
+                                        <A href=MODERATION_NOTICES class="SelectMenu-item">{t.moderation_notices()}</A>
+                                        <A href=MODERATION_QUEUE class="SelectMenu-item">{t.moderation_queue()}</A>
                                         <hr class="SelectMenu-divider" />
                                         <A href=ABOUT class="SelectMenu-item color-fg-muted">{t.about_marzichat()}</A>
                                         <A href=HELP_AND_SAFETY class="SelectMenu-item color-fg-muted">{t.help_and_safety()}</A>
@@ -0,0 +1,52 @@
+use {
+    crate::{
+        content::Post,
+        internationalization::{Language, Translations},
+        markdown::LINK_REL,
+        types::datetime,
+    },
+    leptos::*,
+    leptos_router::*,
+};
+
+/// A post in a list of posts with the domain and preview image of its url.
+#[component]
+pub fn PostItem(cx: Scope, post: Post) -> impl IntoView {
+    let t = Translations::default();
+    let path = format!("/posts/{}", post.post_id);
+
+    view! { cx,
+        <li class="d-flex flex-items-start py-2 border-bottom">
+            <div class="flex-auto">
+                {match post.url {
+                    Some(url) => view! { cx,
+                        <a href=url rel=LINK_REL target="_blank" class="Link--primary text-bold">{post.title.0}</a>
+                    }.into_view(cx),
+                    None => view! { cx,
+                        <A href=path.clone() class="Link--primary text-bold">{post.title.0}</A>
+                    }.into_view(cx),
+                }}
+                {post.domain.map(|domain| view! { cx, <span class="color-fg-muted ml-1">"("{domain}")"</span> })}
+                <div class="color-fg-muted f6">
+                    {post.pinned.then(|| view! { cx, <span class="Label Label--success mr-1">{t.pinned()}</span> })}
+                    {post.community.map(|community| view! { cx,
+                        <A href=format!("/c/{community}")>{format!("c/{community}")}</A>{" | "}
+                    })}
+                    {t.by()}{" "}{post.author.0}{" "}
+                    {datetime::ago(&post.created, Language::default())}{" | "}
+                    <A href=path>
+                        {if post.comment_count > 0 {
+                            format!("{} {}", post.comment_count, t.comments())
+                        } else {
+                            t.discuss().to_owned()
+                        }}
+                    </A>
+                </div>
+            </div>
+            {post.thumbnail_url.map(|thumbnail_url| view! { cx,
+                <img src=thumbnail_url alt="" loading="lazy" referrerpolicy="no-referrer"
+                    class="ml-2 rounded-2" width="80" height="60" style="object-fit: cover;"/>
+            })}
+        </li>
+    }
+}
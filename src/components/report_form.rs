@@ -0,0 +1,69 @@
+use {
+    crate::{
+        internationalization::Translations,
+        moderation::{SubmitReport, MAX_DETAILS_BYTES, REASONS},
+        types::{CommentId, CommunityName, PostId, Username},
+    },
+    leptos::*,
+    leptos_router::*,
+};
+
+/// A link that opens a form to report a post, comment or user. Users are
+/// reported to the moderators of the community they were seen in.
+#[component]
+pub fn ReportForm(
+    cx: Scope,
+    #[prop(optional)] post_id: Option<PostId>,
+    #[prop(optional)] comment_id: Option<CommentId>,
+    #[prop(optional)] username: Option<Username>,
+    #[prop(optional_no_strip)] community: Option<CommunityName>,
+) -> impl IntoView {
+    let t = Translations::default();
+    let (open, set_open) = create_signal(cx, false);
+    let submit_report = create_server_action::<SubmitReport>(cx);
+    let label = if username.is_some() {
+        t.report_user()
+    } else {
+        t.report()
+    };
+    let hidden = move |name: &'static str, value: Option<String>| {
+        value.map(|value| view! { cx, <input type="hidden" name=name value=value/> })
+    };
+    let fields = store_value(
+        cx,
+        view! { cx,
+        {hidden("post_id", post_id.map(|id| id.to_string()))}
+        {hidden("comment_id", comment_id.map(|id| id.to_string()))}
+        {hidden("username", username.map(|username| username.0))}
+        {hidden("community", community.map(|community| community.0))}
+        },
+    );
+
+    view! { cx,
+        <a href="#" class="ml-1" on:click=move |event| {
+            event.prevent_default();
+            set_open.update(|open| *open = !*open);
+        }>{label}</a>
+        <Show when=open fallback=|_| ()>
+            {move || match submit_report.value().get() {
+                Some(Ok(())) => view! { cx, <div class="flash flash-success my-2">{t.report_submitted()}</div> }.into_view(cx),
+                error => view! { cx,
+                    {error.and_then(Result::err).map(|error| view! { cx,
+                        <div class="flash flash-error my-2">{error.to_string()}</div>
+                    })}
+                    <ActionForm action=submit_report class="my-2">
+                        {fields.get_value()}
+                        <select class="form-select mr-2" name="reason" aria-label=t.reason()>
+                            {REASONS.iter().map(|reason| view! { cx,
+                                <option value=format!("{reason:?}")>{reason.label(t)}</option>
+                            }).collect_view(cx)}
+                        </select>
+                        <input class="form-control mr-2" type="text" name="details" placeholder=t.details()
+                            aria-label=t.details() maxlength=MAX_DETAILS_BYTES/>
+                        <button type="submit" class="btn btn-sm">{t.report()}</button>
+                    </ActionForm>
+                }.into_view(cx),
+            }}
+        </Show>
+    }
+}
@@ -0,0 +1,120 @@
+use {
+    crate::{
+        internationalization::Translations,
+        moderation::MAX_REASON_BYTES,
+        sanctions::{self, Kind, LiftSanction, Sanction, SanctionUser, KINDS, MAX_SUSPENSION_DAYS},
+        types::CommunityName,
+    },
+    leptos::*,
+    leptos_router::*,
+};
+
+/// The sanctions in effect in a community, or site-wide if no community is
+/// given, and a form to sanction users. Shown only to users allowed to
+/// sanction users there.
+#[component]
+pub fn Sanctions(
+    cx: Scope,
+    #[prop(optional_no_strip)] community: Option<CommunityName>,
+) -> impl IntoView {
+    let t = Translations::default();
+    let sanction_user = create_server_action::<SanctionUser>(cx);
+    let lift_sanction = create_server_action::<LiftSanction>(cx);
+    let community = store_value(cx, community);
+    let sanctions = create_resource(
+        cx,
+        move || (sanction_user.version().get(), lift_sanction.version().get()),
+        move |_| sanctions::get_sanctions(cx, community.get_value()),
+    );
+    let error = move || {
+        sanction_user
+            .value()
+            .get()
+            .and_then(Result::err)
+            .or_else(|| lift_sanction.value().get().and_then(Result::err))
+            .map(|error| error.to_string())
+    };
+    // the number of days only applies to suspensions
+    let (kind, set_kind) = create_signal(cx, Kind::Suspension);
+
+    view! { cx,
+        <Transition fallback=|| ()>
+            {move || sanctions.read(cx).and_then(Result::ok).map(|sanctions| view! { cx,
+                <h2 class="h4">{t.sanctions()}</h2>
+                {move || error().map(|error| view! { cx, <div class="flash flash-error mb-3">{error}</div> })}
+                {if sanctions.is_empty() {
+                    view! { cx, <p class="color-fg-muted">{t.no_sanctions()}</p> }.into_view(cx)
+                } else {
+                    view! { cx,
+                        <ul class="list-style-none mb-3">
+                            {sanctions.into_iter().map(|sanction| view! { cx,
+                                <SanctionItem sanction lift_sanction/>
+                            }).collect_view(cx)}
+                        </ul>
+                    }.into_view(cx)
+                }}
+                <ActionForm action=sanction_user class="mb-4">
+                    {community.get_value().map(|community| view! { cx,
+                        <input type="hidden" name="community" value=community.0/>
+                    })}
+                    <input class="form-control mr-2" type="text" name="username" required
+                        placeholder=t.username() aria-label=t.username()/>
+                    <select class="form-select mr-2" name="kind" aria-label=t.sanction_user()
+                        on:change=move |event| {
+                            let kind = KINDS
+                                .iter()
+                                .find(|kind| format!("{kind:?}") == event_target_value(&event));
+                            if let Some(kind) = kind {
+                                set_kind.set(*kind);
+                            }
+                        }>
+                        {KINDS.iter().map(|kind| view! { cx,
+                            <option value=format!("{kind:?}")>{kind.label(t)}</option>
+                        }).collect_view(cx)}
+                    </select>
+                    <Show when=move || kind.get() == Kind::Suspension fallback=|_| ()>
+                        <input class="form-control mr-2" type="number" name="days" value="7" min="1"
+                            max=MAX_SUSPENSION_DAYS placeholder=t.duration_in_days()
+                            aria-label=t.duration_in_days()/>
+                    </Show>
+                    <input class="form-control mr-2" type="text" name="reason" required
+                        placeholder=t.reason() aria-label=t.reason() maxlength=MAX_REASON_BYTES/>
+                    <button type="submit" class="btn btn-danger">{t.sanction_user()}</button>
+                </ActionForm>
+            })}
+        </Transition>
+    }
+}
+
+/// A sanction in effect with a button to lift it.
+#[component]
+fn SanctionItem(
+    cx: Scope,
+    sanction: Sanction,
+    lift_sanction: Action<LiftSanction, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let t = Translations::default();
+    view! { cx,
+        <li class="d-flex flex-items-center py-2 border-bottom">
+            <div class="flex-auto">
+                <span class="text-bold">{sanction.username.0}</span>
+                <span class="Label ml-2">{sanction.kind.label(t)}</span>
+                {sanction.expires.map(|expires| view! { cx,
+                    <span class="color-fg-muted f6 ml-2">
+                        {t.until()}" "{expires.format("%Y-%m-%d %H:%M UTC").to_string()}
+                    </span>
+                })}
+                <div class="f6">
+                    {t.reason()}": "{sanction.reason}
+                    {sanction.issued_by.map(|issued_by| view! { cx,
+                        <span class="color-fg-muted">" · "{t.issued_by()}" "{issued_by.0}</span>
+                    })}
+                </div>
+            </div>
+            <ActionForm action=lift_sanction>
+                <input type="hidden" name="sanction_id" value=sanction.sanction_id.to_string()/>
+                <button type="submit" class="btn-link">{t.lift()}</button>
+            </ActionForm>
+        </li>
+    }
+}
@@ -2,35 +2,101 @@
 //! for the backend in one place.
 //!
 //! The configuration is parsed from `.ron` file. Ron is to Rust what JSON is to
-//! JavaScript.
+//! JavaScript. Environment variables and secret files are layered on top of it,
+//! see [`layers`].
 #![cfg(feature = "ssr")]
 
+mod check;
+mod layers;
+
 use {
+    crate::rate_limit::Policy,
     anyhow::{Context, Result},
+    ipnet::IpNet,
+    lettre::message::Mailbox,
+    regex::Regex,
+    rustls_pemfile::Item,
     sentry::types::Dsn,
     serde::{Deserialize, Deserializer},
     std::{
+        collections::{BTreeMap, BTreeSet, HashMap},
         fs::File,
         io::BufReader,
+        net::IpAddr,
         num::{NonZeroU32, NonZeroU64, NonZeroUsize},
         path::{Path, PathBuf},
     },
     tracing_subscriber::EnvFilter,
 };
+pub use {
+    check::{check, key_matches, Problem, Severity},
+    layers::ParseError,
+};
 
-pub type TlsConfig = rustls::ServerConfig;
+/// A tls certificate chain and its private key.
+pub type TlsCertificate = rustls::sign::CertifiedKey;
 
 /// The root configuration.
 // dont' derive Debug to avoid leaking secrets
 #[derive(Deserialize)]
 pub struct Config {
+    pub cache: Option<CacheConfig>,
+    pub keydb: Option<KeydbConfig>,
+    pub link_preview: LinkPreviewConfig,
     pub logging: LoggingConfig,
+    pub mailer: MailerConfig,
+    pub monitors: MonitorsConfig,
+    pub passwords: PasswordsConfig,
     pub postgres: PostgresConfig,
     pub server: ServerConfig,
+    pub sign_in: SignInConfig,
+    pub spam_filter: SpamFilterConfig,
     pub cpu_threads: NonZeroUsize,
 }
 
-/// Logging filters and system metric warning configuration.
+/// The configuration of the cache in keydb. Nothing is cached without it.
+#[derive(Deserialize)]
+pub struct CacheConfig {
+    /// The maximum number of seconds a session lookup is cached.
+    pub session_seconds: NonZeroU64,
+
+    /// The number of seconds the front page is cached.
+    pub front_page_seconds: NonZeroU64,
+}
+
+/// The keydb configuration. Keydb is optional, everything that uses it falls
+/// back to state local to the server instance without it.
+#[derive(Deserialize)]
+pub struct KeydbConfig {
+    /// The keydb url to connect to.
+    pub url: String,
+
+    /// The timeout for connecting and for every command, after which keydb is
+    /// considered unavailable.
+    pub timeout_milliseconds: NonZeroU64,
+}
+
+/// The configuration for fetching link previews of submitted urls.
+#[derive(Deserialize, Debug, Clone)]
+pub struct LinkPreviewConfig {
+    /// The maximum number of previews fetched at the same time.
+    pub concurrency: NonZeroUsize,
+
+    /// The timeout for connecting to the linked site.
+    pub connect_timeout_milliseconds: NonZeroU64,
+
+    /// The timeout for the whole fetch, including reading the response.
+    pub timeout_milliseconds: NonZeroU64,
+
+    /// The maximum number of bytes of the page that are read. The metadata is
+    /// in the head of the page so the rest can safely be ignored.
+    pub max_bytes: NonZeroUsize,
+
+    /// The maximum number of redirects that are followed.
+    pub max_redirects: usize,
+}
+
+/// Logging filters and sinks.
 #[derive(Deserialize)]
 pub struct LoggingConfig {
     /// Logging directives; acceptable directive must must follow [this][1]
@@ -43,17 +109,135 @@ pub struct LoggingConfig {
     /// The sentry dsn.
     pub sentry_data_source_name: Option<Dsn>,
 
-    /// The interval in seconds to log machine stats.
-    pub machine_stats_interval_seconds: NonZeroU64,
+    /// Where to write logs as JSON lines, if anywhere.
+    pub json: Option<JsonLogOutput>,
+
+    /// The OpenTelemetry collector spans are exported to, if any.
+    pub otlp: Option<OtlpConfig>,
+}
+
+/// The OpenTelemetry collector spans are exported to over OTLP/HTTP.
+#[derive(Deserialize)]
+pub struct OtlpConfig {
+    /// The url spans are posted to, usually ending in `/v1/traces`.
+    pub endpoint: String,
+
+    /// The name of the service the spans are attributed to.
+    pub service_name: String,
+
+    /// The fraction of traces started by the server that are exported. Traces
+    /// started by a caller follow the caller's sampling decision.
+    pub sample_ratio: f64,
+}
+
+/// The destination of JSON logs.
+#[derive(Deserialize)]
+pub enum JsonLogOutput {
+    Stdout,
+
+    /// Files in a directory, rotated periodically.
+    File {
+        /// The directory the log files are written to.
+        directory: PathBuf,
+
+        /// The prefix of the log file names, followed by the date of the
+        /// rotation period.
+        file_name_prefix: String,
+
+        rotation: LogRotation,
+    },
+}
+
+/// How often a new log file is started.
+#[derive(Deserialize, Clone, Copy)]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    Daily,
+    Never,
+}
+
+/// The machine stats that are monitored. Stats without a config aren't
+/// sampled.
+#[derive(Deserialize)]
+pub struct MonitorsConfig {
+    pub cpu_temperature_celsius: Option<MonitorConfig>,
+
+    /// The fraction of the system memory in use.
+    pub memory_usage_ratio: Option<MonitorConfig>,
+
+    /// The fraction of the cpu time that isn't idle. Unlike the former
+    /// `cpu_load_warn_limit`, which only counted the time spent in the kernel,
+    /// this includes user time, so it reads higher at the same load.
+    pub cpu_load_ratio: Option<MonitorConfig>,
 
-    /// The cpu temperature in celsius above which a warning is logged.
-    pub cpu_temp_warn_threshold_celsius: f32,
+    /// The fraction of a filesystem's space in use.
+    pub disk_usage_ratio: Option<DiskMonitorConfig>,
 
-    /// The memory usage percentage above which a warning is logged.
-    pub system_memory_usage_warn_threshold_percentage: f64,
+    /// The fraction of the open file descriptor limit of the process in use.
+    pub file_descriptor_usage_ratio: Option<MonitorConfig>,
+}
+
+/// How often a stat is sampled and when warnings are logged about it.
+///
+/// A warning is logged when the stat rises above the warning threshold, and
+/// isn't logged again until the stat has fallen below the clear threshold, so a
+/// stat hovering around the warning threshold doesn't log a warning every time
+/// it's sampled.
+#[derive(Deserialize, Clone, Copy)]
+pub struct MonitorConfig {
+    pub interval_seconds: NonZeroU64,
+    pub warn_threshold: f64,
+
+    /// Must not be above the warning threshold.
+    pub clear_threshold: f64,
+}
+
+/// The monitor of the space in use of a filesystem.
+#[derive(Deserialize)]
+pub struct DiskMonitorConfig {
+    /// The mount point of the filesystem.
+    pub mount_point: PathBuf,
+
+    pub monitor: MonitorConfig,
+}
+
+/// The smtp relay emails are sent through.
+#[derive(Deserialize)]
+pub struct MailerConfig {
+    /// The host of the smtp relay.
+    pub host: String,
 
-    /// The cpu load average above which a warning is logged.
-    pub cpu_load_warn_limit: f32,
+    /// The port of the smtp relay.
+    pub port: u16,
+
+    /// Whether to connect to the relay over tls. Only disable it for a relay
+    /// running locally.
+    pub tls: bool,
+
+    /// The credentials to authenticate with at the relay, if any.
+    pub credentials: Option<MailerCredentials>,
+
+    /// The sender of the emails.
+    #[serde(deserialize_with = "parse_mailbox")]
+    pub from: Mailbox,
+}
+
+/// The credentials to authenticate with at the smtp relay.
+#[derive(Deserialize)]
+pub struct MailerCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// The configuration of password checks.
+#[derive(Deserialize)]
+pub struct PasswordsConfig {
+    /// The path to the list of SHA-1 hashes of breached passwords, imported
+    /// from the Have I Been Pwned password list with `marzichat passwords
+    /// import`. New passwords in the list are rejected. No passwords are
+    /// rejected for being breached if it's not set.
+    pub breached_hashes: Option<PathBuf>,
 }
 
 /// The postgres configuration.
@@ -71,8 +255,33 @@ pub struct PostgresConfig {
 #[derive(Deserialize)]
 pub struct ServerConfig {
     pub rate_limiter: RateLimiterConfig,
-    #[serde(deserialize_with = "parse_tls_config")]
-    pub tls: Option<TlsConfig>,
+
+    /// The networks of the proxies trusted to forward the client ip address in
+    /// the `forwarded_header`. The headers of requests from any other peer are
+    /// ignored.
+    pub trusted_proxies: Vec<IpNet>,
+
+    /// The header the trusted proxies forward the client ip address in. The
+    /// other header is never read, since proxies pass it on from clients
+    /// untouched.
+    pub forwarded_header: ForwardedHeader,
+
+    /// The networks of the peers allowed to scrape `/metrics`, like the
+    /// Prometheus server. Everyone else gets 404 Not Found. Requests through a
+    /// unix socket listener have no peer address and are never allowed.
+    pub metrics_networks: Vec<IpNet>,
+
+    /// The sockets the server accepts connections on.
+    pub listeners: Vec<ListenerConfig>,
+
+    /// The tls certificate, read from pem files. The private key can be in
+    /// PKCS#8, PKCS#1 (RSA) or SEC1 (EC) format.
+    #[serde(deserialize_with = "parse_tls_certificate")]
+    pub tls: Option<TlsCertificate>,
+
+    /// Obtains and renews the tls certificate automatically instead. Can't be
+    /// combined with `tls`.
+    pub acme: Option<AcmeConfig>,
 
     /// Sets number of workers to start per bind address.
     pub os_threads_per_bind_address: NonZeroUsize,
@@ -93,6 +302,73 @@ pub struct ServerConfig {
     pub shutdown_timeout_seconds: NonZeroU64,
 }
 
+/// The header proxies forward the client ip address in.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForwardedHeader {
+    /// `X-Forwarded-For`, appended to by most proxies and load balancers.
+    XForwardedFor,
+
+    /// `Forwarded`, standardized by RFC 7239.
+    Forwarded,
+}
+
+/// Sockets the server accepts connections on, serving plain http or https
+/// with the tls certificate.
+#[derive(Deserialize, Clone, Debug)]
+pub enum ListenerConfig {
+    /// Tcp sockets bound to the port on each address.
+    Tcp {
+        addresses: Vec<IpAddr>,
+        port: u16,
+        tls: bool,
+
+        /// Whether sockets on ipv6 addresses accept only ipv6 connections.
+        /// Must be set to bind both `0.0.0.0` and `::` to the same port.
+        ipv6_only: bool,
+    },
+
+    /// A unix domain socket serving plain http, for a reverse proxy on the
+    /// same machine. A socket left at the path is replaced.
+    Unix { path: PathBuf },
+
+    /// The sockets passed by systemd socket activation with the
+    /// `FileDescriptorName=` of the name, so the server doesn't need the
+    /// privileges to bind ports below 1024. Tls is only served over tcp.
+    Systemd { name: String, tls: bool },
+}
+
+impl ListenerConfig {
+    /// Whether the listener serves https.
+    pub fn tls(&self) -> bool {
+        match self {
+            Self::Tcp { tls, .. } | Self::Systemd { tls, .. } => *tls,
+            Self::Unix { .. } => false,
+        }
+    }
+}
+
+/// The ACME server tls certificates are obtained from, with HTTP-01 challenges
+/// answered on port 80.
+#[derive(Deserialize, Clone, Debug)]
+pub struct AcmeConfig {
+    /// The directory url of the ACME server, like
+    /// `https://acme-v02.api.letsencrypt.org/directory`.
+    pub directory_url: String,
+
+    /// The domains the certificate is for, all pointing at this server.
+    pub domains: Vec<String>,
+
+    /// The contacts given to the ACME server, like `mailto:admin@example.com`.
+    pub contact: Vec<String>,
+
+    /// The directory the account, the certificate and its private key are
+    /// stored in.
+    pub storage_directory: PathBuf,
+
+    /// The number of days before it expires that the certificate is renewed.
+    pub renew_days_before_expiry: NonZeroU32,
+}
+
 /// The rate limiter configuration.
 #[derive(Deserialize, Clone)]
 pub struct RateLimiterConfig {
@@ -108,6 +384,91 @@ pub struct RateLimiterConfig {
     /// Sets the interval (in seconds) after which one element of the quota is
     /// replenished.
     pub replenish_interval_seconds: NonZeroU64,
+
+    /// The quotas of the named rate limit policies, applied per user when
+    /// signed in and per ip address otherwise. Policies applied to whole
+    /// routes, like Api, are applied per ip address. Policies without a
+    /// quota aren't limited.
+    pub policies: HashMap<Policy, RateLimitQuota>,
+
+    /// Where the state of the rate limits is kept.
+    pub store: RateLimitStore,
+}
+
+/// Where the state of the rate limits is kept.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitStore {
+    /// In the memory of each server instance, so every instance allows the
+    /// full rate.
+    Memory,
+
+    /// In keydb, shared by all server instances. Falls back to memory while
+    /// keydb is unavailable.
+    Keydb,
+}
+
+/// The quota of a rate limit policy.
+#[derive(Deserialize, Clone, Debug)]
+pub struct RateLimitQuota {
+    /// How many times the action can be taken before the quota is exhausted.
+    pub burst_size: NonZeroU32,
+
+    /// The interval (in seconds) after which one element of the quota is
+    /// replenished.
+    pub replenish_interval_seconds: NonZeroU64,
+}
+
+/// The brute-force protection of signing in.
+#[derive(Deserialize, Debug)]
+pub struct SignInConfig {
+    /// The number of minutes failed sign-in attempts are counted for.
+    pub failure_window_minutes: NonZeroU64,
+
+    /// The number of failed attempts per account or ip address before each
+    /// further attempt has to wait.
+    pub free_failures: u32,
+
+    /// The number of seconds to wait after the first failed attempt past the
+    /// free ones, doubled with every further failed attempt.
+    pub backoff_base_seconds: NonZeroU64,
+
+    /// The maximum number of seconds to wait between attempts.
+    pub max_backoff_seconds: NonZeroU64,
+
+    /// The number of failed attempts on an account after which it's locked.
+    pub lockout_failures: NonZeroU32,
+
+    /// The number of minutes an account stays locked.
+    pub lockout_minutes: NonZeroU64,
+}
+
+/// The rules of the spam filter run on posts and comments before they're
+/// stored. Content breaking any of them is held for moderators to review.
+#[derive(Deserialize)]
+pub struct SpamFilterConfig {
+    /// Words that aren't allowed, matched as whole words ignoring case.
+    pub banned_words: Vec<String>,
+
+    /// Regular expressions that aren't allowed to match.
+    #[serde(deserialize_with = "parse_regexes")]
+    pub banned_patterns: Vec<Regex>,
+
+    /// The number of days an account is considered new.
+    pub new_account_days: u32,
+
+    /// The maximum number of links in a post or comment of a new account.
+    pub new_account_max_links: usize,
+
+    /// The number of hours identical posts and comments are looked for.
+    pub duplicate_window_hours: NonZeroU64,
+
+    /// The minimum number of bytes of a post or comment for it to be checked
+    /// for duplicates, so short replies like "thanks!" aren't held.
+    pub duplicate_min_bytes: usize,
+
+    /// The reputation below which all posts and comments of an account are
+    /// held.
+    pub min_reputation: i64,
 }
 
 /// The paths to the tls certificate and private key.
@@ -120,15 +481,98 @@ pub struct TlsCertPaths {
     pub cert_key: PathBuf,
 }
 
+/// A documented config with the defaults for production.
+pub const TEMPLATE: &str = include_str!("config/template.ron");
+
+/// Returns the path of the config file, taken from the `CONFIG` environment
+/// variable unless it's given.
+pub fn path(config: Option<PathBuf>) -> Result<PathBuf> {
+    match config {
+        Some(config) => Ok(config),
+        None => Ok(std::env::var_os("CONFIG")
+            .context("CONFIG env var not set and --config not passed in as argument")?
+            .into()),
+    }
+}
+
+/// The effective settings of a config: the config file with the values set by
+/// the environment variables and secret files in place of its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings(ron::Value);
+
 /// Parses the config by reading the entire contents of a config into memory and
-/// then parsing it.
-pub fn parse_from_file<P: AsRef<Path>>(config: P) -> Result<Config> {
-    Ok(
-        ron::from_str(&std::fs::read_to_string(config).context("failed to read config")?)
-            .context("failed to parse config file")?,
+/// then parsing it. Also returns its settings, to compare the config with when
+/// it's reloaded.
+pub fn parse_from_file<P: AsRef<Path>>(config: P) -> Result<(Config, Settings)> {
+    parse_settings_with_vars(
+        &std::fs::read_to_string(config).context("failed to read config")?,
+        std::env::vars(),
     )
 }
 
+/// Parses the config, with the environment variables and secret files layered
+/// on top of it.
+pub fn parse(config: &str) -> Result<Config> {
+    parse_with_vars(config, std::env::vars())
+}
+
+fn parse_with_vars(
+    config: &str,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<Config> {
+    layers::Layers::new(vars)
+        .deserialize(config)
+        .context("failed to parse config file")
+}
+
+fn parse_settings_with_vars(
+    config: &str,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<(Config, Settings)> {
+    let layers = layers::Layers::new(vars);
+    let parsed = layers
+        .deserialize(config)
+        .context("failed to parse config file")?;
+    let mut settings = ron::from_str(config).context("failed to parse config file")?;
+    layers.apply(&mut settings);
+    Ok((parsed, Settings(settings)))
+}
+
+/// Returns the dotted paths of the settings that differ between two configs,
+/// without their values so secrets aren't leaked. Enum variants can't be told
+/// apart when they're map keys, so only the map is reported for changes of
+/// values keyed by them.
+pub fn changes(old: &Settings, new: &Settings) -> Vec<String> {
+    fn diff(path: String, old: &ron::Value, new: &ron::Value, changes: &mut Vec<String>) {
+        use ron::Value;
+        match (old, new) {
+            (Value::Map(old), Value::Map(new)) => {
+                let old: BTreeMap<_, _> = old.iter().collect();
+                let new: BTreeMap<_, _> = new.iter().collect();
+                let keys: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+                for key in keys {
+                    let key_path = match key {
+                        Value::String(key) if path.is_empty() => key.clone(),
+                        Value::String(key) => format!("{path}.{key}"),
+                        _ => path.clone(),
+                    };
+                    match (old.get(*key), new.get(*key)) {
+                        (Some(old), Some(new)) => diff(key_path, old, new, changes),
+                        _ => changes.push(key_path),
+                    }
+                }
+            }
+            (Value::Option(Some(old)), Value::Option(Some(new))) => diff(path, old, new, changes),
+            (old, new) if old != new => changes.push(path),
+            _ => (),
+        }
+    }
+
+    let mut changes = Vec::new();
+    diff(String::new(), &old.0, &new.0, &mut changes);
+    changes
+}
+
 fn parse_env_filter<'de, D>(deserializer: D) -> Result<EnvFilter, D::Error>
 where
     D: Deserializer<'de>,
@@ -139,31 +583,260 @@ where
         .map_err(serde::de::Error::custom)?)
 }
 
-fn parse_tls_config<'de, D>(deserializer: D) -> Result<Option<TlsConfig>, D::Error>
+fn parse_regexes<'de, D>(deserializer: D) -> Result<Vec<Regex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let patterns: Vec<String> = Deserialize::deserialize(deserializer)?;
+    patterns
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+fn parse_mailbox<'de, D>(deserializer: D) -> Result<Mailbox, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mailbox: String = Deserialize::deserialize(deserializer)?;
+    mailbox.parse().map_err(serde::de::Error::custom)
+}
+
+fn parse_tls_certificate<'de, D>(deserializer: D) -> Result<Option<TlsCertificate>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let tls: Option<TlsCertPaths> = Deserialize::deserialize(deserializer)?;
-    tls.map(|tls| create_tls(&tls.cert, &tls.cert_key).map_err(serde::de::Error::custom))
+    tls.map(|tls| read_tls_certificate(&tls.cert, &tls.cert_key).map_err(serde::de::Error::custom))
         .transpose()
 }
 
-fn create_tls(cert: &Path, cert_key: &Path) -> Result<TlsConfig> {
+pub(crate) fn read_tls_certificate(cert: &Path, cert_key: &Path) -> Result<TlsCertificate> {
     let open = |path| File::open(path).with_context(|| format!("error opening {path:?}"));
     let cert_chain = rustls_pemfile::certs(&mut BufReader::new(open(cert)?))
         .context("couldn't parse cert")?
         .into_iter()
         .map(rustls::Certificate)
         .collect();
-    let key = {
-        let mut keys = rustls_pemfile::pkcs8_private_keys(&mut BufReader::new(open(cert_key)?))
-            .context("couldn't parse cert key")?;
-        anyhow::ensure!(!keys.is_empty(), "no cert key found");
-        rustls::PrivateKey(keys.swap_remove(0))
-    };
-    TlsConfig::builder()
-        .with_safe_defaults()
-        .with_no_client_auth()
-        .with_single_cert(cert_chain, key)
-        .context("couldn't create certificate chain")
+    let key = rustls_pemfile::read_all(&mut BufReader::new(open(cert_key)?))
+        .context("couldn't parse cert key")?
+        .into_iter()
+        .find_map(|item| match item {
+            Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => Some(key),
+            _ => None,
+        })
+        .context("no cert key found")?;
+    let key = rustls::sign::any_supported_type(&rustls::PrivateKey(key))
+        .context("unsupported cert key")?;
+    Ok(TlsCertificate::new(cert_chain, key))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_changes() {
+        let old = r#"(
+            logging: (directives: "info", json: None),
+            server: (
+                tls: Some((cert: "a.pem", cert_key: "a.key")),
+                rate_limiter: (policies: {Api: (burst_size: 1)}),
+            ),
+            spam_filter: (banned_patterns: ["a"])
+        )"#;
+        let new = r#"(
+            logging: (directives: "debug", json: None),
+            server: (
+                tls: Some((cert: "b.pem", cert_key: "a.key")),
+                rate_limiter: (policies: {Api: (burst_size: 2)}),
+            ),
+            spam_filter: (banned_patterns: ["a", "b"]),
+            cache: None
+        )"#;
+        let settings = |config| Settings(ron::from_str(config).unwrap());
+        let (old, new) = (settings(old), settings(new));
+        assert_eq!(
+            changes(&old, &new),
+            [
+                "cache",
+                "logging.directives",
+                "server.rate_limiter.policies.burst_size",
+                "server.tls.cert",
+                "spam_filter.banned_patterns"
+            ]
+        );
+        assert!(changes(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_layered_changes() {
+        let dev_config = include_str!("../dev_config.ron");
+        let settings = |config: &str, vars| parse_settings_with_vars(config, vars).unwrap().1;
+        let overridden = vars(&[("POSTGRES__MAX_CONNECTIONS", "8")]);
+        let edited = dev_config.replace("max_connections: 1", "max_connections: 2");
+
+        // the environment variable hides changes of the config file
+        assert!(changes(
+            &settings(dev_config, overridden.clone()),
+            &settings(&edited, overridden.clone())
+        )
+        .is_empty());
+        assert_eq!(
+            changes(
+                &settings(dev_config, vars(&[])),
+                &settings(dev_config, overridden)
+            ),
+            ["postgres.max_connections"]
+        );
+
+        // secret files are compared by their contents
+        let directory = temp_dir("changes");
+        let secret = directory.join("url");
+        std::fs::write(&secret, "postgresql://marzichat@127.0.0.1/marzichat\n").unwrap();
+        let with_secret = with_url_file(dev_config, &secret);
+        let old = settings(&with_secret, vars(&[]));
+        assert!(changes(&settings(dev_config, vars(&[])), &old).is_empty());
+        std::fs::write(&secret, "postgresql://other\n").unwrap();
+        assert_eq!(
+            changes(&old, &settings(&with_secret, vars(&[]))),
+            ["postgres.url"]
+        );
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_template() {
+        let config = parse_with_vars(TEMPLATE, vars(&[])).unwrap();
+        assert!(check(&config, chrono::Utc::now()).is_empty());
+
+        let invalid = TEMPLATE.replace("max_connections: 10", "max_connections: ten");
+        let error = parse_with_vars(&invalid, vars(&[])).err().unwrap();
+        let error = error.downcast_ref::<ParseError>().unwrap();
+        assert!(error.message.starts_with("postgres.max_connections: "));
+        let line = invalid
+            .lines()
+            .position(|line| line.contains("max_connections: ten"))
+            .unwrap()
+            + 1;
+        let indent = " ".repeat(line.to_string().len());
+        assert_eq!(
+            error.snippet(&invalid),
+            Some(format!(
+                "{indent} |\n{line} |         max_connections: ten,\n{indent} | {}^",
+                " ".repeat(25)
+            ))
+        );
+    }
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| (format!("{}{name}", layers::ENV_PREFIX), (*value).to_owned()))
+            .collect()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("marzichat-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// Replaces the postgres url of the dev config with a `url_file` field.
+    fn with_url_file(config: &str, file: &Path) -> String {
+        config.replace(
+            "url: \"postgresql://marzichat@127.0.0.1/marzichat\"",
+            &format!("url_file: {file:?}"),
+        )
+    }
+
+    #[test]
+    fn test_env_var_over_field() {
+        let dev_config = include_str!("../dev_config.ron");
+        let config = parse_with_vars(
+            dev_config,
+            vars(&[
+                ("POSTGRES__MAX_CONNECTIONS", "8"),
+                (
+                    "KEYDB",
+                    "Some((url: \"redis://keydb\", timeout_milliseconds: 500))",
+                ),
+                (
+                    "LOGGING__SENTRY_DATA_SOURCE_NAME",
+                    "https://key@sentry.example/1",
+                ),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(config.postgres.max_connections.get(), 8);
+        assert_eq!(config.keydb.unwrap().url, "redis://keydb");
+        assert!(config.logging.sentry_data_source_name.is_some());
+        assert_eq!(
+            config.postgres.url,
+            "postgresql://marzichat@127.0.0.1/marzichat"
+        );
+    }
+
+    #[test]
+    fn test_config_file_secret_over_env_var() {
+        let directory = temp_dir("config-secret");
+        let secret = directory.join("url");
+        std::fs::write(&secret, "postgresql://config\n").unwrap();
+        let with_secret = with_url_file(include_str!("../dev_config.ron"), &secret);
+
+        let config = parse_with_vars(&with_secret, vars(&[])).unwrap();
+        assert_eq!(config.postgres.url, "postgresql://config");
+        let config =
+            parse_with_vars(&with_secret, vars(&[("POSTGRES__URL", "postgresql://env")])).unwrap();
+        assert_eq!(config.postgres.url, "postgresql://config");
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_env_var_secret_over_config_file_secret() {
+        let directory = temp_dir("env-secret");
+        let (from_config, from_env) = (directory.join("config"), directory.join("env"));
+        std::fs::write(&from_config, "postgresql://config\n").unwrap();
+        std::fs::write(&from_env, "postgresql://env\n").unwrap();
+        let with_secret = with_url_file(include_str!("../dev_config.ron"), &from_config);
+
+        let config = parse_with_vars(
+            &with_secret,
+            vars(&[("POSTGRES__URL_FILE", from_env.to_str().unwrap())]),
+        )
+        .unwrap();
+        assert_eq!(config.postgres.url, "postgresql://env");
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_layer_errors() {
+        let dev_config = include_str!("../dev_config.ron");
+        fn error(config: &str, vars: Vec<(String, String)>) -> String {
+            format!("{:#}", parse_with_vars(config, vars).err().unwrap())
+        }
+        assert!(
+            error(dev_config, vars(&[("POSTGRES__MAX_CONNECTIONS", "many")]))
+                .contains("postgres.max_connections: ")
+        );
+        assert!(error(dev_config, vars(&[("POSTGRES__URLL", "x")]))
+            .contains("MARZICHAT__POSTGRES__URLL doesn't match"));
+        assert!(error(
+            &dev_config.replace("max_connections: 1", "max_connections: 0"),
+            vars(&[])
+        )
+        .contains("postgres.max_connections: "));
+        assert!(error(
+            &dev_config.replace(
+                "127.0.0.1/marzichat\"",
+                "127.0.0.1/marzichat\", url_file: \"/nonexistent\""
+            ),
+            vars(&[])
+        )
+        .contains("postgres.url: `url` is set more than once"));
+        assert!(
+            error(dev_config, vars(&[("POSTGRES__URL_FILE", "/nonexistent")]))
+                .contains("postgres.url: failed to read \"/nonexistent\"")
+        );
+    }
 }
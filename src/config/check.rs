@@ -0,0 +1,431 @@
+//! Checks of the config beyond what parsing it verifies.
+//!
+//! Parsing already rejects malformed settings, like directives or a sentry dsn
+//! that don't parse. The checks here catch settings that are well-formed but
+//! can't work, like a private key not matching its certificate.
+
+use {
+    super::{Config, ListenerConfig, MonitorConfig, ServerConfig, TlsCertificate},
+    crate::types::DateTime,
+    chrono::Duration,
+    rustls::SignatureScheme,
+    std::{collections::BTreeSet, fmt, net::SocketAddr},
+};
+
+/// Certificates expiring sooner than this are warned about.
+const CERTIFICATE_EXPIRY_WARNING_DAYS: i64 = 14;
+
+/// How bad a problem is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The server works, but maybe not as intended.
+    Warning,
+
+    /// The server fails to start or misbehaves.
+    Error,
+}
+
+/// A problem with a setting.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub severity: Severity,
+
+    /// The dotted path of the setting.
+    pub setting: String,
+
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(formatter, "{severity}: {}: {}", self.setting, self.message)
+    }
+}
+
+/// Returns the problems of the config, most severe first.
+pub fn check(config: &Config, now: DateTime) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut report = |severity, setting: &str, message: String| {
+        problems.push(Problem {
+            severity,
+            setting: setting.to_owned(),
+            message,
+        })
+    };
+
+    let monitors = &config.monitors;
+    let ratios = [
+        ("monitors.memory_usage_ratio", monitors.memory_usage_ratio),
+        ("monitors.cpu_load_ratio", monitors.cpu_load_ratio),
+        (
+            "monitors.disk_usage_ratio.monitor",
+            monitors.disk_usage_ratio.as_ref().map(|disk| disk.monitor),
+        ),
+        (
+            "monitors.file_descriptor_usage_ratio",
+            monitors.file_descriptor_usage_ratio,
+        ),
+    ];
+    for (setting, monitor) in ratios {
+        let Some(monitor) = monitor else {
+            continue;
+        };
+        check_thresholds(monitor, &mut report, setting);
+        for (name, threshold) in [
+            ("warn_threshold", monitor.warn_threshold),
+            ("clear_threshold", monitor.clear_threshold),
+        ] {
+            if !(0.0..=1.0).contains(&threshold) {
+                report(
+                    Severity::Error,
+                    &format!("{setting}.{name}"),
+                    format!("{threshold} isn't a ratio between 0 and 1"),
+                );
+            }
+        }
+    }
+    if let Some(monitor) = monitors.cpu_temperature_celsius {
+        check_thresholds(monitor, &mut report, "monitors.cpu_temperature_celsius");
+    }
+
+    if let Some(otlp) = &config.logging.otlp {
+        if !(0.0..=1.0).contains(&otlp.sample_ratio) {
+            report(
+                Severity::Error,
+                "logging.otlp.sample_ratio",
+                format!("{} isn't a ratio between 0 and 1", otlp.sample_ratio),
+            );
+        }
+    }
+
+    let sign_in = &config.sign_in;
+    if sign_in.max_backoff_seconds < sign_in.backoff_base_seconds {
+        report(
+            Severity::Warning,
+            "sign_in.max_backoff_seconds",
+            "is below backoff_base_seconds, so every wait is the maximum".to_owned(),
+        );
+    }
+
+    if let Some(certificate) = &config.server.tls {
+        for (severity, message) in check_certificate(certificate, now) {
+            report(severity, "server.tls", message);
+        }
+    }
+
+    if let Some(acme) = &config.server.acme {
+        if config.server.tls.is_some() {
+            report(
+                Severity::Error,
+                "server.acme",
+                "can't be combined with server.tls".to_owned(),
+            );
+        }
+        if acme.domains.is_empty() {
+            report(
+                Severity::Error,
+                "server.acme.domains",
+                "no domains to obtain a certificate for".to_owned(),
+            );
+        }
+    }
+
+    check_listeners(&config.server, &mut report);
+
+    problems.sort_by(|a, b| b.severity.cmp(&a.severity));
+    problems
+}
+
+fn check_thresholds(
+    monitor: MonitorConfig,
+    report: &mut impl FnMut(Severity, &str, String),
+    setting: &str,
+) {
+    if monitor.clear_threshold > monitor.warn_threshold {
+        report(
+            Severity::Error,
+            &format!("{setting}.clear_threshold"),
+            format!(
+                "{} is above the warning threshold of {}",
+                monitor.clear_threshold, monitor.warn_threshold
+            ),
+        );
+    }
+}
+
+fn check_listeners(server: &ServerConfig, report: &mut impl FnMut(Severity, &str, String)) {
+    if server.listeners.is_empty() {
+        report(
+            Severity::Error,
+            "server.listeners",
+            "no listeners, so no connections are accepted".to_owned(),
+        );
+    }
+
+    let certificate = server.tls.is_some() || server.acme.is_some();
+    let mut bound = BTreeSet::new();
+    for (index, listener) in server.listeners.iter().enumerate() {
+        let setting = format!("server.listeners[{index}]");
+        if listener.tls() && !certificate {
+            report(
+                Severity::Error,
+                &setting,
+                "serves https, which requires server.tls or server.acme".to_owned(),
+            );
+        }
+        let ListenerConfig::Tcp {
+            addresses,
+            port,
+            ipv6_only,
+            ..
+        } = listener
+        else {
+            continue;
+        };
+        if addresses.is_empty() {
+            report(
+                Severity::Error,
+                &format!("{setting}.addresses"),
+                "no addresses to bind".to_owned(),
+            );
+        }
+        for address in addresses {
+            let address = SocketAddr::new(*address, *port);
+            if !bound.insert(address) {
+                report(
+                    Severity::Error,
+                    &format!("{setting}.addresses"),
+                    format!("{address} is bound more than once"),
+                );
+            }
+        }
+        // without ipv6_only, `::` also takes the port on every ipv4 address
+        let dual_stack = !ipv6_only
+            && addresses
+                .iter()
+                .any(|address| address.is_ipv6() && address.is_unspecified());
+        let other_ipv4 = server.listeners.iter().any(|other| match other {
+            ListenerConfig::Tcp {
+                addresses,
+                port: other_port,
+                ..
+            } => other_port == port && addresses.iter().any(|address| address.is_ipv4()),
+            _ => false,
+        });
+        if dual_stack && other_ipv4 {
+            report(
+                Severity::Error,
+                &format!("{setting}.ipv6_only"),
+                format!("must be set to bind :: next to an ipv4 address on port {port}"),
+            );
+        }
+    }
+
+    if certificate && !server.listeners.iter().any(ListenerConfig::tls) {
+        report(
+            Severity::Warning,
+            "server.listeners",
+            "no listener serves https, so the certificate isn't used".to_owned(),
+        );
+    }
+    if server.acme.is_some() && server.listeners.iter().all(ListenerConfig::tls) {
+        report(
+            Severity::Warning,
+            "server.listeners",
+            "no listener serves plain http, which acme challenges are answered over on port 80"
+                .to_owned(),
+        );
+    }
+}
+
+/// The signature schemes private keys are checked with, and the algorithms
+/// verifying their signatures with the public key of a certificate.
+static SCHEMES: [(SignatureScheme, &webpki::SignatureAlgorithm); 5] = [
+    (
+        SignatureScheme::ECDSA_NISTP256_SHA256,
+        &webpki::ECDSA_P256_SHA256,
+    ),
+    (
+        SignatureScheme::ECDSA_NISTP384_SHA384,
+        &webpki::ECDSA_P384_SHA384,
+    ),
+    (SignatureScheme::ED25519, &webpki::ED25519),
+    (
+        SignatureScheme::RSA_PSS_SHA256,
+        &webpki::RSA_PSS_2048_8192_SHA256_LEGACY_KEY,
+    ),
+    (
+        SignatureScheme::RSA_PKCS1_SHA256,
+        &webpki::RSA_PKCS1_2048_8192_SHA256,
+    ),
+];
+
+/// Returns whether the private key matches the certificate, or `None` if the
+/// certificate is invalid or the key type can't be checked.
+pub fn key_matches(certificate: &TlsCertificate) -> Option<bool> {
+    let end_entity = certificate.end_entity_cert().ok()?;
+    let parsed = webpki::EndEntityCert::try_from(end_entity.0.as_slice()).ok()?;
+
+    // sign a message with the private key and verify the signature with the
+    // public key of the certificate
+    let message = b"marzichat config check";
+    SCHEMES.iter().find_map(|(scheme, algorithm)| {
+        let signer = certificate.key.choose_scheme(&[*scheme])?;
+        let signature = signer.sign(message).ok()?;
+        Some(
+            parsed
+                .verify_signature(algorithm, message, &signature)
+                .is_ok(),
+        )
+    })
+}
+
+/// Checks that the private key matches the certificate and that the
+/// certificate isn't expired.
+fn check_certificate(certificate: &TlsCertificate, now: DateTime) -> Vec<(Severity, String)> {
+    let mut problems = Vec::new();
+    let Ok(end_entity) = certificate.end_entity_cert() else {
+        return vec![(Severity::Error, "no certificate found".to_owned())];
+    };
+    if webpki::EndEntityCert::try_from(end_entity.0.as_slice()).is_err() {
+        return vec![(Severity::Error, "the certificate is invalid".to_owned())];
+    }
+
+    match key_matches(certificate) {
+        Some(true) => (),
+        Some(false) => problems.push((
+            Severity::Error,
+            "the private key doesn't match the certificate".to_owned(),
+        )),
+        None => problems.push((
+            Severity::Warning,
+            "the private key can't be checked against the certificate".to_owned(),
+        )),
+    }
+
+    match crate::tls::not_after(end_entity) {
+        Some(expiry) if expiry <= now => problems.push((
+            Severity::Error,
+            format!("the certificate expired at {expiry}"),
+        )),
+        Some(expiry) if expiry - now < Duration::days(CERTIFICATE_EXPIRY_WARNING_DAYS) => problems
+            .push((
+                Severity::Warning,
+                format!("the certificate expires at {expiry}"),
+            )),
+        Some(_) => (),
+        None => problems.push((
+            Severity::Warning,
+            "the expiry of the certificate can't be read".to_owned(),
+        )),
+    }
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        chrono::{TimeZone, Utc},
+        std::path::Path,
+    };
+
+    fn certificate(cert: &str, cert_key: &str) -> TlsCertificate {
+        let testdata = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/config/testdata");
+        super::super::read_tls_certificate(&testdata.join(cert), &testdata.join(cert_key)).unwrap()
+    }
+
+    #[test]
+    fn test_check_certificate() {
+        let expiry = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let valid = certificate("a.pem", "a.key");
+        assert_eq!(
+            crate::tls::not_after(valid.end_entity_cert().unwrap()),
+            Some(expiry)
+        );
+        assert!(check_certificate(&valid, expiry - Duration::days(30)).is_empty());
+        assert_eq!(
+            check_certificate(&valid, expiry - Duration::days(1)),
+            [(
+                Severity::Warning,
+                "the certificate expires at 2021-01-01 00:00:00 UTC".to_owned()
+            )]
+        );
+        assert_eq!(
+            check_certificate(&certificate("a.pem", "b.key"), expiry),
+            [
+                (
+                    Severity::Error,
+                    "the private key doesn't match the certificate".to_owned()
+                ),
+                (
+                    Severity::Error,
+                    "the certificate expired at 2021-01-01 00:00:00 UTC".to_owned()
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_formats() {
+        let expiry = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        // PKCS#1 and SEC1, next to the PKCS#8 keys above
+        for (cert, cert_key) in [("rsa.pem", "rsa.key"), ("ec.pem", "ec.key")] {
+            assert!(
+                check_certificate(&certificate(cert, cert_key), expiry - Duration::days(30))
+                    .is_empty(),
+                "{cert_key}"
+            );
+        }
+        assert_eq!(
+            check_certificate(
+                &certificate("rsa.pem", "ec.key"),
+                expiry - Duration::days(30)
+            ),
+            [(
+                Severity::Error,
+                "the private key doesn't match the certificate".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_check_listeners() {
+        fn problems(listeners: &str) -> Vec<String> {
+            let config = include_str!("../../dev_config.ron").replace(
+                r#"listeners: [Tcp(addresses: ["127.0.0.1"], port: 3000, tls: false, ipv6_only: false)]"#,
+                &format!("listeners: {listeners}"),
+            );
+            let mut server = super::super::parse_with_vars(&config, []).unwrap().server;
+            server.acme = None;
+            let mut problems = Vec::new();
+            check_listeners(&server, &mut |_, setting: &str, message| {
+                problems.push(format!("{setting}: {message}"))
+            });
+            problems
+        }
+
+        assert!(problems(r#"[Tcp(addresses: ["0.0.0.0", "::"], port: 80, tls: false, ipv6_only: true), Systemd(name: "http", tls: false)]"#).is_empty());
+        assert_eq!(
+            problems("[]"),
+            ["server.listeners: no listeners, so no connections are accepted"]
+        );
+        assert_eq!(
+            problems(r#"[Tcp(addresses: ["0.0.0.0", "::"], port: 80, tls: false, ipv6_only: false)]"#),
+            ["server.listeners[0].ipv6_only: must be set to bind :: next to an ipv4 address on port 80"]
+        );
+        assert_eq!(
+            problems(
+                r#"[Tcp(addresses: ["::1", "::1"], port: 80, tls: false, ipv6_only: false), Unix(path: "/run/marzichat.sock"), Systemd(name: "https", tls: true)]"#
+            ),
+            [
+                "server.listeners[0].addresses: [::1]:80 is bound more than once",
+                "server.listeners[2]: serves https, which requires server.tls or server.acme",
+            ]
+        );
+    }
+}
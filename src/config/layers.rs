@@ -0,0 +1,738 @@
+//! Layering of environment variables and secret files over the config file.
+//!
+//! Every field of a config struct can be overridden by an environment variable
+//! named after its path, like `MARZICHAT__POSTGRES__URL` for the `url` of the
+//! `postgres` section. Strings are taken as they are, everything else is parsed
+//! as RON. Any field can also be read from a file instead, like a Docker secret
+//! or a systemd credential, by appending `_file` to its name, either in the
+//! config file or in the environment variable. A field is taken from the first
+//! of:
+//!
+//! 1. the file named by the `..._FILE` environment variable,
+//! 2. the file named by the `..._file` field,
+//! 3. the environment variable,
+//! 4. the field.
+//!
+//! Files can only set strings, and their trailing whitespace is trimmed.
+//!
+//! The values the layers set are recorded, so they can be
+//! [applied](Layers::apply) to the config file to compare the effective
+//! settings of two configs.
+
+use {
+    ron::{error::Position, Error},
+    serde::de::{
+        value::StringDeserializer, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor,
+    },
+    std::{
+        borrow::Cow,
+        cell::RefCell,
+        collections::{HashMap, HashSet},
+        fmt,
+        path::PathBuf,
+    },
+};
+
+/// The prefix of the environment variables overriding fields.
+pub const ENV_PREFIX: &str = "MARZICHAT__";
+
+const FILE_SUFFIX: &str = "_file";
+
+/// An error parsing the config.
+#[derive(Debug)]
+pub struct ParseError {
+    pub message: String,
+
+    /// The position in the config file the error occurred at, if it's caused
+    /// by the config file rather than an environment variable or a secret
+    /// file.
+    pub position: Option<Position>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some(Position { line, col }) => write!(formatter, "{line}:{col}: {}", self.message),
+            None => formatter.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// Returns the line of the config the error occurred at, with a marker
+    /// under the column.
+    pub fn snippet(&self, config: &str) -> Option<String> {
+        let Position { line, col } = self.position?;
+        let text = config.lines().nth(line.checked_sub(1)?)?;
+        let number = line.to_string();
+        let indent = " ".repeat(number.len());
+        // the column counts characters
+        let marker: String = text
+            .chars()
+            .take(col.saturating_sub(1))
+            .map(|character| if character == '\t' { '\t' } else { ' ' })
+            .collect();
+        Some(format!(
+            "{indent} |\n{number} | {text}\n{indent} | {marker}^"
+        ))
+    }
+}
+
+/// Where the value of a field comes from.
+#[derive(Clone, Debug)]
+enum Origin {
+    ConfigFile,
+    EnvVar(String),
+    SecretFile(PathBuf),
+}
+
+/// The environment variables and the state of deserializing a config.
+pub struct Layers {
+    /// The values of the environment variables by name without the prefix.
+    vars: HashMap<String, String>,
+
+    used_vars: RefCell<HashSet<String>>,
+
+    /// The paths of the fields set by the layers and the text they were set
+    /// to.
+    overrides: RefCell<Vec<(String, String)>>,
+
+    /// The path and origin of the field the first error occurred at. Errors
+    /// are recorded on their way up, so it's the innermost field.
+    error: RefCell<Option<(String, Origin)>>,
+}
+
+impl Layers {
+    /// Creates the layers of the environment variables with the prefix.
+    pub fn new(vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            vars: vars
+                .into_iter()
+                .filter_map(|(name, value)| {
+                    Some((name.strip_prefix(ENV_PREFIX)?.to_owned(), value))
+                })
+                .collect(),
+            used_vars: RefCell::default(),
+            overrides: RefCell::default(),
+            error: RefCell::default(),
+        }
+    }
+
+    /// Deserializes the config file with the layers on top of it.
+    pub fn deserialize<'de, T: serde::Deserialize<'de>>(
+        &'de self,
+        config: &'de str,
+    ) -> Result<T, ParseError> {
+        let mut deserializer = ron::Deserializer::from_str(config).map_err(|error| ParseError {
+            message: error.code.to_string(),
+            position: Some(error.position),
+        })?;
+        let result = T::deserialize(Layered {
+            inner: &mut deserializer,
+            path: String::new(),
+            layers: self,
+        })
+        .and_then(|value| deserializer.end().map(|()| value));
+        let value = result.map_err(|error| {
+            let error = deserializer.span_error(error);
+            let (message, position) = match self.error.take() {
+                None => (error.code.to_string(), Some(error.position)),
+                Some((path, Origin::ConfigFile)) => {
+                    (format!("{path}: {}", error.code), Some(error.position))
+                }
+                Some((path, Origin::EnvVar(name))) => (
+                    format!("{path}: {} (set by {ENV_PREFIX}{name})", error.code),
+                    None,
+                ),
+                Some((path, Origin::SecretFile(file))) => {
+                    (format!("{path}: {} (read from {file:?})", error.code), None)
+                }
+            };
+            ParseError { message, position }
+        })?;
+
+        let used_vars = self.used_vars.borrow();
+        if let Some(name) = self.vars.keys().find(|name| !used_vars.contains(*name)) {
+            return Err(ParseError {
+                message: format!("{ENV_PREFIX}{name} doesn't match a field of the config"),
+                position: None,
+            });
+        }
+        Ok(value)
+    }
+
+    /// Sets the fields the layers set while deserializing a config in the
+    /// config file parsed as a value, replacing their `_file` fields, so it
+    /// holds the effective settings.
+    pub fn apply(&self, config: &mut ron::Value) {
+        fn set(value: &mut ron::Value, path: &[&str], text: &str) {
+            use ron::Value;
+
+            match value {
+                Value::Option(Some(value)) => set(value, path, text),
+                Value::Map(map) => {
+                    let [field, rest @ ..] = path else {
+                        return;
+                    };
+                    let key = Value::String((*field).to_owned());
+                    if rest.is_empty() {
+                        map.remove(&Value::String(format!("{field}{FILE_SUFFIX}")));
+                        map.insert(key, Value::String(text.to_owned()));
+                        return;
+                    }
+                    if !map.keys().any(|existing| *existing == key) {
+                        map.insert(key.clone(), Value::Map(ron::Map::new()));
+                    }
+                    if let Some((_, value)) = map.iter_mut().find(|(existing, _)| **existing == key)
+                    {
+                        set(value, rest, text);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        for (path, text) in self.overrides.borrow().iter() {
+            set(config, &path.split('.').collect::<Vec<_>>(), text);
+        }
+    }
+
+    /// Returns the value of the environment variable of the field at the path.
+    fn var(&self, path: &str) -> Option<(String, &str)> {
+        let name = path.replace('.', "__").to_uppercase();
+        let value = self.vars.get(&name)?;
+        self.used_vars.borrow_mut().insert(name.clone());
+        Some((name, value))
+    }
+
+    fn record_override(&self, path: &str, text: &str) {
+        self.overrides
+            .borrow_mut()
+            .push((path.to_owned(), text.to_owned()));
+    }
+
+    fn record_error(&self, path: &str, origin: Origin) {
+        let mut error = self.error.borrow_mut();
+        if error.is_none() {
+            *error = Some((path.to_owned(), origin));
+        }
+    }
+}
+
+fn read_secret(file: PathBuf) -> Result<String, Error> {
+    std::fs::read_to_string(&file)
+        .map(|secret| secret.trim_end().to_owned())
+        .map_err(|error| Error::Message(format!("failed to read {file:?}: {error}")))
+}
+
+/// Forwards the methods of a deserializer that take only a visitor.
+macro_rules! forward {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+                self.inner.$method(visitor)
+            }
+        )*
+    };
+}
+
+/// Forwards the methods of a deserializer taking more than a visitor.
+macro_rules! forward_with_args {
+    () => {
+        fn deserialize_unit_struct<V: Visitor<'de>>(
+            self,
+            name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, D::Error> {
+            self.inner.deserialize_unit_struct(name, visitor)
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, D::Error> {
+            self.inner.deserialize_newtype_struct(name, visitor)
+        }
+
+        fn deserialize_tuple<V: Visitor<'de>>(
+            self,
+            len: usize,
+            visitor: V,
+        ) -> Result<V::Value, D::Error> {
+            self.inner.deserialize_tuple(len, visitor)
+        }
+
+        fn deserialize_tuple_struct<V: Visitor<'de>>(
+            self,
+            name: &'static str,
+            len: usize,
+            visitor: V,
+        ) -> Result<V::Value, D::Error> {
+            self.inner.deserialize_tuple_struct(name, len, visitor)
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            name: &'static str,
+            variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, D::Error> {
+            self.inner.deserialize_enum(name, variants, visitor)
+        }
+    };
+}
+
+/// A deserializer applying the layers to the fields of the structs it
+/// deserializes.
+struct Layered<'de, D> {
+    inner: D,
+
+    /// The dotted path of the value.
+    path: String,
+
+    layers: &'de Layers,
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Layered<'de, D> {
+    type Error = D::Error;
+
+    forward! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_unit deserialize_seq deserialize_map
+        deserialize_identifier deserialize_ignored_any
+    }
+
+    forward_with_args!();
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.inner.deserialize_option(OptionVisitor {
+            inner: visitor,
+            path: self.path,
+            layers: self.layers,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        let (path, layers) = (self.path.clone(), self.layers);
+        self.inner
+            .deserialize_struct(
+                name,
+                fields,
+                StructVisitor {
+                    inner: visitor,
+                    path: self.path,
+                    fields,
+                    layers,
+                },
+            )
+            .map_err(|error| {
+                layers.record_error(&path, Origin::ConfigFile);
+                error
+            })
+    }
+}
+
+struct LayeredSeed<'de, S> {
+    inner: S,
+    path: String,
+    layers: &'de Layers,
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for LayeredSeed<'de, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.inner.deserialize(Layered {
+            inner: deserializer,
+            path: self.path,
+            layers: self.layers,
+        })
+    }
+}
+
+struct OptionVisitor<'de, V> {
+    inner: V,
+    path: String,
+    layers: &'de Layers,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for OptionVisitor<'de, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.inner.visit_some(Layered {
+            inner: deserializer,
+            path: self.path,
+            layers: self.layers,
+        })
+    }
+}
+
+struct StructVisitor<'de, V> {
+    inner: V,
+    path: String,
+    fields: &'static [&'static str],
+    layers: &'de Layers,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for StructVisitor<'de, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_map(LayeredMap {
+            inner: map,
+            path: self.path,
+            fields: self.fields.iter(),
+            all_fields: self.fields,
+            layers: self.layers,
+            inner_done: false,
+            seen: HashSet::new(),
+            next: None,
+        })
+    }
+}
+
+/// Where the value of the next field is taken from.
+enum Source<'de> {
+    /// The value in the config file.
+    ConfigFile,
+
+    /// The secret file named by the `_file` field in the config file.
+    ConfigFileSecret,
+
+    EnvVar {
+        name: String,
+        value: &'de str,
+    },
+
+    /// The secret file named by the `_FILE` environment variable.
+    EnvVarSecret {
+        name: String,
+        file: &'de str,
+    },
+}
+
+struct LayeredMap<'de, A> {
+    inner: A,
+    path: String,
+
+    /// The fields that haven't been checked for environment variables after
+    /// the fields in the config file.
+    fields: std::slice::Iter<'static, &'static str>,
+    all_fields: &'static [&'static str],
+    layers: &'de Layers,
+    inner_done: bool,
+    seen: HashSet<String>,
+
+    /// The path of the next value, where it's taken from, and whether the
+    /// config file has a value for it.
+    next: Option<(String, Source<'de>, bool)>,
+}
+
+impl<'de, A: MapAccess<'de>> LayeredMap<'de, A> {
+    fn field_path(&self, field: &str) -> String {
+        if self.path.is_empty() {
+            field.to_owned()
+        } else {
+            format!("{}.{field}", self.path)
+        }
+    }
+
+    /// Returns where the value of the field is taken from, if anywhere besides
+    /// the config file.
+    fn layer(&self, path: &str) -> Option<Source<'de>> {
+        let layers = self.layers;
+        if let Some((name, file)) = layers.var(&format!("{path}{FILE_SUFFIX}")) {
+            return Some(Source::EnvVarSecret { name, file });
+        }
+        let (name, value) = layers.var(path)?;
+        Some(Source::EnvVar { name, value })
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for LayeredMap<'de, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        use serde::de::Error as _;
+
+        if !self.inner_done {
+            match self.inner.next_key_seed(KeyName)? {
+                Some(key) => {
+                    let (field, is_secret) = match key.strip_suffix(FILE_SUFFIX) {
+                        Some(field)
+                            if self.all_fields.contains(&field)
+                                && !self.all_fields.contains(&key.as_str()) =>
+                        {
+                            (field.to_owned(), true)
+                        }
+                        _ => (key, false),
+                    };
+                    let path = self.field_path(&field);
+                    if !self.seen.insert(field.clone()) {
+                        self.layers.record_error(&path, Origin::ConfigFile);
+                        return Err(A::Error::custom(format_args!(
+                            "`{field}` is set more than once"
+                        )));
+                    }
+                    let source = match self.layer(&path) {
+                        Some(source @ Source::EnvVarSecret { .. }) => source,
+                        _ if is_secret => Source::ConfigFileSecret,
+                        Some(source) => source,
+                        None => Source::ConfigFile,
+                    };
+                    let key = seed
+                        .deserialize(StringDeserializer::<Error>::new(field))
+                        .map_err(|error| {
+                            self.layers.record_error(&path, Origin::ConfigFile);
+                            A::Error::custom(error)
+                        })?;
+                    self.next = Some((path, source, true));
+                    return Ok(Some(key));
+                }
+                None => self.inner_done = true,
+            }
+        }
+
+        // fields only set by environment variables
+        while let Some(field) = self.fields.next() {
+            if self.seen.contains(*field) {
+                continue;
+            }
+            let path = self.field_path(field);
+            let Some(source) = self.layer(&path) else {
+                continue;
+            };
+            self.seen.insert((*field).to_owned());
+            self.next = Some((path, source, false));
+            return seed
+                .deserialize(StringDeserializer::<Error>::new((*field).to_owned()))
+                .map(Some)
+                .map_err(A::Error::custom);
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, A::Error> {
+        use serde::de::Error as _;
+
+        let (path, source, in_config_file) = self
+            .next
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        if in_config_file && !matches!(source, Source::ConfigFile | Source::ConfigFileSecret) {
+            self.inner.next_value::<IgnoredAny>()?;
+        }
+        let layers = self.layers;
+        match source {
+            Source::ConfigFile => self
+                .inner
+                .next_value_seed(LayeredSeed {
+                    inner: seed,
+                    path: path.clone(),
+                    layers,
+                })
+                .map_err(|error| {
+                    layers.record_error(&path, Origin::ConfigFile);
+                    error
+                }),
+            Source::ConfigFileSecret => {
+                let file: PathBuf = self.inner.next_value().map_err(|error| {
+                    layers.record_error(&format!("{path}{FILE_SUFFIX}"), Origin::ConfigFile);
+                    error
+                })?;
+                read_secret(file.clone())
+                    .and_then(|secret| {
+                        layers.record_override(&path, &secret);
+                        seed.deserialize(Text(Cow::Owned(secret)))
+                    })
+                    .map_err(|error| {
+                        layers.record_error(&path, Origin::SecretFile(file));
+                        A::Error::custom(error)
+                    })
+            }
+            Source::EnvVar { name, value } => {
+                layers.record_override(&path, value);
+                seed.deserialize(Text(Cow::Borrowed(value)))
+                    .map_err(|error| {
+                        layers.record_error(&path, Origin::EnvVar(name));
+                        A::Error::custom(error)
+                    })
+            }
+            Source::EnvVarSecret { name, file } => read_secret(PathBuf::from(file))
+                .and_then(|secret| {
+                    layers.record_override(&path, &secret);
+                    seed.deserialize(Text(Cow::Owned(secret)))
+                })
+                .map_err(|error| {
+                    layers.record_error(&path, Origin::EnvVar(format!("{name}_FILE")));
+                    A::Error::custom(error)
+                }),
+        }
+    }
+}
+
+/// Deserializes the name of a field.
+struct KeyName;
+
+impl<'de> DeserializeSeed<'de> for KeyName {
+    type Value = String;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<String, D::Error> {
+        deserializer.deserialize_identifier(KeyName)
+    }
+}
+
+impl Visitor<'_> for KeyName {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a field name")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<String, E> {
+        Ok(name.to_owned())
+    }
+}
+
+/// The value of an environment variable or a secret file. Strings are taken
+/// as they are, anything else is parsed as RON, which only environment
+/// variables can contain.
+struct Text<'de>(Cow<'de, str>);
+
+impl<'de> Text<'de> {
+    fn parse<T>(
+        self,
+        deserialize: impl FnOnce(&mut ron::Deserializer<'de>) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let Cow::Borrowed(text) = self.0 else {
+            return Err(Error::Message("files can only set strings".to_owned()));
+        };
+        let mut deserializer = ron::Deserializer::from_str(text).map_err(|error| error.code)?;
+        deserialize(&mut deserializer)
+            .and_then(|value| deserializer.end().map(|()| value))
+            .map_err(|error| Error::Message(deserializer.span_error(error).to_string()))
+    }
+
+    fn visit_text<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Cow::Borrowed(text) => visitor.visit_borrowed_str(text),
+            Cow::Owned(text) => visitor.visit_string(text),
+        }
+    }
+}
+
+/// Forwards the methods of a deserializer that take only a visitor to ron.
+macro_rules! parse {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                self.parse(|deserializer| deserializer.$method(visitor))
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Text<'de> {
+    type Error = Error;
+
+    parse! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_bytes deserialize_byte_buf
+        deserialize_unit deserialize_seq deserialize_map deserialize_ignored_any
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.visit_text(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.visit_text(visitor)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.visit_text(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let text = self.0.trim();
+        if text == "None" || text.starts_with("Some(") {
+            self.parse(|deserializer| deserializer.deserialize_option(visitor))
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.parse(|deserializer| deserializer.deserialize_unit_struct(name, visitor))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.parse(|deserializer| deserializer.deserialize_newtype_struct(name, visitor))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.parse(|deserializer| deserializer.deserialize_tuple(len, visitor))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.parse(|deserializer| deserializer.deserialize_tuple_struct(name, len, visitor))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.parse(|deserializer| deserializer.deserialize_struct(name, fields, visitor))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.parse(|deserializer| deserializer.deserialize_enum(name, variants, visitor))
+    }
+}
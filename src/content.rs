@@ -0,0 +1,260 @@
+//! User generated content module.
+//!
+//! Posts, comments and bios are written in Markdown. The rendered and
+//! sanitized html is stored alongside the Markdown so it's rendered once when
+//! written instead of on every page view. Posts and comments run through the
+//! [spam filter](crate::spam_filter) before they're stored.
+
+use {
+    crate::types::*,
+    leptos::*,
+    serde::{Deserialize, Serialize},
+};
+
+/// A post as shown to readers.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Post {
+    pub post_id: PostId,
+    pub created: DateTime,
+
+    /// The community the post was submitted to. Posts submitted before
+    /// communities existed don't belong to one.
+    pub community: Option<CommunityName>,
+
+    pub author: Username,
+    pub title: Title,
+    pub url: Option<String>,
+
+    /// The registrable domain of the url.
+    pub domain: Option<String>,
+
+    /// The url of the preview image of the linked page.
+    pub thumbnail_url: Option<String>,
+
+    pub comment_count: i64,
+    pub body_html: String,
+
+    /// Pinned posts are shown at the top of their community's front page.
+    pub pinned: bool,
+
+    /// Locked posts can't be commented on.
+    pub locked: bool,
+
+    /// Removed posts are hidden from lists and search, and their body and link
+    /// are only shown to their author and the moderators of their community.
+    pub removed: bool,
+
+    /// Held posts are only shown to their author until a moderator approves
+    /// them.
+    pub held: bool,
+}
+
+/// The number of posts per page.
+pub const POSTS_PER_PAGE: i64 = 30;
+
+/// A comment as shown to readers together with its replies.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub comment_id: CommentId,
+    pub created: DateTime,
+    pub author: Username,
+    pub body_html: String,
+
+    /// Removed comments are shown as a placeholder without their body so
+    /// their replies stay threaded.
+    pub removed: bool,
+
+    /// Held comments are only shown to their author until a moderator
+    /// approves them.
+    pub held: bool,
+
+    pub replies: Vec<Comment>,
+}
+
+/// Returns a post.
+#[tracing::instrument(skip_all)]
+#[server(GetPost, "/api")]
+pub async fn get_post(cx: Scope, post_id: PostId) -> Result<Option<Post>, ServerFnError> {
+    let viewer = crate::session::user_id(cx).await?;
+    Ok(crate::postgres::post(post_id, viewer).await?)
+}
+
+/// Returns a page of the newest posts, created before the given time if any.
+#[tracing::instrument(skip_all)]
+#[server(GetNewestPosts, "/api")]
+pub async fn get_newest_posts(
+    cx: Scope,
+    before: Option<DateTime>,
+) -> Result<Vec<Post>, ServerFnError> {
+    let viewer = crate::session::user_id(cx).await?;
+    Ok(crate::cache::newest_posts(before, viewer).await?)
+}
+
+/// Returns the comments of a post as a tree of replies.
+#[tracing::instrument(skip_all)]
+#[server(GetComments, "/api")]
+pub async fn get_comments(cx: Scope, post_id: PostId) -> Result<Vec<Comment>, ServerFnError> {
+    let viewer = crate::session::user_id(cx).await?;
+    Ok(thread(crate::postgres::comments(post_id, viewer).await?))
+}
+
+/// Submits a post to a community by the signed in user.
+#[tracing::instrument(skip_all)]
+#[server(SubmitPost, "/api")]
+pub async fn submit_post(
+    cx: Scope,
+    community: CommunityName,
+    title: String,
+    url: String,
+    body: String,
+) -> Result<(), ServerFnError> {
+    use crate::{
+        community::require_community_id,
+        link_preview, markdown, postgres,
+        rate_limit::{self, Policy},
+        sanctions, session, spam_filter,
+        types::validation::Validate,
+    };
+
+    let author = session::require_user_id(cx).await?;
+    rate_limit::check(cx, Policy::Post).await?;
+    let community_id = require_community_id(&community).await?;
+    sanctions::require_not_blocked_in(author, Some(community_id)).await?;
+
+    let title = Title(title);
+    title
+        .validate()
+        .map_err(|violations| ServerFnError::Args(violations.to_string()))?;
+
+    let url = (!url.trim().is_empty())
+        .then(|| link_preview::normalize(&url))
+        .transpose()
+        .map_err(|error| ServerFnError::Args(error.to_string()))?;
+    let domain = url.as_ref().and_then(link_preview::domain);
+    let url = url.map(String::from);
+
+    // the body is optional for link posts
+    let body = Text(body);
+    if body.0.len() > Text::MAX_BYTES {
+        return Err(ServerFnError::Args(text::Violation::TooLong.to_string()));
+    }
+
+    let text = [
+        title.0.as_str(),
+        url.as_deref().unwrap_or_default(),
+        &body.0,
+    ]
+    .join("\n");
+    let filtered = spam_filter::check(author, &text).await?;
+
+    let post_id = PostId(uuid::Uuid::new_v4());
+    postgres::create_post(
+        post_id,
+        community_id,
+        author,
+        &title,
+        url.as_deref(),
+        domain.as_deref(),
+        &body,
+        &markdown::render(&body.0),
+        &filtered,
+    )
+    .await?;
+    crate::cache::invalidate_front_page().await;
+    if let Some(url) = url {
+        link_preview::spawn(post_id, url);
+    }
+
+    leptos_actix::redirect(cx, &format!("/posts/{post_id}"));
+    Ok(())
+}
+
+/// Submits a comment on a post, or a reply to another comment, by the signed
+/// in user.
+#[tracing::instrument(skip_all)]
+#[server(SubmitComment, "/api")]
+pub async fn submit_comment(
+    cx: Scope,
+    post_id: PostId,
+    parent_id: Option<CommentId>,
+    body: String,
+) -> Result<(), ServerFnError> {
+    use crate::{
+        markdown, postgres,
+        rate_limit::{self, Policy},
+        sanctions, session, spam_filter,
+        types::validation::Validate,
+    };
+
+    let author = session::require_user_id(cx).await?;
+    rate_limit::check(cx, Policy::Comment).await?;
+    let community_id = postgres::post_subject(post_id)
+        .await?
+        .and_then(|post| post.community_id);
+    sanctions::require_not_blocked_in(author, community_id).await?;
+
+    let body = Text(body);
+    body.validate()
+        .map_err(|violations| ServerFnError::Args(violations.to_string()))?;
+
+    let filtered = spam_filter::check(author, &body.0).await?;
+
+    let comment_id = CommentId(uuid::Uuid::new_v4());
+    let created = postgres::create_comment(
+        comment_id,
+        post_id,
+        parent_id,
+        author,
+        &body,
+        &markdown::render(&body.0),
+        &filtered,
+    )
+    .await?;
+    if !created {
+        return Err(ServerFnError::Args(
+            "the post is locked or the parent comment doesn't belong to it".to_owned(),
+        ));
+    }
+    crate::cache::invalidate_front_page().await;
+    Ok(())
+}
+
+/// Updates the bio of the signed in user.
+#[tracing::instrument(skip_all)]
+#[server(UpdateBio, "/api")]
+pub async fn update_bio(cx: Scope, bio: String) -> Result<(), ServerFnError> {
+    use crate::{markdown, postgres, session};
+
+    let user_id = session::require_user_id(cx).await?;
+
+    let bio = Text(bio);
+    if bio.0.len() > Text::MAX_BYTES {
+        return Err(ServerFnError::Args(text::Violation::TooLong.to_string()));
+    }
+    postgres::update_bio(user_id, &bio, &markdown::render(&bio.0)).await?;
+    Ok(())
+}
+
+/// Arranges comments, given with the id of the comment they reply to, into a
+/// tree of replies.
+#[cfg(feature = "ssr")]
+fn thread(comments: Vec<(Option<CommentId>, Comment)>) -> Vec<Comment> {
+    use std::collections::HashMap;
+
+    fn replies(
+        children: &mut HashMap<Option<CommentId>, Vec<Comment>>,
+        parent: Option<CommentId>,
+    ) -> Vec<Comment> {
+        let mut comments = children.remove(&parent).unwrap_or_default();
+        for comment in &mut comments {
+            comment.replies = replies(children, Some(comment.comment_id));
+        }
+        comments
+    }
+
+    let mut children = HashMap::<_, Vec<_>>::new();
+    for (parent, comment) in comments {
+        children.entry(parent).or_default().push(comment);
+    }
+    replies(&mut children, None)
+}
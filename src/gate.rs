@@ -0,0 +1,120 @@
+//! Middleware restricting pages to users with a permission.
+#![cfg(feature = "ssr")]
+
+use {
+    actix_web::{
+        body::EitherBody,
+        dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+        Error, HttpResponse,
+    },
+    futures_util::future::{ok, LocalBoxFuture, Ready},
+    marzichat::{
+        permissions::{self, Permission},
+        session,
+    },
+    std::rc::Rc,
+};
+
+/// Middleware answering requests for a page, and the pages under it, with
+/// 403 Forbidden unless the signed in user has the permission, so the page is
+/// never rendered for other users.
+pub struct RequirePermission {
+    path: &'static str,
+    permission: Permission,
+}
+
+impl RequirePermission {
+    pub fn new(path: &'static str, permission: Permission) -> Self {
+        Self { path, permission }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequirePermission
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Error = Error;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    type InitError = ();
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Transform = RequirePermissionMiddleware<S>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequirePermissionMiddleware {
+            service: Rc::new(service),
+            path: self.path,
+            permission: self.permission,
+        })
+    }
+}
+
+pub struct RequirePermissionMiddleware<S> {
+    service: Rc<S>,
+    path: &'static str,
+    permission: Permission,
+}
+
+impl<S, B> Service<ServiceRequest> for RequirePermissionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = ServiceResponse<EitherBody<B>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if !is_under(req.path(), self.path) {
+            let res = self.service.call(req);
+            return Box::pin(async move { res.await.map(ServiceResponse::map_into_left_body) });
+        }
+
+        let service = Rc::clone(&self.service);
+        let permission = self.permission;
+        Box::pin(async move {
+            let allowed = match session::request_user_id(req.request()).await {
+                Ok(Some(user_id)) => permissions::has_permission(user_id, permission).await,
+                Ok(None) => Ok(false),
+                Err(error) => Err(error),
+            };
+            let response = match allowed {
+                Ok(true) => {
+                    return service
+                        .call(req)
+                        .await
+                        .map(ServiceResponse::map_into_left_body)
+                }
+                Ok(false) => HttpResponse::Forbidden().finish(),
+                Err(error) => {
+                    tracing::error!(?error, %permission, "failed to check the permission");
+                    HttpResponse::InternalServerError().finish()
+                }
+            };
+            Ok(req.into_response(response.map_into_right_body()))
+        })
+    }
+}
+
+/// Returns true if the path is the page or one under it.
+fn is_under(path: &str, page: &str) -> bool {
+    path.strip_prefix(page)
+        .map_or(false, |rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_under() {
+        assert!(is_under("/admin", "/admin"));
+        assert!(is_under("/admin/roles", "/admin"));
+        assert!(!is_under("/administrators", "/admin"));
+        assert!(!is_under("/", "/admin"));
+    }
+}
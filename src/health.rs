@@ -0,0 +1,121 @@
+//! Readiness checks of the services the server depends on.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::{keydb, postgres},
+    serde::Serialize,
+    std::{
+        future::Future,
+        time::{Duration, Instant},
+    },
+};
+
+/// The time after which a dependency is considered down.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The status of a dependency.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Up,
+    Down,
+
+    /// The dependency is optional and not configured.
+    NotConfigured,
+}
+
+/// The result of checking a dependency.
+#[derive(Clone, Debug, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+
+    /// The time the check took, if it ran.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_milliseconds: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The result of checking all dependencies.
+#[derive(Clone, Debug, Serialize)]
+pub struct Readiness {
+    /// True if no required dependency is down. Keydb is reported but not
+    /// required, since everything using it falls back to state local to the
+    /// instance.
+    pub ready: bool,
+
+    pub checks: Vec<Check>,
+}
+
+/// Checks all dependencies concurrently.
+pub async fn readiness() -> Readiness {
+    let (postgres, migrations, keydb) = tokio::join!(
+        check("postgres", postgres::ping()),
+        check("migrations", async {
+            match postgres::pending_migrations()
+                .await
+                .map_err(|error| error.to_string())?
+            {
+                0 => Ok(()),
+                pending => Err(format!("{pending} pending migrations")),
+            }
+        }),
+        async {
+            if !keydb::is_configured() {
+                return Check {
+                    name: "keydb",
+                    status: Status::NotConfigured,
+                    latency_milliseconds: None,
+                    error: None,
+                };
+            }
+            check(
+                "keydb",
+                keydb::run(|mut connection| async move {
+                    redis::cmd("PING")
+                        .query_async::<_, ()>(&mut connection)
+                        .await
+                }),
+            )
+            .await
+        },
+    );
+    Readiness {
+        ready: [&postgres, &migrations]
+            .iter()
+            .all(|check| check.status != Status::Down),
+        checks: vec![postgres, migrations, keydb],
+    }
+}
+
+/// Runs a check, failing it if it takes longer than [`CHECK_TIMEOUT`].
+async fn check<T, E: ToString>(
+    name: &'static str,
+    check: impl Future<Output = Result<T, E>>,
+) -> Check {
+    let start = Instant::now();
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(result) => result.map(drop).map_err(|error| error.to_string()),
+        Err(_) => Err("timed out".to_owned()),
+    };
+    let latency_milliseconds = Some(start.elapsed().as_millis() as u64);
+    match result {
+        Ok(()) => Check {
+            name,
+            status: Status::Up,
+            latency_milliseconds,
+            error: None,
+        },
+        Err(error) => {
+            tracing::warn!(name, error, "readiness check failed");
+            Check {
+                name,
+                status: Status::Down,
+                latency_milliseconds,
+                error: Some(error),
+            }
+        }
+    }
+}
@@ -367,6 +367,654 @@ impl Translations {
         "Veuillez saisir un mot de passe."
     );
 
+    translations!(
+        effective_as_of,
+        "Effective as of",
+        "Vigente desde el",
+        "Gültig ab",
+        "En vigueur depuis le"
+    );
+
+    translations!(
+        not_yet_translated,
+        "This document hasn't been translated into your language yet.",
+        "Este documento aún no se ha traducido a tu idioma.",
+        "Dieses Dokument wurde noch nicht in deine Sprache übersetzt.",
+        "Ce document n'a pas encore été traduit dans ta langue."
+    );
+
+    translations!(
+        new_version_published,
+        "A new version has been published: ",
+        "Se ha publicado una nueva versión: ",
+        "Eine neue Version wurde veröffentlicht: ",
+        "Une nouvelle version a été publiée : "
+    );
+
+    translations!(
+        please_review_and_accept,
+        ". Please review and accept it to continue.",
+        ". Por favor revísala y acéptala para continuar.",
+        ". Bitte lies sie und akzeptiere sie, um fortzufahren.",
+        ". Merci de la lire et de l'accepter pour continuer."
+    );
+
+    translations!(accept, "Accept", "Aceptar", "Akzeptieren", "Accepter");
+
+    translations!(submit, "Submit", "Enviar", "Einreichen", "Soumettre");
+
+    translations!(
+        submit_a_post,
+        "Submit a post",
+        "Enviar una publicación",
+        "Beitrag einreichen",
+        "Soumettre une publication"
+    );
+
+    translations!(title, "Title", "Título", "Titel", "Titre");
+
+    translations!(url, "URL", "URL", "URL", "URL");
+
+    translations!(text, "Text", "Texto", "Text", "Texte");
+
+    translations!(
+        markdown_supported,
+        "Links, emphasis, code, quotes and lists can be written in Markdown.",
+        "Puedes escribir enlaces, énfasis, código, citas y listas en Markdown.",
+        "Links, Hervorhebungen, Code, Zitate und Listen kannst du in Markdown schreiben.",
+        "Tu peux écrire les liens, l'emphase, le code, les citations et les listes en Markdown."
+    );
+
+    translations!(
+        add_comment,
+        "Add comment",
+        "Añadir comentario",
+        "Kommentieren",
+        "Ajouter un commentaire"
+    );
+
+    translations!(reply, "Reply", "Responder", "Antworten", "Répondre");
+
+    translations!(by, "by", "por", "von", "par");
+
+    translations!(
+        no_comments_yet,
+        "No comments yet.",
+        "Aún no hay comentarios.",
+        "Noch keine Kommentare.",
+        "Pas encore de commentaires."
+    );
+
+    translations!(
+        post_not_found,
+        "Post not found.",
+        "Publicación no encontrada.",
+        "Beitrag nicht gefunden.",
+        "Publication introuvable."
+    );
+
+    translations!(
+        comments,
+        "comments",
+        "comentarios",
+        "Kommentare",
+        "commentaires"
+    );
+
+    translations!(discuss, "discuss", "comentar", "diskutieren", "discuter");
+
+    translations!(more, "More", "Más", "Mehr", "Plus");
+
+    translations!(search, "Search", "Buscar", "Suchen", "Rechercher");
+
+    translations!(author, "Author", "Autor", "Autor", "Auteur");
+
+    translations!(from, "From", "Desde", "Von", "Du");
+
+    translations!(to, "To", "Hasta", "Bis", "Au");
+
+    translations!(
+        no_results,
+        "No results.",
+        "No hay resultados.",
+        "Keine Ergebnisse.",
+        "Aucun résultat."
+    );
+
+    translations!(
+        communities,
+        "Communities",
+        "Comunidades",
+        "Communitys",
+        "Communautés"
+    );
+
+    translations!(
+        community,
+        "Community",
+        "Comunidad",
+        "Community",
+        "Communauté"
+    );
+
+    translations!(
+        create_community,
+        "Create a community",
+        "Crear una comunidad",
+        "Community erstellen",
+        "Créer une communauté"
+    );
+
+    translations!(name, "Name", "Nombre", "Name", "Nom");
+
+    translations!(
+        description,
+        "Description",
+        "Descripción",
+        "Beschreibung",
+        "Description"
+    );
+
+    translations!(rules, "Rules", "Reglas", "Regeln", "Règles");
+
+    translations!(
+        one_rule_per_line,
+        "One rule per line.",
+        "Una regla por línea.",
+        "Eine Regel pro Zeile.",
+        "Une règle par ligne."
+    );
+
+    translations!(
+        subscribers,
+        "subscribers",
+        "suscriptores",
+        "Abonnenten",
+        "abonnés"
+    );
+
+    translations!(
+        moderators,
+        "Moderators",
+        "Moderadores",
+        "Moderatoren",
+        "Modérateurs"
+    );
+
+    translations!(
+        subscribe,
+        "Subscribe",
+        "Suscribirse",
+        "Abonnieren",
+        "S'abonner"
+    );
+
+    translations!(
+        unsubscribe,
+        "Unsubscribe",
+        "Cancelar suscripción",
+        "Abbestellen",
+        "Se désabonner"
+    );
+
+    translations!(save, "Save", "Guardar", "Speichern", "Enregistrer");
+
+    translations!(
+        add_moderator,
+        "Add moderator",
+        "Añadir moderador",
+        "Moderator hinzufügen",
+        "Ajouter un modérateur"
+    );
+
+    translations!(pin, "Pin", "Fijar", "Anheften", "Épingler");
+
+    translations!(unpin, "Unpin", "Desfijar", "Lösen", "Désépingler");
+
+    translations!(pinned, "Pinned", "Fijado", "Angeheftet", "Épinglé");
+
+    translations!(lock, "Lock", "Bloquear", "Sperren", "Verrouiller");
+
+    translations!(
+        unlock,
+        "Unlock",
+        "Desbloquear",
+        "Entsperren",
+        "Déverrouiller"
+    );
+
+    translations!(remove, "Remove", "Eliminar", "Entfernen", "Retirer");
+
+    translations!(
+        post_removed,
+        "This post was removed by a moderator.",
+        "Un moderador eliminó esta publicación.",
+        "Dieser Beitrag wurde von einem Moderator entfernt.",
+        "Cette publication a été retirée par un modérateur."
+    );
+
+    translations!(
+        post_locked,
+        "This post is locked. New comments can't be added.",
+        "Esta publicación está bloqueada. No se pueden añadir comentarios.",
+        "Dieser Beitrag ist gesperrt. Es können keine Kommentare hinzugefügt werden.",
+        "Cette publication est verrouillée. Tu ne peux plus la commenter."
+    );
+
+    translations!(
+        community_not_found,
+        "Community not found.",
+        "Comunidad no encontrada.",
+        "Community nicht gefunden.",
+        "Communauté introuvable."
+    );
+
+    translations!(report, "Report", "Reportar", "Melden", "Signaler");
+
+    translations!(
+        report_user,
+        "Report user",
+        "Reportar usuario",
+        "Nutzer melden",
+        "Signaler l'utilisateur"
+    );
+
+    translations!(reason, "Reason", "Motivo", "Grund", "Motif");
+
+    translations!(details, "Details", "Detalles", "Details", "Détails");
+
+    translations!(
+        report_submitted,
+        "Thanks, the moderators will review your report.",
+        "Gracias, los moderadores revisarán tu reporte.",
+        "Danke, die Moderatoren werden deine Meldung prüfen.",
+        "Merci, les modérateurs vont examiner ton signalement."
+    );
+
+    translations!(spam, "Spam", "Spam", "Spam", "Spam");
+
+    translations!(
+        harassment,
+        "Harassment",
+        "Acoso",
+        "Belästigung",
+        "Harcèlement"
+    );
+
+    translations!(
+        hate_speech,
+        "Hate speech",
+        "Discurso de odio",
+        "Hassrede",
+        "Discours haineux"
+    );
+
+    translations!(violence, "Violence", "Violencia", "Gewalt", "Violence");
+
+    translations!(
+        misinformation,
+        "Misinformation",
+        "Desinformación",
+        "Falschinformation",
+        "Désinformation"
+    );
+
+    translations!(
+        rule_violation,
+        "Breaks a community rule",
+        "Infringe una regla de la comunidad",
+        "Verstößt gegen eine Community-Regel",
+        "Enfreint une règle de la communauté"
+    );
+
+    translations!(other, "Other", "Otro", "Sonstiges", "Autre");
+
+    translations!(approve, "Approve", "Aprobar", "Freigeben", "Approuver");
+
+    translations!(ban, "Ban", "Vetar", "Sperren", "Bannir");
+
+    translations!(warn, "Warn", "Advertir", "Verwarnen", "Avertir");
+
+    translations!(
+        moderation_queue,
+        "Moderation queue",
+        "Cola de moderación",
+        "Moderationswarteschlange",
+        "File de modération"
+    );
+
+    translations!(
+        no_open_reports,
+        "No open reports.",
+        "No hay reportes abiertos.",
+        "Keine offenen Meldungen.",
+        "Aucun signalement ouvert."
+    );
+
+    translations!(
+        reported_by,
+        "reported by",
+        "reportado por",
+        "gemeldet von",
+        "signalé par"
+    );
+
+    translations!(
+        moderation_notices,
+        "Moderation notices",
+        "Avisos de moderación",
+        "Moderationshinweise",
+        "Avis de modération"
+    );
+
+    translations!(
+        no_moderation_notices,
+        "No moderation actions were taken on your content.",
+        "No se tomaron acciones de moderación sobre tu contenido.",
+        "Es wurden keine Moderationsmaßnahmen zu deinen Inhalten ergriffen.",
+        "Aucune action de modération n'a été prise sur ton contenu."
+    );
+
+    translations!(
+        post_was_removed,
+        "Your post was removed",
+        "Tu publicación fue eliminada",
+        "Dein Beitrag wurde entfernt",
+        "Ta publication a été retirée"
+    );
+
+    translations!(
+        comment_was_removed,
+        "Your comment was removed",
+        "Tu comentario fue eliminado",
+        "Dein Kommentar wurde entfernt",
+        "Ton commentaire a été retiré"
+    );
+
+    translations!(
+        admin,
+        "Admin",
+        "Administración",
+        "Verwaltung",
+        "Administration"
+    );
+
+    translations!(
+        forbidden,
+        "You don't have permission to view this page.",
+        "No tienes permiso para ver esta página.",
+        "Du hast keine Berechtigung, diese Seite anzusehen.",
+        "Tu n'as pas la permission de voir cette page."
+    );
+
+    translations!(roles, "Roles", "Roles", "Rollen", "Rôles");
+
+    translations!(role, "Role", "Rol", "Rolle", "Rôle");
+
+    translations!(
+        grant_role,
+        "Grant role",
+        "Asignar rol",
+        "Rolle vergeben",
+        "Attribuer le rôle"
+    );
+
+    translations!(revoke, "Revoke", "Revocar", "Entziehen", "Retirer");
+
+    translations!(
+        access_admin,
+        "Access the admin pages",
+        "Acceder a la administración",
+        "Verwaltung aufrufen",
+        "Accéder à l'administration"
+    );
+
+    translations!(
+        manage_roles,
+        "Manage roles",
+        "Gestionar roles",
+        "Rollen verwalten",
+        "Gérer les rôles"
+    );
+
+    translations!(
+        moderate_all_communities,
+        "Moderate all communities",
+        "Moderar todas las comunidades",
+        "Alle Communitys moderieren",
+        "Modérer toutes les communautés"
+    );
+
+    translations!(
+        you_were_banned,
+        "You were banned from",
+        "Se te vetó de",
+        "Du wurdest gesperrt in",
+        "Tu as été banni de"
+    );
+
+    translations!(
+        you_were_warned,
+        "You were warned",
+        "Recibiste una advertencia",
+        "Du wurdest verwarnt",
+        "Tu as reçu un avertissement"
+    );
+
+    translations!(
+        comment_removed,
+        "[removed]",
+        "[eliminado]",
+        "[entfernt]",
+        "[retiré]"
+    );
+
+    translations!(
+        suspend,
+        "Suspend",
+        "Suspender",
+        "Sperren auf Zeit",
+        "Suspendre"
+    );
+
+    translations!(
+        shadow_ban,
+        "Shadow-ban",
+        "Veto invisible",
+        "Shadowban",
+        "Bannissement invisible"
+    );
+
+    translations!(lift, "Lift", "Levantar", "Aufheben", "Lever");
+
+    translations!(
+        suspension,
+        "Suspension",
+        "Suspensión",
+        "Zeitweise Sperre",
+        "Suspension"
+    );
+
+    translations!(
+        permanent_ban,
+        "Permanent ban",
+        "Veto permanente",
+        "Dauerhafte Sperre",
+        "Bannissement définitif"
+    );
+
+    translations!(
+        sanctions,
+        "Sanctions",
+        "Sanciones",
+        "Sanktionen",
+        "Sanctions"
+    );
+
+    translations!(
+        sanction_user,
+        "Sanction user",
+        "Sancionar usuario",
+        "Nutzer sanktionieren",
+        "Sanctionner l'utilisateur"
+    );
+
+    translations!(
+        no_sanctions,
+        "No sanctions in effect.",
+        "No hay sanciones vigentes.",
+        "Keine Sanktionen in Kraft.",
+        "Aucune sanction en vigueur."
+    );
+
+    translations!(
+        duration_in_days,
+        "Duration in days",
+        "Duración en días",
+        "Dauer in Tagen",
+        "Durée en jours"
+    );
+
+    translations!(until, "Until", "Hasta", "Bis", "Jusqu'au");
+
+    translations!(
+        issued_by,
+        "Issued by",
+        "Emitida por",
+        "Verhängt von",
+        "Émise par"
+    );
+
+    translations!(
+        sanction_users,
+        "Suspend, ban and shadow-ban users site-wide",
+        "Suspender, vetar y vetar de forma invisible a usuarios en todo el sitio",
+        "Nutzer seitenweit sperren, auf Zeit sperren und shadowbannen",
+        "Suspendre, bannir et bannir de manière invisible des utilisateurs sur tout le site"
+    );
+
+    translations!(
+        account_suspended,
+        "Your account is suspended.",
+        "Tu cuenta está suspendida.",
+        "Dein Konto ist zeitweise gesperrt.",
+        "Ton compte est suspendu."
+    );
+
+    translations!(
+        account_banned,
+        "Your account is banned.",
+        "Tu cuenta está vetada.",
+        "Dein Konto ist gesperrt.",
+        "Ton compte est banni."
+    );
+
+    translations!(
+        community_suspended,
+        "You are suspended from posting and commenting in this community.",
+        "Estás suspendido de publicar y comentar en esta comunidad.",
+        "Du bist zeitweise vom Posten und Kommentieren in dieser Community gesperrt.",
+        "Tu es suspendu de publication et de commentaire dans cette communauté."
+    );
+
+    translations!(
+        community_banned,
+        "You are banned from posting and commenting in this community.",
+        "Tienes vetado publicar y comentar en esta comunidad.",
+        "Du bist vom Posten und Kommentieren in dieser Community gesperrt.",
+        "Tu es banni de publication et de commentaire dans cette communauté."
+    );
+
+    translations!(
+        you_were_suspended_from,
+        "You were suspended from",
+        "Se te suspendió de",
+        "Du wurdest zeitweise gesperrt in",
+        "Tu as été suspendu de"
+    );
+
+    translations!(
+        your_account_was_suspended,
+        "Your account was suspended",
+        "Tu cuenta fue suspendida",
+        "Dein Konto wurde zeitweise gesperrt",
+        "Ton compte a été suspendu"
+    );
+
+    translations!(
+        your_account_was_banned,
+        "Your account was banned",
+        "Tu cuenta fue vetada",
+        "Dein Konto wurde gesperrt",
+        "Ton compte a été banni"
+    );
+
+    translations!(
+        banned_word,
+        "Banned word",
+        "Palabra prohibida",
+        "Verbotenes Wort",
+        "Mot interdit"
+    );
+
+    translations!(
+        banned_pattern,
+        "Banned pattern",
+        "Patrón prohibido",
+        "Verbotenes Muster",
+        "Motif interdit"
+    );
+
+    translations!(
+        link_limit,
+        "Too many links from a new account",
+        "Demasiados enlaces de una cuenta nueva",
+        "Zu viele Links von einem neuen Konto",
+        "Trop de liens d'un nouveau compte"
+    );
+
+    translations!(
+        duplicate_content,
+        "Duplicate content",
+        "Contenido duplicado",
+        "Doppelter Inhalt",
+        "Contenu en double"
+    );
+
+    translations!(
+        low_reputation,
+        "Low reputation",
+        "Reputación baja",
+        "Geringe Reputation",
+        "Réputation faible"
+    );
+
+    translations!(
+        held_content,
+        "Held content",
+        "Contenido retenido",
+        "Zurückgehaltene Inhalte",
+        "Contenu retenu"
+    );
+
+    translations!(
+        no_held_content,
+        "No held content.",
+        "No hay contenido retenido.",
+        "Keine zurückgehaltenen Inhalte.",
+        "Aucun contenu retenu."
+    );
+
+    translations!(
+        held_for_review,
+        "Held for review by the moderators. Only you can see it for now.",
+        "Retenido para revisión por los moderadores. Por ahora solo tú puedes verlo.",
+        "Zur Prüfung durch die Moderatoren zurückgehalten. Vorerst kannst nur du es sehen.",
+        "Retenu pour examen par les modérateurs. Pour l'instant, toi seul peux le voir."
+    );
+
+    translations!(held, "Held", "Retenido", "Zurückgehalten", "Retenu");
+
     /// Create a new `Translations` instance for the given `Language`.
     pub fn for_language(language: Language) -> Self {
         Self(language)
@@ -0,0 +1,103 @@
+//! Keydb connection shared by the server instances.
+//!
+//! Keydb is optional: callers fall back to state local to the instance while
+//! it's not configured or unavailable. Connecting and every command time out
+//! after the configured timeout, and after a failure no connection is attempted
+//! for a while so requests don't all wait for the timeout during an outage.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::config::KeydbConfig,
+    redis::{aio::ConnectionManager, Client, RedisError},
+    std::{
+        future::Future,
+        sync::{Mutex, OnceLock},
+        time::{Duration, Instant},
+    },
+    tokio::sync::OnceCell,
+};
+
+/// The interval after a failure during which no connection is attempted.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The error type for keydb.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("keydb is not configured")]
+    NotConfigured,
+
+    #[error("keydb is unavailable")]
+    Unavailable,
+
+    #[error("keydb timed out")]
+    Timeout,
+
+    #[error("keydb error: {0}")]
+    Redis(#[from] RedisError),
+}
+
+struct Keydb {
+    client: Client,
+    timeout: Duration,
+    connection: OnceCell<ConnectionManager>,
+    last_failure: Mutex<Option<Instant>>,
+}
+
+static KEYDB: OnceLock<Keydb> = OnceLock::new();
+
+/// Sets the keydb instance to connect to. The connection is established on
+/// first use.
+pub fn init(config: KeydbConfig) -> Result<(), Error> {
+    let keydb = Keydb {
+        client: Client::open(config.url)?,
+        timeout: Duration::from_millis(config.timeout_milliseconds.get()),
+        connection: OnceCell::new(),
+        last_failure: Mutex::new(None),
+    };
+    if KEYDB.set(keydb).is_err() {
+        panic!("keydb already initialized");
+    }
+    Ok(())
+}
+
+/// Returns true if keydb is configured.
+pub fn is_configured() -> bool {
+    KEYDB.get().is_some()
+}
+
+/// Runs a command with a connection to keydb, timing out after the configured
+/// timeout.
+pub async fn run<T, F, Fut>(command: F) -> Result<T, Error>
+where
+    F: FnOnce(ConnectionManager) -> Fut,
+    Fut: Future<Output = Result<T, RedisError>>,
+{
+    let keydb = KEYDB.get().ok_or(Error::NotConfigured)?;
+    let last_failure = *keydb.last_failure.lock().expect("poisoned lock");
+    if last_failure.is_some_and(|last_failure| last_failure.elapsed() < RETRY_INTERVAL) {
+        return Err(Error::Unavailable);
+    }
+
+    let result = tokio::time::timeout(keydb.timeout, async {
+        let connection = keydb
+            .connection
+            .get_or_try_init(|| ConnectionManager::new(keydb.client.clone()))
+            .await?;
+        command(connection.clone()).await
+    })
+    .await
+    .map_err(|_| Error::Timeout)
+    .and_then(|result| result.map_err(Error::from));
+
+    let mut last_failure = keydb.last_failure.lock().expect("poisoned lock");
+    match &result {
+        Ok(_) if last_failure.take().is_some() => tracing::info!("keydb is available again"),
+        Err(error) if last_failure.is_none() => {
+            tracing::warn!(?error, "keydb is unavailable");
+            *last_failure = Some(Instant::now());
+        }
+        Err(_) => *last_failure = Some(Instant::now()),
+        Ok(_) => (),
+    }
+    result
+}
@@ -0,0 +1,242 @@
+//! Legal documents module.
+//!
+//! The terms and conditions and the privacy policy are stored as Markdown in
+//! the `legal` directory with one folder per document and version and one file
+//! per language, e.g. `legal/privacy-policy/2024-01-01/en-US.md`. A version is
+//! identified by the date it takes effect. Users accept a specific version and
+//! are asked to accept again once a newer version is published.
+
+use {
+    crate::{
+        internationalization::{Language, Translations},
+        routes::{PRIVACY_POLICY, TERMS_AND_CONDITIONS},
+    },
+    chrono::NaiveDate,
+    derive_more::Display,
+    leptos::*,
+    serde::{Deserialize, Serialize},
+};
+
+/// A legal document that users have to accept.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(type_name = "legal_document"))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize, Display, Hash)]
+pub enum Document {
+    TermsAndConditions,
+    PrivacyPolicy,
+}
+
+/// A list of all legal documents.
+pub const DOCUMENTS: &[Document] = &[Document::TermsAndConditions, Document::PrivacyPolicy];
+
+impl Document {
+    /// The localized title of the document.
+    pub fn title(self, t: Translations) -> &'static str {
+        match self {
+            Document::TermsAndConditions => t.terms_and_conditions(),
+            Document::PrivacyPolicy => t.privacy_policy(),
+        }
+    }
+
+    /// The route the document is served at.
+    pub const fn path(self) -> &'static str {
+        match self {
+            Document::TermsAndConditions => TERMS_AND_CONDITIONS,
+            Document::PrivacyPolicy => PRIVACY_POLICY,
+        }
+    }
+}
+
+/// A published version of a legal document.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize, Hash)]
+pub struct Version {
+    pub document: Document,
+
+    /// The date the version takes effect.
+    pub effective: NaiveDate,
+}
+
+/// A legal document rendered to html.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Rendered {
+    pub version: Version,
+
+    /// The language the document is written in, which is English if the
+    /// document has not been translated into the requested language yet.
+    pub language: Language,
+
+    pub html: String,
+}
+
+/// Returns the current version of a legal document rendered in the requested
+/// language.
+#[tracing::instrument(skip_all)]
+#[server(GetLegalDocument, "/api")]
+pub async fn get_legal_document(
+    document: Document,
+    language: Language,
+) -> Result<Rendered, ServerFnError> {
+    Ok(source::render(document, language))
+}
+
+/// Returns the current versions of the legal documents the signed in user
+/// hasn't accepted yet.
+#[tracing::instrument(skip_all)]
+#[server(PendingLegalDocuments, "/api")]
+pub async fn pending_legal_documents(cx: Scope) -> Result<Vec<Version>, ServerFnError> {
+    let Some(user_id) = crate::session::user_id(cx).await? else {
+        return Ok(Vec::new());
+    };
+    let accepted = crate::postgres::accepted_legal_versions(user_id).await?;
+    Ok(current_versions()
+        .into_iter()
+        .filter(|version| !accepted.contains(version))
+        .collect())
+}
+
+/// Records that the signed in user accepted a version of a legal document.
+#[tracing::instrument(skip_all)]
+#[server(AcceptLegalDocument, "/api")]
+pub async fn accept_legal_document(
+    cx: Scope,
+    document: Document,
+    effective: NaiveDate,
+) -> Result<(), ServerFnError> {
+    let user_id = crate::session::require_user_id(cx).await?;
+    let version = Version {
+        document,
+        effective,
+    };
+    if version != source::current_version(document) {
+        return Err(ServerFnError::Args(format!(
+            "{effective} is not the current version of the {document}"
+        )));
+    }
+    crate::postgres::accept_legal_versions(user_id, &[version]).await?;
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub use source::current_versions;
+
+#[cfg(feature = "ssr")]
+mod source {
+    //! The Markdown sources of every published version, compiled into the
+    //! binary.
+
+    use {
+        super::{Document, Rendered, Version, DOCUMENTS},
+        crate::{
+            internationalization::Language, COMPANY_NAME, PRODUCT_NAME, SITE_URL, SUPPORT_EMAIL,
+        },
+        chrono::NaiveDate,
+    };
+
+    /// A version of a legal document in a single language.
+    struct Source {
+        document: Document,
+        effective: &'static str,
+        language: Language,
+        markdown: &'static str,
+    }
+
+    /// All published versions. Add new versions and translations here.
+    const SOURCES: &[Source] = &[
+        Source {
+            document: Document::TermsAndConditions,
+            effective: "2024-01-01",
+            language: Language::English,
+            markdown: include_str!("../legal/terms-and-conditions/2024-01-01/en-US.md"),
+        },
+        Source {
+            document: Document::PrivacyPolicy,
+            effective: "2024-01-01",
+            language: Language::English,
+            markdown: include_str!("../legal/privacy-policy/2024-01-01/en-US.md"),
+        },
+    ];
+
+    impl Source {
+        fn version(&self) -> Version {
+            Version {
+                document: self.document,
+                effective: NaiveDate::parse_from_str(self.effective, "%Y-%m-%d")
+                    .expect("invalid legal document version"),
+            }
+        }
+    }
+
+    /// Returns the current version of a legal document.
+    pub fn current_version(document: Document) -> Version {
+        SOURCES
+            .iter()
+            .filter(|source| source.document == document)
+            .map(Source::version)
+            .max_by_key(|version| version.effective)
+            .expect("legal document has no published version")
+    }
+
+    /// Returns the current versions of all legal documents.
+    pub fn current_versions() -> Vec<Version> {
+        DOCUMENTS.iter().copied().map(current_version).collect()
+    }
+
+    /// Renders the current version of a legal document, falling back to
+    /// English if it hasn't been translated into the language.
+    pub fn render(document: Document, language: Language) -> Rendered {
+        let version = current_version(document);
+        let sources = || {
+            SOURCES
+                .iter()
+                .filter(move |source| source.version() == version)
+        };
+        let source = sources()
+            .find(|source| source.language == language)
+            .or_else(|| sources().find(|source| source.language == Language::English))
+            .expect("legal document has no English version");
+
+        let markdown = source
+            .markdown
+            .replace("{PRODUCT_NAME}", PRODUCT_NAME)
+            .replace("{COMPANY_NAME}", COMPANY_NAME)
+            .replace("{SITE_URL}", SITE_URL)
+            .replace("{SUPPORT_EMAIL}", SUPPORT_EMAIL);
+
+        let mut html = String::with_capacity(markdown.len() * 3 / 2);
+        pulldown_cmark::html::push_html(&mut html, pulldown_cmark::Parser::new(&markdown));
+
+        Rendered {
+            version,
+            language: source.language,
+            html,
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn test_every_version_has_an_english_source() {
+            for source in SOURCES {
+                let version = source.version();
+                assert!(
+                    SOURCES
+                        .iter()
+                        .any(|other| other.version() == version
+                            && other.language == Language::English)
+                );
+            }
+        }
+
+        #[test]
+        fn test_render_current_versions() {
+            for &document in DOCUMENTS {
+                let rendered = render(document, Language::German);
+                assert_eq!(rendered.version, current_version(document));
+                assert!(!rendered.html.contains("{PRODUCT_NAME}"));
+                assert!(rendered.html.contains("<h2>"));
+            }
+        }
+    }
+}
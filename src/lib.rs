@@ -23,15 +23,43 @@ use {
     leptos_meta::*,
     leptos_router::*,
     routes::{
-        not_found::*, privacy::*, signin::*, signup::*, stories::*, story::*, terms::*, users::*,
+        admin::*, communities::*, community::*, moderation::*, not_found::*, post::*, posts::*,
+        privacy::*, search::*, signin::*, signup::*, stories::*, story::*, submit::*, terms::*,
+        users::*,
     },
     std::sync::OnceLock,
 };
 
+pub mod acme;
+pub mod address;
 pub mod api;
+pub mod breached_passwords;
+pub mod cache;
+pub mod community;
 pub mod components;
+pub mod config;
+pub mod content;
+pub mod health;
 pub mod internationalization;
+pub mod keydb;
+pub mod legal;
+pub mod link_preview;
+pub mod mailer;
+pub mod markdown;
+pub mod metrics;
+pub mod moderation;
+pub mod monitor;
+pub mod permissions;
+pub mod postgres;
+pub mod rate_limit;
 pub mod routes;
+pub mod sanctions;
+pub mod search;
+pub mod session;
+pub mod sign_in;
+pub mod spam_filter;
+pub mod telemetry;
+pub mod tls;
 pub mod types;
 
 include!(concat!(env!("OUT_DIR"), "/info.rs"));
@@ -63,11 +91,22 @@ pub fn App(cx: Scope) -> impl IntoView {
         <Meta name="description" content="The best forum on the internet."/>
         <Router>
             <Nav/>
+            <LegalUpdates/>
             <div class="container-xl p-3 pt-8">
                 <Routes>
                     <Route path=HOME view=Stories/>
                     <Route path="users/:id" view=User/>
                     <Route path="stories/:id" view=Story/>
+                    <Route path=POSTS view=Posts/>
+                    <Route path="posts/:id" view=Post/>
+                    <Route path=SUBMIT view=Submit/>
+                    <Route path=SEARCH view=Search/>
+                    <Route path=COMMUNITIES view=Communities/>
+                    <Route path="c/:name" view=Community/>
+                    <Route path=CREATE_COMMUNITY view=NewCommunity/>
+                    <Route path=MODERATION_QUEUE view=ModerationQueue/>
+                    <Route path=MODERATION_NOTICES view=ModerationNotices/>
+                    <Route path=ADMIN view=Admin/>
 
                     <Route path=SIGNIN view=Signin/>
                     <Route path=SIGNUP view=Signup/>
@@ -1,70 +1,153 @@
-//! Rate limiter based on actix governor.
+//! Middleware applying the [rate limits](rate_limit) to requests.
 #![cfg(feature = "ssr")]
 
 use {
-    crate::{address, config::RateLimiterConfig},
-    actix_governor::{
-        governor::{
-            clock::{Clock, DefaultClock, QuantaInstant},
-            middleware::StateInformationMiddleware,
-            NotUntil,
-        },
-        Governor, GovernorConfigBuilder, KeyExtractor, SimpleKeyExtractionError,
-    },
     actix_web::{
-        dev::ServiceRequest,
-        http::{header::ContentType, StatusCode},
-        HttpResponse, HttpResponseBuilder,
+        body::EitherBody,
+        dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+        http::header::{ContentType, HeaderMap, HeaderName, HeaderValue, RETRY_AFTER},
+        Error, HttpResponse,
+    },
+    futures_util::future::{ok, LocalBoxFuture, Ready},
+    marzichat::{
+        config::RateLimiterConfig,
+        rate_limit::{self, Policy},
     },
-    std::net::IpAddr,
+    std::rc::Rc,
 };
 
-/// Returns a new rate limiter governor layer
-pub fn layer(
-    config: RateLimiterConfig,
-) -> Governor<IpAddressExtractor, StateInformationMiddleware> {
-    Governor::new(
-        &GovernorConfigBuilder::default()
-            .per_second(config.replenish_interval_seconds.get())
-            .burst_size(config.burst_size.get())
-            .key_extractor(IpAddressExtractor)
-            .use_headers()
-            .finish()
-            .expect("invalid governor configuration"),
-    )
+/// The header with the number of requests allowed in a burst.
+const LIMIT: &str = "x-ratelimit-limit";
+
+/// The header with the number of requests that can still be made right away.
+const REMAINING: &str = "x-ratelimit-remaining";
+
+/// The header with the seconds to wait after exceeding the limit.
+const AFTER: &str = "x-ratelimit-after";
+
+/// The rate limit a middleware applies.
+#[derive(Clone, Copy, Debug)]
+enum Limit {
+    /// The limit of all requests per ip address.
+    IpAddress,
+
+    Policy(Policy),
+}
+
+/// Sets up the rate limits of the config and returns the rate limiter layer
+/// limiting all requests per ip address. Must be called once, the layer is
+/// cloned for every worker.
+pub fn layer(config: RateLimiterConfig) -> Limiter {
+    rate_limit::init(&config);
+    Limiter(Limit::IpAddress)
+}
+
+/// Returns middleware applying a rate limit policy to all requests of the
+/// routes it wraps.
+pub fn policy(policy: Policy) -> Limiter {
+    Limiter(Limit::Policy(policy))
 }
 
-/// The ip address extractor used for rate limiting.
+/// Middleware applying a rate limit to all requests of the routes it wraps,
+/// sending the state of the limit in the `x-ratelimit-*` headers like
+/// governor.
 #[derive(Clone)]
-pub struct IpAddressExtractor;
+pub struct Limiter(Limit);
 
-impl KeyExtractor for IpAddressExtractor {
-    type Key = IpAddr;
-    type KeyExtractionError = SimpleKeyExtractionError<&'static str>;
+impl<S, B> Transform<S, ServiceRequest> for Limiter
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Error = Error;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    type InitError = ();
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Transform = LimiterMiddleware<S>;
 
-    fn extract(&self, request: &ServiceRequest) -> Result<Self::Key, Self::KeyExtractionError> {
-        address::parse(request.request()).map_err(|_| {
-            Self::KeyExtractionError::new("Bad Request: failed to parse ip address")
-                .set_content_type(ContentType::plaintext())
-                .set_status_code(StatusCode::BAD_REQUEST)
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(LimiterMiddleware {
+            limit: self.0,
+            service: Rc::new(service),
         })
     }
+}
+
+pub struct LimiterMiddleware<S> {
+    limit: Limit,
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for LimiterMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = ServiceResponse<EitherBody<B>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let limit = self.limit;
+
+        Box::pin(async move {
+            let checked = match limit {
+                Limit::IpAddress => rate_limit::check_ip_address(req.request()).await,
+                Limit::Policy(policy) => rate_limit::check_request(req.request(), policy).await,
+            };
+            let response = match checked {
+                Ok(allowed) => {
+                    let mut response = service.call(req).await?;
+                    if let Some(allowed) = allowed {
+                        // the headers of the innermost, most specific limit
+                        // are kept
+                        let headers = response.headers_mut();
+                        insert_new(
+                            headers,
+                            HeaderName::from_static(LIMIT),
+                            allowed.burst_size.into(),
+                        );
+                        insert_new(
+                            headers,
+                            HeaderName::from_static(REMAINING),
+                            allowed.remaining.into(),
+                        );
+                    }
+                    return Ok(response.map_into_left_body());
+                }
+                Err(rate_limit::Error::Exceeded {
+                    wait_seconds,
+                    burst_size,
+                }) => HttpResponse::TooManyRequests()
+                    .content_type(ContentType::plaintext())
+                    .append_header((RETRY_AFTER, wait_seconds))
+                    .append_header((AFTER, wait_seconds))
+                    .append_header((LIMIT, burst_size))
+                    .append_header((REMAINING, 0))
+                    .body(format!(
+                        "Too Many Requests: retry after {wait_seconds} seconds"
+                    )),
+                Err(rate_limit::Error::Address(_)) => HttpResponse::BadRequest()
+                    .content_type(ContentType::plaintext())
+                    .body("Bad Request: failed to parse ip address"),
+                Err(error) => {
+                    tracing::error!(?error, ?limit, "couldn't check rate limit");
+                    HttpResponse::InternalServerError().finish()
+                }
+            };
+            Ok(req.into_response(response.map_into_right_body()))
+        })
+    }
+}
 
-    fn exceed_rate_limit_response(
-        &self,
-        negative: &NotUntil<QuantaInstant>,
-        mut response: HttpResponseBuilder,
-    ) -> HttpResponse {
-        let wait_time = negative
-            .wait_time_from(DefaultClock::default().now())
-            .as_secs();
-
-        response
-            .status(StatusCode::TOO_MANY_REQUESTS)
-            .content_type(ContentType::plaintext())
-            .append_header((actix_web::http::header::RETRY_AFTER, wait_time))
-            .body(format!(
-                "Too Many Requests: retry after {wait_time} seconds"
-            ))
+/// Sets the header unless it's already set.
+fn insert_new(headers: &mut HeaderMap, name: HeaderName, value: HeaderValue) {
+    if !headers.contains_key(&name) {
+        headers.insert(name, value);
     }
 }
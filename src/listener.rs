@@ -0,0 +1,189 @@
+//! Sockets the server accepts connections on, bound by the server or passed by
+//! systemd socket activation.
+#![cfg(feature = "ssr")]
+
+use {
+    anyhow::{Context, Result},
+    listenfd::ListenFd,
+    marzichat::config::ListenerConfig,
+    socket2::{Domain, Socket, Type},
+    std::{
+        net::{SocketAddr, TcpListener},
+        os::unix::{fs::FileTypeExt, net::UnixListener},
+        path::Path,
+    },
+};
+
+/// The backlog of pending connections of the sockets bound by the server,
+/// like actix uses.
+const BACKLOG: i32 = 1024;
+
+/// An open socket.
+pub enum Listener {
+    Tcp { listener: TcpListener, tls: bool },
+    Unix(UnixListener),
+}
+
+/// The sockets passed by systemd socket activation, with their
+/// `FileDescriptorName=`.
+pub struct Passed {
+    sockets: ListenFd,
+    names: Vec<String>,
+}
+
+impl Passed {
+    /// Takes the sockets passed by systemd. Must be called before any thread
+    /// is spawned, since `ListenFd` unsets `LISTEN_PID` and `LISTEN_FDS` so
+    /// child processes don't take them too.
+    pub fn from_env() -> Self {
+        let names = std::env::var("LISTEN_FDNAMES").unwrap_or_default();
+        Self {
+            sockets: ListenFd::from_env(),
+            names: names.split(':').map(str::to_owned).collect(),
+        }
+    }
+
+    /// Returns the indices of the sockets with the name.
+    fn indices(&self, name: &str) -> Vec<usize> {
+        (0..self.sockets.len())
+            .filter(|index| self.names.get(*index).map(String::as_str) == Some(name))
+            .collect()
+    }
+}
+
+/// Opens the sockets of the listeners.
+pub fn open(configs: &[ListenerConfig], mut passed: Passed) -> Result<Vec<Listener>> {
+    anyhow::ensure!(!configs.is_empty(), "no listeners configured");
+
+    let mut listeners = Vec::new();
+    for config in configs {
+        match config {
+            ListenerConfig::Tcp {
+                addresses,
+                port,
+                tls,
+                ipv6_only,
+            } => {
+                anyhow::ensure!(!addresses.is_empty(), "no addresses to bind port {port} on");
+                for address in addresses {
+                    let address = SocketAddr::new(*address, *port);
+                    let listener = bind(address, *ipv6_only)
+                        .with_context(|| format!("couldn't bind {address}"))?;
+                    listeners.push(Listener::Tcp {
+                        listener,
+                        tls: *tls,
+                    });
+                }
+            }
+            ListenerConfig::Unix { path } => {
+                listeners.push(Listener::Unix(bind_unix(path)?));
+            }
+            ListenerConfig::Systemd { name, tls } => {
+                let count = listeners.len();
+                for index in passed.indices(name) {
+                    // taking a socket of the wrong type fails and leaves it
+                    match passed.sockets.take_tcp_listener(index) {
+                        Ok(listener) => {
+                            if let Some(listener) = listener {
+                                listener.set_nonblocking(true)?;
+                                listeners.push(Listener::Tcp {
+                                    listener,
+                                    tls: *tls,
+                                });
+                            }
+                        }
+                        Err(_) => {
+                            let listener =
+                                passed.sockets.take_unix_listener(index).with_context(|| {
+                                    format!("systemd passed {name}, which isn't a stream socket")
+                                })?;
+                            anyhow::ensure!(
+                                !tls,
+                                "systemd passed the unix socket {name}, tls is only served over tcp"
+                            );
+                            if let Some(listener) = listener {
+                                listener.set_nonblocking(true)?;
+                                listeners.push(Listener::Unix(listener));
+                            }
+                        }
+                    }
+                }
+                anyhow::ensure!(
+                    listeners.len() > count,
+                    "systemd passed no sockets named {name}"
+                );
+            }
+        }
+    }
+    Ok(listeners)
+}
+
+/// Binds a tcp socket, accepting only ipv6 connections on ipv6 addresses if
+/// `ipv6_only` is set.
+fn bind(address: SocketAddr, ipv6_only: bool) -> std::io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(address), Type::STREAM, None)?;
+    socket.set_reuse_address(true)?;
+    if address.is_ipv6() {
+        socket.set_only_v6(ipv6_only)?;
+    }
+    socket.set_nonblocking(true)?;
+    socket.bind(&address.into())?;
+    socket.listen(BACKLOG)?;
+    Ok(socket.into())
+}
+
+/// Binds a unix domain socket, replacing a socket left at the path by a
+/// previous run. Anything else at the path is left alone.
+fn bind_unix(path: &Path) -> Result<UnixListener> {
+    if std::fs::symlink_metadata(path).map_or(false, |file| file.file_type().is_socket()) {
+        std::fs::remove_file(path)
+            .with_context(|| format!("couldn't remove the old socket {path:?}"))?;
+    }
+    let listener = UnixListener::bind(path).with_context(|| format!("couldn't bind {path:?}"))?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        std::net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    };
+
+    #[test]
+    fn test_bind_ipv6_only() {
+        let ipv6 = bind(SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0), true).unwrap();
+        let port = ipv6.local_addr().unwrap().port();
+        bind(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), port), false).unwrap();
+
+        // without ipv6_only the ipv6 socket takes the port on ipv4 too
+        let dual_stack = bind(SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0), false).unwrap();
+        let port = dual_stack.local_addr().unwrap().port();
+        let error = bind(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), port), false).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AddrInUse);
+
+        // ipv6_only doesn't apply to ipv4 addresses
+        bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0), true).unwrap();
+    }
+
+    #[test]
+    fn test_bind_unix() {
+        let directory =
+            std::env::temp_dir().join(format!("marzichat-listener-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        // the socket file is left behind like after a crash
+        let path = directory.join("http.sock");
+        drop(bind_unix(&path).unwrap());
+        assert!(path.exists());
+        bind_unix(&path).unwrap();
+
+        let file = directory.join("file");
+        std::fs::write(&file, "not a socket").unwrap();
+        assert!(bind_unix(&file).is_err());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "not a socket");
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -2,18 +2,38 @@
 #![cfg(feature = "ssr")]
 
 use {
-    crate::config::LoggingConfig,
-    anyhow::Context,
-    std::time::Duration,
-    systemstat::{saturating_sub_bytes, Platform, System},
-    tracing_subscriber::{fmt::layer, layer::SubscriberExt, util::SubscriberInitExt},
+    marzichat::{
+        config::{JsonLogOutput, LogRotation, LoggingConfig},
+        telemetry,
+    },
+    opentelemetry::{global, sdk::propagation::TraceContextPropagator, trace::TracerProvider},
+    std::sync::OnceLock,
+    tracing_appender::{non_blocking, non_blocking::WorkerGuard, rolling::Rotation},
+    tracing_subscriber::{
+        fmt::layer, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry,
+    },
 };
 
-/// Sets up logging.
+/// The handle to replace the logging directives.
+static DIRECTIVES: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Flushes the buffered logs when dropped, so it must be held until the
+/// program exits.
+pub struct Guards {
+    _json: Option<WorkerGuard>,
+    _sentry: sentry::ClientInitGuard,
+}
+
+/// Sets up logging. Logs are flushed when the returned guards are dropped.
 #[deny(dead_code)]
-pub fn init(config: LoggingConfig) {
+#[must_use = "logs are lost if the guards are dropped early"]
+pub fn init(config: LoggingConfig) -> Guards {
+    let (directives, handle) = reload::Layer::new(config.directives);
+    if DIRECTIVES.set(handle).is_err() {
+        panic!("logger already initialized");
+    }
     let log = tracing_subscriber::registry()
-        .with(config.directives)
+        .with(directives)
         .with(sentry_tracing::layer());
 
     // show line numbers and hide timestamps in debug builds
@@ -25,6 +45,40 @@ pub fn init(config: LoggingConfig) {
     let log =
         log.with(tracing_journald::Layer::new().expect("failed to initialize journald layer"));
 
+    // writes happen on a background thread so logging never blocks a request
+    let mut json_guard = None;
+    let json = config.json.map(|output| {
+        let (writer, guard) = match output {
+            JsonLogOutput::Stdout => non_blocking(std::io::stdout()),
+            JsonLogOutput::File {
+                directory,
+                file_name_prefix,
+                rotation,
+            } => non_blocking(tracing_appender::rolling::RollingFileAppender::new(
+                rotation_of(rotation),
+                directory,
+                file_name_prefix,
+            )),
+        };
+        json_guard = Some(guard);
+        layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_writer(writer)
+    });
+    let log = log.with(json);
+
+    let otlp = config.otlp.map(|otlp| {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let provider =
+            telemetry::tracer_provider(&otlp).expect("failed to initialize otlp exporter");
+        let tracer = provider.tracer("marzichat");
+        global::set_tracer_provider(provider);
+        tracing_opentelemetry::layer().with_tracer(tracer)
+    });
+    let log = log.with(otlp);
+
     log.init();
 
     let release = sentry::release_name!().expect("error getting release name");
@@ -40,71 +94,26 @@ pub fn init(config: LoggingConfig) {
         tracing::warn!("Sentry not configured");
     }
 
-    // keep the guard for the lifetime of the program
-    std::mem::forget(guard);
-
-    let period = Duration::from_secs(config.machine_stats_interval_seconds.into());
-    tokio::task::spawn(async move {
-        let mut interval = tokio::time::interval(period);
-        loop {
-            interval.tick().await;
-            let cpu_temp = System::new().cpu_temp().context("error getting cpu temp")?;
-            if cpu_temp > config.cpu_temp_warn_threshold_celsius {
-                tracing::warn!(
-                    "cpu temp is above warning threshold of {}°C: {}°C",
-                    config.cpu_temp_warn_threshold_celsius,
-                    cpu_temp
-                );
-            }
-        }
-        // allow lint to annotate return type which cannot be inferred
-        #[allow(unreachable_code)]
-        Ok::<_, anyhow::Error>(())
-    });
-
-    tokio::task::spawn(async move {
-        let mut interval = tokio::time::interval(period);
-        loop {
-            interval.tick().await;
-            let memory = System::new()
-                .memory()
-                .context("error getting memory usage")?;
-            let memory_usage = saturating_sub_bytes(memory.total, memory.free);
-            let memory_usage_percentage =
-                (memory_usage.as_u64() as f64) / (memory.total.as_u64() as f64);
-            if memory_usage_percentage > config.system_memory_usage_warn_threshold_percentage {
-                tracing::warn!(
-                    "memory usage is above warning threshold of {}%: {:.2}%",
-                    config.system_memory_usage_warn_threshold_percentage * 100.0,
-                    memory_usage_percentage * 100.0
-                );
-            }
-        }
-        // allow lint to annotate return type which cannot be inferred
-        #[allow(unreachable_code)]
-        Ok::<_, anyhow::Error>(())
-    });
+    Guards {
+        _json: json_guard,
+        _sentry: guard,
+    }
+}
 
-    tokio::task::spawn(async move {
-        let mut interval = tokio::time::interval(period);
-        loop {
-            interval.tick().await;
-            let cpu_load_aggregate = System::new()
-                .cpu_load_aggregate()
-                .context("error getting cpu load")?;
+/// Replaces the logging directives.
+pub fn reload(directives: EnvFilter) -> anyhow::Result<()> {
+    DIRECTIVES
+        .get()
+        .expect("logger is not initialized")
+        .reload(directives)?;
+    Ok(())
+}
 
-            tokio::time::sleep(Duration::from_secs(1)).await;
-            let cpu_load_aggregate = cpu_load_aggregate.done()?;
-            if cpu_load_aggregate.system > config.cpu_load_warn_limit {
-                tracing::warn!(
-                    "cpu load is above warning threshold of {}%: {}%",
-                    config.cpu_load_warn_limit * 100.0,
-                    cpu_load_aggregate.system * 100.0
-                );
-            }
-        }
-        // allow lint to annotate return type which cannot be inferred
-        #[allow(unreachable_code)]
-        Ok::<_, anyhow::Error>(())
-    });
+fn rotation_of(rotation: LogRotation) -> Rotation {
+    match rotation {
+        LogRotation::Minutely => Rotation::MINUTELY,
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Never => Rotation::NEVER,
+    }
 }
@@ -0,0 +1,75 @@
+//! Sends emails through an smtp relay.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::{config::MailerConfig, types::Email},
+    lettre::{
+        message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+        AsyncTransport, Message, Tokio1Executor,
+    },
+    std::sync::OnceLock,
+};
+
+struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+static MAILER: OnceLock<Mailer> = OnceLock::new();
+
+/// Creates the connection pool to the smtp relay.
+pub fn init(config: MailerConfig) {
+    let builder = if config.tls {
+        AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host).expect("invalid smtp relay host")
+    } else {
+        AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+    };
+    let builder = match config.credentials {
+        Some(credentials) => {
+            builder.credentials(Credentials::new(credentials.username, credentials.password))
+        }
+        None => builder,
+    };
+    let mailer = Mailer {
+        transport: builder.port(config.port).build(),
+        from: config.from,
+    };
+    if MAILER.set(mailer).is_err() {
+        panic!("mailer already initialized");
+    }
+}
+
+/// Sends a plain text email in the background. Failures are logged since the
+/// request that triggered the email shouldn't fail because of them.
+pub fn send(to: &Email, subject: &str, body: String) {
+    let Some(mailer) = MAILER.get() else {
+        tracing::warn!(subject, "mailer is not initialized, dropping email");
+        return;
+    };
+    let to = match to.0.parse::<Mailbox>() {
+        Ok(to) => to,
+        Err(error) => {
+            tracing::error!(?error, subject, "invalid email recipient");
+            return;
+        }
+    };
+    let message = match Message::builder()
+        .from(mailer.from.clone())
+        .to(to)
+        .subject(subject)
+        .body(body)
+    {
+        Ok(message) => message,
+        Err(error) => {
+            tracing::error!(?error, subject, "couldn't build email");
+            return;
+        }
+    };
+
+    let subject = subject.to_owned();
+    tokio::spawn(async move {
+        if let Err(error) = mailer.transport.send(message).await {
+            tracing::error!(?error, subject, "couldn't send email");
+        }
+    });
+}
@@ -15,12 +15,13 @@
     variant_size_differences
 )]
 
-mod address;
-mod config;
+mod gate;
 mod limiter;
+mod listener;
 mod logger;
-mod postgres;
 mod redirect;
+mod reload;
+mod request_id;
 mod server;
 
 #[cfg(feature = "ssr")]
@@ -31,37 +32,47 @@ async fn main(
     #[opt(short, long)]
     config: Option<std::path::PathBuf>,
 
-    /// Validate the config and exit without running the server.
-    #[opt]
-    dry_run: bool,
-
     /// Print info and exit.
     #[opt(short, long)]
     info: bool,
+
+    /// Grant the admin role to a user and exit.
+    #[opt(long)]
+    grant_admin: Option<String>,
 ) -> anyhow::Result<()> {
-    use anyhow::Context;
+    use {
+        anyhow::Context,
+        marzichat::{
+            address, breached_passwords, cache, config, keydb, link_preview, mailer, monitor,
+            postgres, sign_in, spam_filter,
+        },
+    };
 
     if info {
         println!("{}", marzichat::summary());
         Ok(())
     } else {
-        let config = match config {
-            Some(config) => config,
-            None => std::env::var_os("CONFIG")
-                .context("CONFIG env var not set and --config not passed in as argument")?
-                .into(),
-        };
-        let config = config::parse_from_file(&config).context("invalid config")?;
-        if dry_run {
-            sqlx::postgres::PgPool::connect(&config.postgres.url)
-                .await
-                .context("postgres unreachable")?;
+        let config_path = config::path(config)?;
+        let (config, settings) = config::parse_from_file(&config_path).context("invalid config")?;
+        if let Some(username) = grant_admin {
+            let username = marzichat::types::Username(username);
+            postgres::init(config.postgres).await;
+            let user_id = postgres::user_id(&username)
+                .await?
+                .with_context(|| format!("the user {username} doesn't exist"))?;
+            postgres::grant_role(user_id, "admin", None).await?;
+            println!("granted the admin role to {username}");
             Ok(())
         } else {
-            // initialize a cpu-bound thread pool
-            logger::init(config.logging);
+            // before the threads of the logger, monitors and pools are spawned
+            let passed = listener::Passed::from_env();
+
+            // held until the end of main so the buffered logs are written
+            let _log_guards = logger::init(config.logging);
+            monitor::init(config.monitors).context("invalid monitors config")?;
             postgres::init(config.postgres).await;
 
+            // initialize a cpu-bound thread pool
             let cpu_threads = config.cpu_threads.get();
             tracing::info!(cpu_threads, "starting rayon thread pool");
             rayon::ThreadPoolBuilder::new()
@@ -69,7 +80,32 @@ async fn main(
                 .build_global()
                 .expect("unable to create rayon thread pool");
 
-            server::run(config.server).await
+            link_preview::init(config.link_preview).await;
+            spam_filter::init(config.spam_filter);
+            mailer::init(config.mailer);
+            breached_passwords::init(config.passwords)
+                .context("failed to load breached passwords")?;
+            sign_in::init(config.sign_in)
+                .await
+                .context("failed to hash the dummy password")?;
+            if let Some(keydb) = config.keydb {
+                keydb::init(keydb).context("invalid keydb config")?;
+            }
+            if let Some(cache) = config.cache {
+                cache::init(cache);
+            }
+            address::init(
+                config.server.trusted_proxies.clone(),
+                config.server.forwarded_header,
+            );
+            reload::spawn(config_path, settings)?;
+
+            let result = server::run(config.server, passed).await;
+            // export the spans that are still buffered
+            tokio::task::spawn_blocking(opentelemetry::global::shutdown_tracer_provider)
+                .await
+                .context("failed to shut down the tracer provider")?;
+            result
         }
     }
 }
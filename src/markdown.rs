@@ -0,0 +1,139 @@
+//! Markdown rendering module.
+//!
+//! User generated text (posts, comments and bios) is written in a subset of
+//! CommonMark: links, emphasis, code, block quotes and lists. Everything else
+//! is either flattened into the subset or escaped, and the resulting html is
+//! run through a strict allowlist sanitizer before it is stored.
+
+#[cfg(feature = "ssr")]
+use {
+    ammonia::{Builder, UrlRelative},
+    std::{
+        collections::{HashMap, HashSet},
+        sync::OnceLock,
+    },
+};
+
+/// The `rel` attribute added to every link.
+pub const LINK_REL: &str = "nofollow ugc noopener";
+
+/// Renders Markdown to sanitized html.
+#[cfg(feature = "ssr")]
+pub fn render(markdown: &str) -> String {
+    use pulldown_cmark::{html, Event, Options, Parser, Tag};
+
+    let events = Parser::new_ext(markdown, Options::empty()).filter_map(|event| match event {
+        // show raw html as written instead of interpreting it
+        Event::Html(html) => Some(Event::Text(html)),
+        // headings become plain paragraphs
+        Event::Start(Tag::Heading(..)) => Some(Event::Start(Tag::Paragraph)),
+        Event::End(Tag::Heading(..)) => Some(Event::End(Tag::Paragraph)),
+        // images become links to the image
+        Event::Start(Tag::Image(kind, url, title)) => {
+            Some(Event::Start(Tag::Link(kind, url, title)))
+        }
+        Event::End(Tag::Image(kind, url, title)) => Some(Event::End(Tag::Link(kind, url, title))),
+        Event::Rule => None,
+        event => Some(event),
+    });
+
+    let mut unsanitized = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut unsanitized, events);
+    sanitize(&unsanitized)
+}
+
+/// Removes every tag, attribute and url scheme that isn't on the allowlist and
+/// adds [`LINK_REL`] to links.
+///
+/// Also used for html from third parties.
+#[cfg(feature = "ssr")]
+pub fn sanitize(html: &str) -> String {
+    static SANITIZER: OnceLock<Builder<'static>> = OnceLock::new();
+    SANITIZER
+        .get_or_init(|| {
+            let mut builder = Builder::empty();
+            builder
+                .tags(HashSet::from([
+                    "a",
+                    "blockquote",
+                    "br",
+                    "code",
+                    "em",
+                    // used for emphasis by Hacker News
+                    "i",
+                    "li",
+                    "ol",
+                    "p",
+                    "pre",
+                    "strong",
+                    "ul",
+                ]))
+                .clean_content_tags(HashSet::from(["script", "style"]))
+                .tag_attributes(HashMap::from([
+                    ("a", HashSet::from(["href"])),
+                    ("ol", HashSet::from(["start"])),
+                ]))
+                .url_schemes(HashSet::from(["http", "https", "mailto"]))
+                .url_relative(UrlRelative::Deny)
+                .link_rel(Some(LINK_REL));
+            builder
+        })
+        .clean(html)
+        .to_string()
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_subset() {
+        assert_eq!(
+            render("*emphasis*, **strong** and `code`"),
+            "<p><em>emphasis</em>, <strong>strong</strong> and <code>code</code></p>\n"
+        );
+        assert_eq!(
+            render("> quote\n\n- one\n- two"),
+            "<blockquote>\n<p>quote</p>\n</blockquote>\n<ul>\n<li>one</li>\n<li>two</li>\n</ul>\n"
+        );
+        assert_eq!(render("# heading"), "<p>heading</p>\n");
+    }
+
+    #[test]
+    fn test_render_links() {
+        assert_eq!(
+            render("[site](https://example.com)"),
+            r#"<p><a href="https://example.com" rel="nofollow ugc noopener">site</a></p>"#
+                .to_owned()
+                + "\n"
+        );
+        assert_eq!(
+            render("[click](javascript:alert(1))"),
+            "<p><a rel=\"nofollow ugc noopener\">click</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_render_escapes_html() {
+        assert_eq!(
+            render("<script>alert(1)</script>"),
+            "&lt;script&gt;alert(1)&lt;/script&gt;"
+        );
+        assert_eq!(
+            render("<img src=x onerror=alert(1)>"),
+            "&lt;img src=x onerror=alert(1)&gt;"
+        );
+    }
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(
+            sanitize(r#"<p onclick="alert(1)">hi<script>alert(1)</script></p>"#),
+            "<p>hi</p>"
+        );
+        assert_eq!(
+            sanitize(r#"<i>italic</i> <a href="/relative">link</a>"#),
+            r#"<i>italic</i> <a rel="nofollow ugc noopener">link</a>"#
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Prometheus metrics.
+//!
+//! Metrics are recorded where the events happen and encoded in the Prometheus
+//! text format when `/metrics` is scraped. Gauges of state that's cheap to
+//! read, like the postgres pool, are updated on scrape instead.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::postgres,
+    prometheus::{
+        core::Collector, Encoder, Gauge, Histogram, HistogramOpts, HistogramVec, IntCounterVec,
+        IntGauge, Opts, Registry, TextEncoder,
+    },
+    std::{sync::OnceLock, time::Duration},
+};
+
+/// The content type of the encoded metrics.
+pub const CONTENT_TYPE: &str = prometheus::TEXT_FORMAT;
+
+/// The label of requests that didn't match a route, so unknown paths don't
+/// create a time series each.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// The methods recorded by name. Clients can send any method token, so the
+/// others are recorded as [`OTHER_METHOD`].
+const METHODS: [&str; 9] = [
+    "GET", "HEAD", "POST", "PUT", "DELETE", "CONNECT", "OPTIONS", "TRACE", "PATCH",
+];
+
+/// The label of requests with a method not in [`METHODS`].
+const OTHER_METHOD: &str = "other";
+
+/// The metrics of the server.
+pub struct Metrics {
+    registry: Registry,
+
+    /// The number of handled requests per route, method and status.
+    pub http_requests: IntCounterVec,
+
+    /// The time it took to handle requests per route, method and status.
+    pub http_request_duration: HistogramVec,
+
+    /// The number of requests rejected per rate limit.
+    pub rate_limit_rejections: IntCounterVec,
+
+    pub postgres_connections: IntGauge,
+    pub postgres_idle_connections: IntGauge,
+    pub postgres_max_connections: IntGauge,
+
+    /// The number of queries waiting for a connection from the pool.
+    pub postgres_acquire_waiting: IntGauge,
+
+    /// The time queries waited for a connection from the pool.
+    pub postgres_acquire_duration: Histogram,
+
+    pub cpu_temperature_celsius: Gauge,
+
+    /// The fraction of the system memory in use.
+    pub memory_usage_ratio: Gauge,
+
+    /// The fraction of the cpu time that isn't idle.
+    pub cpu_load_ratio: Gauge,
+
+    /// The fraction of the monitored filesystem's space in use.
+    pub disk_usage_ratio: Gauge,
+
+    /// The fraction of the open file descriptor limit of the process in use.
+    pub file_descriptor_usage_ratio: Gauge,
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("marzichat".to_owned()), None)?;
+        Ok(Self {
+            http_requests: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("http_requests_total", "Handled http requests"),
+                    &["route", "method", "status"],
+                )?,
+            )?,
+            http_request_duration: register(
+                &registry,
+                HistogramVec::new(
+                    HistogramOpts::new(
+                        "http_request_duration_seconds",
+                        "Time taken to handle http requests",
+                    ),
+                    &["route", "method", "status"],
+                )?,
+            )?,
+            rate_limit_rejections: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new(
+                        "rate_limit_rejections_total",
+                        "Requests rejected by rate limits",
+                    ),
+                    &["limit"],
+                )?,
+            )?,
+            postgres_connections: register(
+                &registry,
+                IntGauge::new("postgres_connections", "Open postgres pool connections")?,
+            )?,
+            postgres_idle_connections: register(
+                &registry,
+                IntGauge::new(
+                    "postgres_idle_connections",
+                    "Idle postgres pool connections",
+                )?,
+            )?,
+            postgres_max_connections: register(
+                &registry,
+                IntGauge::new(
+                    "postgres_max_connections",
+                    "Maximum postgres pool connections",
+                )?,
+            )?,
+            postgres_acquire_waiting: register(
+                &registry,
+                IntGauge::new(
+                    "postgres_acquire_waiting",
+                    "Queries waiting for a postgres pool connection",
+                )?,
+            )?,
+            postgres_acquire_duration: register(
+                &registry,
+                Histogram::with_opts(
+                    HistogramOpts::new(
+                        "postgres_acquire_duration_seconds",
+                        "Time taken to acquire a postgres pool connection",
+                    )
+                    .buckets(vec![
+                        0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 30.0,
+                    ]),
+                )?,
+            )?,
+            cpu_temperature_celsius: register(
+                &registry,
+                Gauge::new("cpu_temperature_celsius", "Cpu temperature")?,
+            )?,
+            memory_usage_ratio: register(
+                &registry,
+                Gauge::new("memory_usage_ratio", "Fraction of the system memory in use")?,
+            )?,
+            cpu_load_ratio: register(
+                &registry,
+                Gauge::new("cpu_load_ratio", "Fraction of the cpu time that isn't idle")?,
+            )?,
+            disk_usage_ratio: register(
+                &registry,
+                Gauge::new(
+                    "disk_usage_ratio",
+                    "Fraction of the monitored filesystem's space in use",
+                )?,
+            )?,
+            file_descriptor_usage_ratio: register(
+                &registry,
+                Gauge::new(
+                    "file_descriptor_usage_ratio",
+                    "Fraction of the open file descriptor limit in use",
+                )?,
+            )?,
+            registry,
+        })
+    }
+}
+
+/// Registers the metric and returns it.
+fn register<M: Collector + Clone + 'static>(
+    registry: &Registry,
+    metric: M,
+) -> prometheus::Result<M> {
+    registry.register(Box::new(metric.clone()))?;
+    Ok(metric)
+}
+
+/// Returns the metrics of the server.
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(|| Metrics::new().expect("invalid metrics"))
+}
+
+/// Records a handled request. The route is the pattern of the matched route,
+/// if any. Requests that failed with an error are recorded with the status of
+/// the error response.
+pub fn observe_request(route: Option<&str>, method: &str, status: u16, duration: Duration) {
+    let metrics = metrics();
+    let status = status.to_string();
+    let labels = [
+        route.unwrap_or(UNMATCHED_ROUTE),
+        method_label(method),
+        &status,
+    ];
+    metrics.http_requests.with_label_values(&labels).inc();
+    metrics
+        .http_request_duration
+        .with_label_values(&labels)
+        .observe(duration.as_secs_f64());
+}
+
+/// Returns the label of the request method.
+fn method_label(method: &str) -> &str {
+    if METHODS.contains(&method) {
+        method
+    } else {
+        OTHER_METHOD
+    }
+}
+
+/// Returns all metrics in the Prometheus text format.
+pub fn encode() -> Result<String, prometheus::Error> {
+    let metrics = metrics();
+    if let Some(pool) = postgres::pool_stats() {
+        metrics.postgres_connections.set(pool.size.into());
+        metrics.postgres_idle_connections.set(pool.idle as i64);
+        metrics.postgres_max_connections.set(pool.max.into());
+    }
+
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&metrics.registry.gather(), &mut buffer)?;
+    String::from_utf8(buffer).map_err(|error| prometheus::Error::Msg(error.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_method_label() {
+        assert_eq!(method_label("GET"), "GET");
+        assert_eq!(method_label("PATCH"), "PATCH");
+        assert_eq!(method_label("get"), OTHER_METHOD);
+        assert_eq!(method_label("PURGE"), OTHER_METHOD);
+    }
+}
@@ -0,0 +1,382 @@
+//! Moderation module.
+//!
+//! Users report posts, comments and other users. Reports land in the
+//! moderation queue of the community the content belongs to, where its
+//! moderators remove the content, approve it, ban or warn its author. Every
+//! moderation action, including pinning and locking posts, is recorded in the
+//! append-only moderation log. Posts and comments held by the
+//! [spam filter](crate::spam_filter) land in the same queue to be approved or
+//! removed. Authors see the actions taken on their content and the reasons
+//! given in their moderation notices. Users with the
+//! [`ModerateAllCommunities`](crate::permissions::Permission) permission
+//! moderate every community.
+
+use {
+    crate::{internationalization::Translations, spam_filter::Verdict, types::*},
+    leptos::*,
+    serde::{Deserialize, Serialize},
+};
+
+/// The maximum number of bytes the details of a report can be.
+// Don't change without updating the reports table contraints.
+pub const MAX_DETAILS_BYTES: usize = 1000;
+
+/// The maximum number of bytes the reason given for a moderation action can
+/// be.
+// Don't change without updating the moderation log table contraints.
+pub const MAX_REASON_BYTES: usize = 1000;
+
+/// Why something was reported.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(type_name = "report_reason"))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize, Hash)]
+pub enum Reason {
+    Spam,
+    Harassment,
+    HateSpeech,
+    Violence,
+    Misinformation,
+    RuleViolation,
+    Other,
+}
+
+/// A list of all report reasons.
+pub const REASONS: &[Reason] = &[
+    Reason::Spam,
+    Reason::Harassment,
+    Reason::HateSpeech,
+    Reason::Violence,
+    Reason::Misinformation,
+    Reason::RuleViolation,
+    Reason::Other,
+];
+
+impl Reason {
+    /// The localized label of the reason.
+    pub fn label(self, t: Translations) -> &'static str {
+        match self {
+            Reason::Spam => t.spam(),
+            Reason::Harassment => t.harassment(),
+            Reason::HateSpeech => t.hate_speech(),
+            Reason::Violence => t.violence(),
+            Reason::Misinformation => t.misinformation(),
+            Reason::RuleViolation => t.rule_violation(),
+            Reason::Other => t.other(),
+        }
+    }
+}
+
+/// An action taken by a moderator.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(type_name = "moderation_action"))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize, Hash)]
+pub enum Action {
+    /// Hide the post or comment.
+    Remove,
+
+    /// Keep the content and close the report.
+    Approve,
+
+    /// Ban the author from posting and commenting in the community, or ban
+    /// the user site-wide.
+    Ban,
+
+    /// Warn the author.
+    Warn,
+
+    Pin,
+    Unpin,
+    Lock,
+    Unlock,
+
+    /// Suspend the user for a number of days.
+    Suspend,
+
+    /// Hide the user's content from everyone else.
+    ShadowBan,
+
+    /// Lift a suspension, ban or shadow-ban.
+    Lift,
+}
+
+/// The actions that resolve a report.
+pub const REPORT_ACTIONS: &[Action] = &[Action::Remove, Action::Approve, Action::Ban, Action::Warn];
+
+impl Action {
+    /// The localized label of the action.
+    pub fn label(self, t: Translations) -> &'static str {
+        match self {
+            Action::Remove => t.remove(),
+            Action::Approve => t.approve(),
+            Action::Ban => t.ban(),
+            Action::Warn => t.warn(),
+            Action::Pin => t.pin(),
+            Action::Unpin => t.unpin(),
+            Action::Lock => t.lock(),
+            Action::Unlock => t.unlock(),
+            Action::Suspend => t.suspend(),
+            Action::ShadowBan => t.shadow_ban(),
+            Action::Lift => t.lift(),
+        }
+    }
+
+    /// Whether the author has to be told why the action was taken.
+    pub const fn requires_reason(self) -> bool {
+        matches!(
+            self,
+            Action::Remove | Action::Ban | Action::Warn | Action::Suspend | Action::ShadowBan
+        )
+    }
+}
+
+/// An open report in the moderation queue.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub report_id: ReportId,
+    pub created: DateTime,
+    pub reporter: Username,
+    pub reason: Reason,
+    pub details: String,
+    pub community: Option<CommunityName>,
+    pub target: Target,
+}
+
+/// What a report is about.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum Target {
+    Post {
+        post_id: PostId,
+        title: Title,
+        author: Username,
+    },
+    Comment {
+        post_id: PostId,
+        comment_id: CommentId,
+        body_html: String,
+        author: Username,
+    },
+    User {
+        username: Username,
+    },
+}
+
+/// A post or comment held by the spam filter.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Held {
+    pub created: DateTime,
+    pub community: Option<CommunityName>,
+
+    /// The held post or comment.
+    pub target: Target,
+
+    /// The verdicts of all rules, including the ones it passed.
+    pub verdicts: Vec<Verdict>,
+}
+
+/// The actions that review held content.
+pub const REVIEW_ACTIONS: &[Action] = &[Action::Approve, Action::Remove];
+
+/// A moderation action taken on the signed in user's content or account.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Notice {
+    pub created: DateTime,
+    pub action: Action,
+    pub community: Option<CommunityName>,
+    pub post_id: Option<PostId>,
+    pub comment_id: Option<CommentId>,
+    pub reason: String,
+}
+
+/// The post, comment or user a moderation action is taken on.
+#[cfg(feature = "ssr")]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Subject {
+    pub community_id: Option<CommunityId>,
+    pub post_id: Option<PostId>,
+    pub comment_id: Option<CommentId>,
+
+    /// The author of the post or comment, or the user.
+    pub user_id: UserId,
+}
+
+/// Reports a post, comment or user. Exactly one of them has to be given. Users
+/// are reported to the moderators of the community they were seen in, or to
+/// the users who moderate all communities otherwise.
+#[tracing::instrument(skip_all)]
+#[server(SubmitReport, "/api")]
+pub async fn submit_report(
+    cx: Scope,
+    post_id: Option<PostId>,
+    comment_id: Option<CommentId>,
+    username: Option<Username>,
+    community: Option<CommunityName>,
+    reason: Reason,
+    details: String,
+) -> Result<(), ServerFnError> {
+    use crate::{
+        community::require_community_id,
+        postgres,
+        rate_limit::{self, Policy},
+        session,
+    };
+
+    let reporter = session::require_user_id(cx).await?;
+    rate_limit::check(cx, Policy::Report).await?;
+
+    let details = details.trim();
+    if details.len() > MAX_DETAILS_BYTES {
+        return Err(ServerFnError::Args("the details are too long".to_owned()));
+    }
+
+    let subject = match (post_id, comment_id, username) {
+        (Some(post_id), None, None) => postgres::post_subject(post_id).await?,
+        (None, Some(comment_id), None) => postgres::comment_subject(comment_id).await?,
+        (None, None, Some(username)) => {
+            let community_id = match community {
+                Some(community) => Some(require_community_id(&community).await?),
+                None => None,
+            };
+            postgres::user_id(&username).await?.map(|user_id| Subject {
+                community_id,
+                post_id: None,
+                comment_id: None,
+                user_id,
+            })
+        }
+        _ => {
+            return Err(ServerFnError::Args(
+                "report exactly one post, comment or user".to_owned(),
+            ))
+        }
+    }
+    .ok_or_else(|| ServerFnError::Args("the reported content doesn't exist".to_owned()))?;
+
+    postgres::create_report(
+        ReportId(uuid::Uuid::new_v4()),
+        reporter,
+        reason,
+        details,
+        &subject,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Returns the open reports in the communities the signed in user moderates,
+/// the oldest first.
+#[tracing::instrument(skip_all)]
+#[server(GetReports, "/api")]
+pub async fn get_reports(cx: Scope) -> Result<Vec<Report>, ServerFnError> {
+    let moderator = crate::session::require_user_id(cx).await?;
+    Ok(crate::postgres::open_reports(moderator).await?)
+}
+
+/// Resolves a report by taking one of the [`REPORT_ACTIONS`]. The reason is
+/// shown to the author and is required unless the content is approved.
+#[tracing::instrument(skip_all)]
+#[server(ResolveReport, "/api")]
+pub async fn resolve_report(
+    cx: Scope,
+    report_id: ReportId,
+    action: Action,
+    reason: String,
+) -> Result<(), ServerFnError> {
+    use crate::{permissions::require_moderator, postgres, session};
+
+    let moderator = session::require_user_id(cx).await?;
+    if !REPORT_ACTIONS.contains(&action) {
+        return Err(ServerFnError::Args(format!(
+            "{action:?} doesn't resolve a report"
+        )));
+    }
+    let reason = validate_reason(action, reason)?;
+
+    let subject = postgres::open_report_subject(report_id)
+        .await?
+        .ok_or_else(|| ServerFnError::Args("the report doesn't exist or is resolved".to_owned()))?;
+    require_moderator(moderator, subject.community_id).await?;
+    if action == Action::Ban && subject.community_id.is_none() {
+        return Err(ServerFnError::Args(
+            "users can only be banned from a community".to_owned(),
+        ));
+    }
+    if action == Action::Remove && subject.post_id.is_none() && subject.comment_id.is_none() {
+        return Err(ServerFnError::Args(
+            "only posts and comments can be removed".to_owned(),
+        ));
+    }
+
+    postgres::moderate(moderator, action, &subject, Some(report_id), &reason).await?;
+    crate::cache::invalidate_front_page().await;
+    Ok(())
+}
+
+/// Returns the posts and comments held by the spam filter in the communities
+/// the signed in user moderates, the oldest first.
+#[tracing::instrument(skip_all)]
+#[server(GetHeld, "/api")]
+pub async fn get_held(cx: Scope) -> Result<Vec<Held>, ServerFnError> {
+    let moderator = crate::session::require_user_id(cx).await?;
+    Ok(crate::postgres::held_content(moderator).await?)
+}
+
+/// Reviews a held post or comment by taking one of the [`REVIEW_ACTIONS`].
+/// Exactly one of them has to be given. Approved content is shown to everyone.
+#[tracing::instrument(skip_all)]
+#[server(ReviewHeld, "/api")]
+pub async fn review_held(
+    cx: Scope,
+    post_id: Option<PostId>,
+    comment_id: Option<CommentId>,
+    action: Action,
+    reason: String,
+) -> Result<(), ServerFnError> {
+    use crate::{permissions::require_moderator, postgres, session};
+
+    let moderator = session::require_user_id(cx).await?;
+    if !REVIEW_ACTIONS.contains(&action) {
+        return Err(ServerFnError::Args(format!(
+            "{action:?} doesn't review held content"
+        )));
+    }
+    let reason = validate_reason(action, reason)?;
+
+    let subject = match (post_id, comment_id) {
+        (Some(post_id), None) => postgres::post_subject(post_id).await?,
+        (None, Some(comment_id)) => postgres::comment_subject(comment_id).await?,
+        _ => {
+            return Err(ServerFnError::Args(
+                "review exactly one post or comment".to_owned(),
+            ))
+        }
+    }
+    .ok_or_else(|| ServerFnError::Args("the held content doesn't exist".to_owned()))?;
+    require_moderator(moderator, subject.community_id).await?;
+
+    postgres::moderate(moderator, action, &subject, None, &reason).await?;
+    crate::cache::invalidate_front_page().await;
+    Ok(())
+}
+
+/// Returns the moderation actions taken on the signed in user's content or
+/// account, the newest first.
+#[tracing::instrument(skip_all)]
+#[server(GetNotices, "/api")]
+pub async fn get_notices(cx: Scope) -> Result<Vec<Notice>, ServerFnError> {
+    let user_id = crate::session::require_user_id(cx).await?;
+    Ok(crate::postgres::notices(user_id).await?)
+}
+
+/// Trims the reason for a moderation action and checks it's given if the
+/// action requires one.
+#[cfg(feature = "ssr")]
+pub fn validate_reason(action: Action, reason: String) -> Result<String, ServerFnError> {
+    let reason = reason.trim().to_owned();
+    if action.requires_reason() && reason.is_empty() {
+        return Err(ServerFnError::Args("a reason is required".to_owned()));
+    }
+    if reason.len() > MAX_REASON_BYTES {
+        return Err(ServerFnError::Args("the reason is too long".to_owned()));
+    }
+    Ok(reason)
+}
@@ -0,0 +1,337 @@
+//! Monitors of machine stats.
+//!
+//! Every configured stat is sampled periodically by its own task. The latest
+//! readings are exported as metrics and shown by `/info`, and warnings are
+//! logged when a stat crosses its warning threshold. A failed sample is logged
+//! and retried at the next interval.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::{
+        config::{MonitorConfig, MonitorsConfig},
+        internationalization::Language,
+        metrics::metrics,
+        types::{datetime, DateTime},
+    },
+    anyhow::{Context, Result},
+    prometheus::Gauge,
+    std::{collections::BTreeMap, fmt::Write, path::PathBuf, sync::Mutex, time::Duration},
+    systemstat::{saturating_sub_bytes, Platform, System},
+};
+
+/// A monitored stat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Stat {
+    CpuTemperature,
+    MemoryUsage,
+    CpuLoad,
+    DiskUsage,
+    FileDescriptorUsage,
+}
+
+impl Stat {
+    fn name(self) -> &'static str {
+        match self {
+            Self::CpuTemperature => "cpu temperature",
+            Self::MemoryUsage => "memory usage",
+            Self::CpuLoad => "cpu load",
+            Self::DiskUsage => "disk usage",
+            Self::FileDescriptorUsage => "file descriptor usage",
+        }
+    }
+
+    fn gauge(self) -> &'static Gauge {
+        let metrics = metrics();
+        match self {
+            Self::CpuTemperature => &metrics.cpu_temperature_celsius,
+            Self::MemoryUsage => &metrics.memory_usage_ratio,
+            Self::CpuLoad => &metrics.cpu_load_ratio,
+            Self::DiskUsage => &metrics.disk_usage_ratio,
+            Self::FileDescriptorUsage => &metrics.file_descriptor_usage_ratio,
+        }
+    }
+
+    /// Formats a value of the stat for humans.
+    fn format(self, value: f64) -> String {
+        match self {
+            Self::CpuTemperature => format!("{value:.1}°C"),
+            _ => format!("{:.1}%", value * 100.0),
+        }
+    }
+}
+
+/// The latest sample of a stat.
+struct Reading {
+    value: Result<f64, String>,
+    at: DateTime,
+}
+
+static READINGS: Mutex<BTreeMap<Stat, Reading>> = Mutex::new(BTreeMap::new());
+
+/// Whether a stat is above its warning threshold, with hysteresis.
+#[derive(Debug)]
+struct Alarm {
+    warn_threshold: f64,
+    clear_threshold: f64,
+    raised: bool,
+}
+
+/// A change of the state of an [`Alarm`].
+#[derive(Debug, PartialEq, Eq)]
+enum Transition {
+    Raised,
+    Cleared,
+}
+
+impl Alarm {
+    fn new(config: MonitorConfig) -> Self {
+        Self {
+            warn_threshold: config.warn_threshold,
+            clear_threshold: config.clear_threshold,
+            raised: false,
+        }
+    }
+
+    fn update(&mut self, value: f64) -> Option<Transition> {
+        if !self.raised && value > self.warn_threshold {
+            self.raised = true;
+            Some(Transition::Raised)
+        } else if self.raised && value < self.clear_threshold {
+            self.raised = false;
+            Some(Transition::Cleared)
+        } else {
+            None
+        }
+    }
+}
+
+/// Starts the configured monitors.
+pub fn init(config: MonitorsConfig) -> Result<()> {
+    let monitors = [
+        (Stat::CpuTemperature, config.cpu_temperature_celsius, None),
+        (Stat::MemoryUsage, config.memory_usage_ratio, None),
+        (Stat::CpuLoad, config.cpu_load_ratio, None),
+        (
+            Stat::FileDescriptorUsage,
+            config.file_descriptor_usage_ratio,
+            None,
+        ),
+    ]
+    .into_iter()
+    .chain(
+        config
+            .disk_usage_ratio
+            .map(|disk| (Stat::DiskUsage, Some(disk.monitor), Some(disk.mount_point))),
+    );
+    for (stat, config, mount_point) in monitors {
+        let Some(config) = config else {
+            continue;
+        };
+        anyhow::ensure!(
+            config.clear_threshold <= config.warn_threshold,
+            "the clear threshold of the {} monitor is above its warning threshold",
+            stat.name()
+        );
+        tokio::task::spawn(monitor(stat, config, mount_point));
+    }
+    Ok(())
+}
+
+/// Samples a stat every interval until the program exits.
+async fn monitor(stat: Stat, config: MonitorConfig, mount_point: Option<PathBuf>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(config.interval_seconds.get()));
+    let mut alarm = Alarm::new(config);
+    let mut failing = false;
+    loop {
+        interval.tick().await;
+        let value = sample(stat, mount_point.as_ref()).await;
+        match &value {
+            Ok(value) => {
+                if std::mem::take(&mut failing) {
+                    tracing::info!(stat = stat.name(), "sampling recovered");
+                }
+                stat.gauge().set(*value);
+                match alarm.update(*value) {
+                    Some(Transition::Raised) => tracing::warn!(
+                        "{} is above warning threshold of {}: {}",
+                        stat.name(),
+                        stat.format(config.warn_threshold),
+                        stat.format(*value)
+                    ),
+                    Some(Transition::Cleared) => tracing::info!(
+                        "{} is back below {}: {}",
+                        stat.name(),
+                        stat.format(config.clear_threshold),
+                        stat.format(*value)
+                    ),
+                    None => (),
+                }
+            }
+            // only log the first of consecutive failures, a stat that can't be
+            // sampled at all, like the cpu temperature on most vms, would
+            // otherwise log an error every interval
+            Err(error) if !failing => {
+                failing = true;
+                tracing::error!(stat = stat.name(), error = %format_args!("{error:#}"), "failed to sample");
+            }
+            Err(_) => (),
+        }
+        READINGS.lock().expect("poisoned lock").insert(
+            stat,
+            Reading {
+                value: value.map_err(|error| format!("{error:#}")),
+                at: chrono::Utc::now(),
+            },
+        );
+    }
+}
+
+async fn sample(stat: Stat, mount_point: Option<&PathBuf>) -> Result<f64> {
+    match stat {
+        Stat::CpuTemperature => {
+            blocking(|| {
+                Ok(System::new()
+                    .cpu_temp()
+                    .context("error getting cpu temp")?
+                    .into())
+            })
+            .await
+        }
+        Stat::MemoryUsage => {
+            blocking(|| {
+                let memory = System::new()
+                    .memory()
+                    .context("error getting memory usage")?;
+                let used = saturating_sub_bytes(memory.total, memory.free);
+                Ok(used.as_u64() as f64 / memory.total.as_u64() as f64)
+            })
+            .await
+        }
+        Stat::CpuLoad => {
+            let cpu_load = blocking(|| {
+                System::new()
+                    .cpu_load_aggregate()
+                    .context("error getting cpu load")
+            })
+            .await?;
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            blocking(move || {
+                let cpu_load = cpu_load.done().context("error getting cpu load")?;
+                Ok((1.0 - cpu_load.idle).into())
+            })
+            .await
+        }
+        Stat::DiskUsage => {
+            let mount_point = mount_point.context("no mount point")?.clone();
+            blocking(move || {
+                let filesystem = System::new()
+                    .mount_at(&mount_point)
+                    .with_context(|| format!("error getting the filesystem at {mount_point:?}"))?;
+                let used = saturating_sub_bytes(filesystem.total, filesystem.avail);
+                Ok(used.as_u64() as f64 / filesystem.total.as_u64() as f64)
+            })
+            .await
+        }
+        Stat::FileDescriptorUsage => blocking(file_descriptor_usage).await,
+    }
+}
+
+/// Runs a sample that reads files on the blocking thread pool, so it doesn't
+/// hold up the tasks of the runtime.
+async fn blocking<T: Send + 'static>(
+    sample: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(sample)
+        .await
+        .context("sampling panicked")?
+}
+
+/// Returns the fraction of the soft limit of open file descriptors of the
+/// process in use.
+#[cfg(target_os = "linux")]
+fn file_descriptor_usage() -> Result<f64> {
+    let open = std::fs::read_dir("/proc/self/fd")
+        .context("error listing open file descriptors")?
+        .count();
+    let limits =
+        std::fs::read_to_string("/proc/self/limits").context("error reading process limits")?;
+    Ok(match open_files_limit(&limits)? {
+        Some(limit) => open as f64 / limit as f64,
+        None => 0.0,
+    })
+}
+
+/// Returns the soft limit of open file descriptors in the contents of
+/// `/proc/self/limits`, or None if it's unlimited.
+#[cfg(any(target_os = "linux", test))]
+fn open_files_limit(limits: &str) -> Result<Option<u64>> {
+    let limit = limits
+        .lines()
+        .find_map(|line| line.strip_prefix("Max open files"))
+        .and_then(|limits| limits.split_whitespace().next())
+        .context("no open file descriptor limit")?;
+    if limit == "unlimited" {
+        return Ok(None);
+    }
+    limit
+        .parse()
+        .map(Some)
+        .context("invalid open file descriptor limit")
+}
+
+#[cfg(not(target_os = "linux"))]
+fn file_descriptor_usage() -> Result<f64> {
+    anyhow::bail!("counting open file descriptors is only supported on linux")
+}
+
+/// Returns the latest readings in plain text format.
+pub fn summary() -> String {
+    let readings = READINGS.lock().expect("poisoned lock");
+    let mut summary = String::new();
+    for (stat, reading) in readings.iter() {
+        let value = match &reading.value {
+            Ok(value) => stat.format(*value),
+            Err(error) => format!("error: {error}"),
+        };
+        let ago = datetime::ago(&reading.at, Language::English);
+        let _ = writeln!(summary, "{}: {value} ({ago})", stat.name());
+    }
+    summary
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, std::num::NonZeroU64};
+
+    #[test]
+    fn test_alarm() {
+        let mut alarm = Alarm::new(MonitorConfig {
+            interval_seconds: NonZeroU64::new(1).unwrap(),
+            warn_threshold: 0.8,
+            clear_threshold: 0.7,
+        });
+        assert_eq!(alarm.update(0.5), None);
+        assert_eq!(alarm.update(0.81), Some(Transition::Raised));
+        assert_eq!(alarm.update(0.9), None);
+        assert_eq!(alarm.update(0.75), None);
+        assert_eq!(alarm.update(0.85), None);
+        assert_eq!(alarm.update(0.69), Some(Transition::Cleared));
+        assert_eq!(alarm.update(0.75), None);
+        assert_eq!(alarm.update(0.81), Some(Transition::Raised));
+    }
+
+    #[test]
+    fn test_open_files_limit() {
+        let limits = |soft| {
+            format!(
+                "Limit                     Soft Limit           Hard Limit           Units     \n\
+                 Max processes             63422                63422                processes \n\
+                 Max open files            {soft:<20} 524288               files     \n"
+            )
+        };
+        assert_eq!(open_files_limit(&limits("1024")).unwrap(), Some(1024));
+        assert_eq!(open_files_limit(&limits("unlimited")).unwrap(), None);
+        assert!(open_files_limit(&limits("many")).is_err());
+        assert!(open_files_limit("").is_err());
+    }
+}
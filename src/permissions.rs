@@ -0,0 +1,157 @@
+//! Roles and permissions module.
+//!
+//! Users are granted roles and roles grant permissions. Both are stored in
+//! Postgres so roles can be granted and revoked without a deploy. Users without
+//! a role are regular users. Server functions doing something privileged call
+//! [`require_permission`] instead of checking roles themselves.
+
+use {
+    crate::{internationalization::Translations, types::*},
+    derive_more::Display,
+    leptos::*,
+    serde::{Deserialize, Serialize},
+};
+
+/// Something only some users are allowed to do.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(type_name = "permission"))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize, Display, Hash)]
+pub enum Permission {
+    /// View the admin pages.
+    AccessAdmin,
+
+    /// Grant roles to users and revoke them.
+    ManageRoles,
+
+    /// Act as a moderator of every community and handle reports about users
+    /// that weren't made in a community.
+    ModerateAllCommunities,
+
+    /// Suspend, ban and shadow-ban users site-wide.
+    SanctionUsers,
+}
+
+impl Permission {
+    /// The localized description of the permission.
+    pub fn label(self, t: Translations) -> &'static str {
+        match self {
+            Permission::AccessAdmin => t.access_admin(),
+            Permission::ManageRoles => t.manage_roles(),
+            Permission::ModerateAllCommunities => t.moderate_all_communities(),
+            Permission::SanctionUsers => t.sanction_users(),
+        }
+    }
+}
+
+/// A role, the permissions it grants and the users it's granted to.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Role {
+    pub name: String,
+    pub description: String,
+    pub permissions: Vec<Permission>,
+    pub members: Vec<Username>,
+}
+
+/// Returns all roles.
+#[tracing::instrument(skip_all)]
+#[server(GetRoles, "/api")]
+pub async fn get_roles(cx: Scope) -> Result<Vec<Role>, ServerFnError> {
+    require_permission(cx, Permission::AccessAdmin).await?;
+    Ok(crate::postgres::roles().await?)
+}
+
+/// Grants a role to a user.
+#[tracing::instrument(skip_all)]
+#[server(GrantRole, "/api")]
+pub async fn grant_role(cx: Scope, username: Username, role: String) -> Result<(), ServerFnError> {
+    use crate::postgres;
+
+    let granted_by = require_permission(cx, Permission::ManageRoles).await?;
+    let user_id = postgres::user_id(&username)
+        .await?
+        .ok_or_else(|| ServerFnError::Args(format!("the user {username} doesn't exist")))?;
+    match postgres::grant_role(user_id, &role, Some(granted_by)).await {
+        Err(postgres::Error::ForeignKeyViolation(_)) => Err(ServerFnError::Args(format!(
+            "the role {role} doesn't exist"
+        ))),
+        result => Ok(result?),
+    }
+}
+
+/// Revokes a role from a user.
+#[tracing::instrument(skip_all)]
+#[server(RevokeRole, "/api")]
+pub async fn revoke_role(cx: Scope, username: Username, role: String) -> Result<(), ServerFnError> {
+    use crate::postgres;
+
+    let revoked_by = require_permission(cx, Permission::ManageRoles).await?;
+    let user_id = postgres::user_id(&username)
+        .await?
+        .ok_or_else(|| ServerFnError::Args(format!("the user {username} doesn't exist")))?;
+    // so admins can't lock themselves out
+    if user_id == revoked_by {
+        return Err(ServerFnError::Args(
+            "you can't revoke your own roles".to_owned(),
+        ));
+    }
+    postgres::revoke_role(user_id, &role).await?;
+    Ok(())
+}
+
+/// Returns whether any of the user's roles grants the permission. Every check
+/// of a permission goes through here.
+#[cfg(feature = "ssr")]
+pub async fn has_permission(
+    user_id: UserId,
+    permission: Permission,
+) -> Result<bool, crate::postgres::Error> {
+    crate::postgres::has_permission(user_id, permission).await
+}
+
+/// Returns the id of the signed in user or an error if the user doesn't have
+/// the permission.
+#[cfg(feature = "ssr")]
+pub async fn require_permission(
+    cx: Scope,
+    permission: Permission,
+) -> Result<UserId, ServerFnError> {
+    let user_id = crate::session::require_user_id(cx).await?;
+    if !has_permission(user_id, permission).await? {
+        return Err(ServerFnError::ServerError(format!(
+            "missing the {permission} permission"
+        )));
+    }
+    Ok(user_id)
+}
+
+/// Returns whether the user moderates the community, or the content and users
+/// outside of communities if there's none. Users with the
+/// [`ModerateAllCommunities`](Permission::ModerateAllCommunities) permission
+/// moderate everything. Queries listing what a user moderates use the
+/// `moderates` SQL function, which this calls too.
+#[cfg(feature = "ssr")]
+pub async fn moderates(
+    user_id: UserId,
+    community_id: Option<CommunityId>,
+) -> Result<bool, crate::postgres::Error> {
+    crate::postgres::moderates(user_id, community_id).await
+}
+
+/// Returns an error if the user doesn't moderate the community, or the content
+/// and users outside of communities if there's none.
+#[cfg(feature = "ssr")]
+pub async fn require_moderator(
+    user_id: UserId,
+    community_id: Option<CommunityId>,
+) -> Result<(), ServerFnError> {
+    if !moderates(user_id, community_id).await? {
+        return Err(ServerFnError::ServerError(match community_id {
+            Some(_) => "not a moderator of the community".to_owned(),
+            None => format!(
+                "missing the {} permission",
+                Permission::ModerateAllCommunities
+            ),
+        }));
+    }
+    Ok(())
+}
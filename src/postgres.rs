@@ -2,11 +2,23 @@
 #![cfg(feature = "ssr")]
 
 use {
-    crate::config::PostgresConfig,
+    crate::{
+        community,
+        config::PostgresConfig,
+        content,
+        internationalization::Language,
+        legal::{self, Document},
+        link_preview,
+        metrics::metrics,
+        moderation,
+        permissions::{self, Permission},
+        sanctions, search, sign_in, spam_filter,
+        types::*,
+    },
     anyhow::Result,
-    marzichat::{internationalization::Language, types::*},
-    sqlx::{error::DatabaseError, Pool, Postgres},
+    sqlx::{error::DatabaseError, pool::PoolConnection, Pool, Postgres, Transaction},
     std::sync::OnceLock,
+    uuid::Uuid,
 };
 
 static POOL: OnceLock<Pool<Postgres>> = OnceLock::new();
@@ -34,6 +46,18 @@ pub async fn init(config: PostgresConfig) {
         .expect("postgres connection pool already initialized");
 }
 
+/// Sets a connection pool that connects on first use, for tests that don't
+/// need postgres to be reachable.
+#[cfg(test)]
+pub(crate) fn init_lazy(url: &str) {
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .acquire_timeout(std::time::Duration::from_millis(100))
+        .connect_lazy(url)
+        .expect("invalid postgres url");
+    POOL.set(pool)
+        .expect("postgres connection pool already initialized");
+}
+
 /// Returns a reference to the postgres connection pool.
 #[inline]
 fn db() -> &'static Pool<Postgres> {
@@ -41,6 +65,85 @@ fn db() -> &'static Pool<Postgres> {
         .expect("postgres connection pool is not initialized")
 }
 
+/// Acquires a connection from the pool, recording how long it waited.
+async fn connection() -> Result<PoolConnection<Postgres>, Error> {
+    let _waiting = Waiting::start();
+    let _timer = metrics().postgres_acquire_duration.start_timer();
+    Ok(db().acquire().await?)
+}
+
+/// Begins a transaction on a connection from the pool, recording how long it
+/// waited for the connection, including the round trip of `BEGIN`.
+async fn begin() -> Result<Transaction<'static, Postgres>, Error> {
+    let _waiting = Waiting::start();
+    let _timer = metrics().postgres_acquire_duration.start_timer();
+    Ok(db().begin().await?)
+}
+
+/// Counts a query as waiting for a connection until dropped, so cancelled
+/// queries stop counting too.
+struct Waiting;
+
+impl Waiting {
+    fn start() -> Self {
+        metrics().postgres_acquire_waiting.inc();
+        Self
+    }
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        metrics().postgres_acquire_waiting.dec();
+    }
+}
+
+/// The state of the postgres connection pool.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolStats {
+    /// The number of open connections.
+    pub size: u32,
+
+    /// The number of idle connections.
+    pub idle: usize,
+
+    /// The maximum number of connections.
+    pub max: u32,
+}
+
+/// Returns the state of the postgres connection pool, if it's initialized.
+pub fn pool_stats() -> Option<PoolStats> {
+    POOL.get().map(|pool| PoolStats {
+        size: pool.size(),
+        idle: pool.num_idle(),
+        max: pool.options().get_max_connections(),
+    })
+}
+
+/// Runs a trivial query to check that postgres can be reached.
+#[tracing::instrument(skip_all)]
+pub async fn ping() -> Result<(), Error> {
+    sqlx::query("SELECT 1")
+        .execute(&mut *connection().await?)
+        .await?;
+    Ok(())
+}
+
+/// Returns the number of migrations embedded in the binary that haven't been
+/// applied successfully.
+#[tracing::instrument(skip_all)]
+pub async fn pending_migrations() -> Result<usize, Error> {
+    let applied: Vec<i64> =
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(&mut *connection().await?)
+            .await?;
+    Ok(sqlx::migrate!()
+        .iter()
+        .filter(|migration| {
+            !migration.migration_type.is_down_migration() && !applied.contains(&migration.version)
+        })
+        .count())
+}
+
 /// A Postgres error.
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -80,37 +183,43 @@ impl From<sqlx::Error> for Error {
 }
 
 /// Check whether the username is not associated with a user.
+#[tracing::instrument(skip_all)]
 pub async fn is_username_available(username: &Username) -> Result<bool, Error> {
     Ok(sqlx::query!(
         "SELECT EXISTS (SELECT 1 FROM users WHERE username = $1)",
         username.0
     )
-    .fetch_one(db())
+    .fetch_one(&mut *connection().await?)
     .await?
     .exists
     .map_or(true, |value| !value))
 }
+
 /// Check whether the email address is not associated with a user.
+#[tracing::instrument(skip_all)]
 pub async fn is_email_available(email: &Email) -> Result<bool, Error> {
     Ok(sqlx::query!(
         "SELECT EXISTS (SELECT 1 FROM users WHERE email = $1)",
         email.0
     )
-    .fetch_one(db())
+    .fetch_one(&mut *connection().await?)
     .await?
     .exists
     .map_or(true, |value| !value))
 }
 
-/// Create a user.
+/// Create a user who accepted the given versions of the legal documents.
+#[tracing::instrument(skip_all)]
 pub async fn create_user(
     user_id: UserId,
     username: &Username,
     email: &Email,
     phc_string: &str,
     language: Language,
+    accepted: &[legal::Version],
 ) -> Result<(), Error> {
     let now = chrono::Utc::now();
+    let mut transaction = begin().await?;
     sqlx::query!(
         "INSERT INTO users VALUES ($1, $2, $3, $4, $5, $6, $7)",
         user_id.0,
@@ -121,8 +230,1621 @@ pub async fn create_user(
         phc_string,
         language as Language,
     )
-    .execute(db())
+    .execute(&mut *transaction)
+    .await?;
+
+    for version in accepted {
+        sqlx::query!(
+            "INSERT INTO legal_acceptances VALUES ($1, $2, $3, $4)",
+            user_id.0,
+            version.document as Document,
+            version.effective,
+            now,
+        )
+        .execute(&mut *transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Returns every version of the legal documents the user has accepted.
+#[tracing::instrument(skip_all)]
+pub async fn accepted_legal_versions(user_id: UserId) -> Result<Vec<legal::Version>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT document AS "document: Document", version FROM legal_acceptances WHERE user_id = $1"#,
+        user_id.0
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| legal::Version {
+        document: row.document,
+        effective: row.version,
+    })
+    .collect())
+}
+
+/// Records that the user accepted the given versions of the legal documents.
+#[tracing::instrument(skip_all)]
+pub async fn accept_legal_versions(
+    user_id: UserId,
+    versions: &[legal::Version],
+) -> Result<(), Error> {
+    let now = chrono::Utc::now();
+    for version in versions {
+        sqlx::query!(
+            "INSERT INTO legal_acceptances VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+            user_id.0,
+            version.document as Document,
+            version.effective,
+            now,
+        )
+        .execute(&mut *connection().await?)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Create a session for the user.
+#[tracing::instrument(skip_all)]
+pub async fn create_session(
+    session_id: Uuid,
+    user_id: UserId,
+    expires: DateTime,
+) -> Result<(), Error> {
+    sqlx::query!(
+        "INSERT INTO sessions VALUES ($1, $2, $3, $4)",
+        session_id,
+        user_id.0,
+        chrono::Utc::now(),
+        expires,
+    )
+    .execute(&mut *connection().await?)
+    .await?;
+    Ok(())
+}
+
+/// Returns the user of the session and when the session expires if the
+/// session exists and hasn't expired.
+#[tracing::instrument(skip_all)]
+pub async fn session_user(session_id: Uuid) -> Result<Option<(UserId, DateTime)>, Error> {
+    Ok(sqlx::query!(
+        "SELECT user_id, expires FROM sessions WHERE session_id = $1 AND expires > now()",
+        session_id
+    )
+    .fetch_optional(&mut *connection().await?)
+    .await?
+    .map(|row| (UserId(row.user_id), row.expires)))
+}
+
+/// Create a post together with the verdicts of the spam filter, held if the
+/// post broke any rule. The body html must already be rendered and sanitized.
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(skip_all)]
+pub async fn create_post(
+    post_id: PostId,
+    community_id: CommunityId,
+    author: UserId,
+    title: &Title,
+    url: Option<&str>,
+    domain: Option<&str>,
+    body: &Text,
+    body_html: &str,
+    filtered: &spam_filter::Outcome,
+) -> Result<(), Error> {
+    let now = chrono::Utc::now();
+    let mut transaction = begin().await?;
+    sqlx::query!(
+        "INSERT INTO posts
+            (post_id, created, updated, community_id, author, title, url, domain, body, body_html,
+                content_hash, held)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        post_id.0,
+        now,
+        now,
+        community_id.0,
+        author.0,
+        title.0,
+        url,
+        domain,
+        body.0,
+        body_html,
+        filtered.content_hash,
+        filtered.held(),
+    )
+    .execute(&mut *transaction)
+    .await?;
+    for verdict in &filtered.verdicts {
+        sqlx::query!(
+            "INSERT INTO filter_verdicts (post_id, rule, passed, details) VALUES ($1, $2, $3, $4)",
+            post_id.0,
+            verdict.rule as spam_filter::Rule,
+            verdict.passed,
+            verdict.details,
+        )
+        .execute(&mut *transaction)
+        .await?;
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Maps a row of a query selecting the columns of [`content::Post`].
+macro_rules! post_from_row {
+    ($row:expr) => {{
+        let row = $row;
+        content::Post {
+            post_id: PostId(row.post_id),
+            created: row.created,
+            community: row.community.map(CommunityName),
+            author: Username(row.username),
+            title: Title(row.title),
+            url: row.url,
+            domain: row.domain,
+            thumbnail_url: row.image_url,
+            comment_count: row.comment_count,
+            body_html: row.body_html,
+            pinned: row.pinned,
+            locked: row.locked,
+            removed: row.removed.is_some(),
+            held: row.held,
+        }
+    }};
+}
+
+/// Returns a post, including removed posts. The body and link of removed
+/// posts are only returned to their authors and the moderators of their
+/// community. Posts and comments of shadow-banned users are only returned to
+/// their authors.
+#[tracing::instrument(skip_all)]
+pub async fn post(post_id: PostId, viewer: Option<UserId>) -> Result<Option<content::Post>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT post_id, posts.created, communities.name AS "community?", username, posts.title,
+            CASE WHEN readable THEN url END AS url,
+            CASE WHEN readable THEN domain END AS domain,
+            CASE WHEN readable THEN image_url END AS "image_url?",
+            CASE WHEN readable THEN body_html ELSE '' END AS "body_html!",
+            pinned, locked, removed, held,
+            (SELECT count(*) FROM comments
+                WHERE comments.post_id = posts.post_id
+                    AND (comments.author = $2
+                        OR (NOT comments.held
+                            AND NOT is_shadow_banned(comments.author, posts.community_id)))
+            ) AS "comment_count!"
+        FROM posts
+        JOIN users ON author = user_id
+        LEFT JOIN communities USING (community_id)
+        LEFT JOIN link_previews USING (post_id)
+        CROSS JOIN LATERAL (
+            SELECT posts.removed IS NULL
+                OR posts.author = $2
+                OR moderates($2, posts.community_id) AS readable
+        ) AS visibility
+        WHERE post_id = $1
+            AND (author = $2 OR (NOT held AND NOT is_shadow_banned(author, posts.community_id)))"#,
+        post_id.0,
+        viewer.map(|id| id.0)
+    )
+    .fetch_optional(&mut *connection().await?)
+    .await?
+    .map(|row| post_from_row!(row)))
+}
+
+/// Returns a page of the newest posts, created before the given time if any.
+/// Posts and comments of shadow-banned users are only returned to their
+/// authors.
+#[tracing::instrument(skip_all)]
+pub async fn newest_posts(
+    before: Option<DateTime>,
+    viewer: Option<UserId>,
+    limit: i64,
+) -> Result<Vec<content::Post>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT post_id, posts.created, communities.name AS "community?", username, posts.title,
+            url, domain, image_url AS "image_url?", body_html, pinned, locked, removed, held,
+            (SELECT count(*) FROM comments
+                WHERE comments.post_id = posts.post_id
+                    AND (comments.author = $3
+                        OR (NOT comments.held
+                            AND NOT is_shadow_banned(comments.author, posts.community_id)))
+            ) AS "comment_count!"
+        FROM posts
+        JOIN users ON author = user_id
+        LEFT JOIN communities USING (community_id)
+        LEFT JOIN link_previews USING (post_id)
+        WHERE removed IS NULL
+            AND ($1::TIMESTAMPTZ IS NULL OR posts.created < $1)
+            AND (author = $3 OR (NOT held AND NOT is_shadow_banned(author, posts.community_id)))
+        ORDER BY posts.created DESC
+        LIMIT $2"#,
+        before,
+        limit,
+        viewer.map(|id| id.0)
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| post_from_row!(row))
+    .collect())
+}
+
+/// Returns a page of a community's posts: the pinned posts followed by the
+/// newest posts on the first page, and the posts created before the given time
+/// on the following pages. Posts and comments of shadow-banned users are only
+/// returned to their authors.
+#[tracing::instrument(skip_all)]
+pub async fn community_posts(
+    community_id: CommunityId,
+    before: Option<DateTime>,
+    viewer: Option<UserId>,
+    limit: i64,
+) -> Result<Vec<content::Post>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT post_id, posts.created, communities.name AS "community?", username, posts.title,
+            url, domain, image_url AS "image_url?", body_html, pinned, locked, removed, held,
+            (SELECT count(*) FROM comments
+                WHERE comments.post_id = posts.post_id
+                    AND (comments.author = $4
+                        OR (NOT comments.held
+                            AND NOT is_shadow_banned(comments.author, posts.community_id)))
+            ) AS "comment_count!"
+        FROM posts
+        JOIN users ON author = user_id
+        LEFT JOIN communities USING (community_id)
+        LEFT JOIN link_previews USING (post_id)
+        WHERE community_id = $1
+            AND removed IS NULL
+            AND ($2::TIMESTAMPTZ IS NULL OR (NOT pinned AND posts.created < $2))
+            AND (author = $4 OR (NOT held AND NOT is_shadow_banned(author, community_id)))
+        ORDER BY pinned DESC, posts.created DESC
+        LIMIT $3"#,
+        community_id.0,
+        before,
+        limit,
+        viewer.map(|id| id.0)
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| post_from_row!(row))
+    .collect())
+}
+
+/// Returns the community and author of a post.
+#[tracing::instrument(skip_all)]
+pub async fn post_subject(post_id: PostId) -> Result<Option<moderation::Subject>, Error> {
+    Ok(sqlx::query!(
+        "SELECT community_id, author FROM posts WHERE post_id = $1",
+        post_id.0
+    )
+    .fetch_optional(&mut *connection().await?)
+    .await?
+    .map(|row| moderation::Subject {
+        community_id: row.community_id.map(CommunityId),
+        post_id: Some(post_id),
+        comment_id: None,
+        user_id: UserId(row.author),
+    }))
+}
+
+/// Returns the community and author of a comment.
+#[tracing::instrument(skip_all)]
+pub async fn comment_subject(comment_id: CommentId) -> Result<Option<moderation::Subject>, Error> {
+    Ok(sqlx::query!(
+        "SELECT community_id, comments.author
+        FROM comments JOIN posts USING (post_id)
+        WHERE comment_id = $1",
+        comment_id.0
+    )
+    .fetch_optional(&mut *connection().await?)
+    .await?
+    .map(|row| moderation::Subject {
+        community_id: row.community_id.map(CommunityId),
+        post_id: None,
+        comment_id: Some(comment_id),
+        user_id: UserId(row.author),
+    }))
+}
+
+/// Returns the posts with a url whose preview hasn't been fetched yet.
+#[tracing::instrument(skip_all)]
+pub async fn pending_link_previews() -> Result<Vec<(PostId, String)>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT post_id, url AS "url!" FROM posts
+        WHERE url IS NOT NULL AND NOT EXISTS (SELECT 1 FROM link_previews WHERE link_previews.post_id = posts.post_id)"#
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| (PostId(row.post_id), row.url))
+    .collect())
+}
+
+/// Stores the fetched preview of a post's url.
+#[tracing::instrument(skip_all)]
+pub async fn set_link_preview(
+    post_id: PostId,
+    metadata: &link_preview::Metadata,
+) -> Result<(), Error> {
+    sqlx::query!(
+        "INSERT INTO link_previews VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (post_id) DO UPDATE SET
+            fetched = $2, title = $3, description = $4, site_name = $5, image_url = $6",
+        post_id.0,
+        chrono::Utc::now(),
+        metadata.title,
+        metadata.description,
+        metadata.site_name,
+        metadata.image_url,
+    )
+    .execute(&mut *connection().await?)
+    .await?;
+    Ok(())
+}
+
+/// Returns the comments of a post, oldest first, each together with the id of
+/// the comment it replies to. Comments of shadow-banned users are only
+/// returned to their authors, together with their replies.
+#[tracing::instrument(skip_all)]
+pub async fn comments(
+    post_id: PostId,
+    viewer: Option<UserId>,
+) -> Result<Vec<(Option<CommentId>, content::Comment)>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT comment_id, parent_id, comments.created, username,
+            CASE WHEN comments.removed IS NULL THEN comments.body_html ELSE '' END AS "body_html!",
+            comments.removed IS NOT NULL AS "removed!",
+            comments.held
+        FROM comments
+        JOIN users ON comments.author = user_id
+        JOIN posts USING (post_id)
+        WHERE post_id = $1
+            AND (comments.author = $2
+                OR (NOT comments.held
+                    AND NOT is_shadow_banned(comments.author, posts.community_id)))
+        ORDER BY comments.created"#,
+        post_id.0,
+        viewer.map(|id| id.0)
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| {
+        (
+            row.parent_id.map(CommentId),
+            content::Comment {
+                comment_id: CommentId(row.comment_id),
+                created: row.created,
+                author: Username(row.username),
+                body_html: row.body_html,
+                removed: row.removed,
+                held: row.held,
+                replies: Vec::new(),
+            },
+        )
+    })
+    .collect())
+}
+
+/// Create a comment on a post together with the verdicts of the spam filter,
+/// held if the comment broke any rule. The body html must already be rendered
+/// and sanitized.
+///
+/// Returns false if the post is locked or removed or the parent comment
+/// doesn't belong to the same post.
+#[tracing::instrument(skip_all)]
+pub async fn create_comment(
+    comment_id: CommentId,
+    post_id: PostId,
+    parent_id: Option<CommentId>,
+    author: UserId,
+    body: &Text,
+    body_html: &str,
+    filtered: &spam_filter::Outcome,
+) -> Result<bool, Error> {
+    let now = chrono::Utc::now();
+    let mut transaction = begin().await?;
+    let created = sqlx::query!(
+        "INSERT INTO comments
+            (comment_id, created, updated, post_id, parent_id, author, body, body_html,
+                content_hash, held)
+        SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+        WHERE EXISTS (SELECT 1 FROM posts WHERE post_id = $4 AND NOT locked AND removed IS NULL)
+            AND ($5::UUID IS NULL
+                OR EXISTS (SELECT 1 FROM comments WHERE comment_id = $5 AND post_id = $4))",
+        comment_id.0,
+        now,
+        now,
+        post_id.0,
+        parent_id.map(|parent_id| parent_id.0),
+        author.0,
+        body.0,
+        body_html,
+        filtered.content_hash,
+        filtered.held(),
+    )
+    .execute(&mut *transaction)
+    .await?
+    .rows_affected()
+        == 1;
+    if !created {
+        return Ok(false);
+    }
+    for verdict in &filtered.verdicts {
+        sqlx::query!(
+            "INSERT INTO filter_verdicts (comment_id, rule, passed, details)
+            VALUES ($1, $2, $3, $4)",
+            comment_id.0,
+            verdict.rule as spam_filter::Rule,
+            verdict.passed,
+            verdict.details,
+        )
+        .execute(&mut *transaction)
+        .await?;
+    }
+    transaction.commit().await?;
+    Ok(true)
+}
+
+/// Returns when a user signed up and their reputation.
+#[tracing::instrument(skip_all)]
+pub async fn author_standing(user_id: UserId) -> Result<(DateTime, i64), Error> {
+    let row = sqlx::query!(
+        r#"SELECT created, reputation(user_id) AS "reputation!" FROM users WHERE user_id = $1"#,
+        user_id.0
+    )
+    .fetch_one(&mut *connection().await?)
+    .await?;
+    Ok((row.created, row.reputation))
+}
+
+/// Returns the number of posts and comments with the content hash created
+/// since the given time.
+#[tracing::instrument(skip_all)]
+pub async fn duplicates(content_hash: &[u8], since: DateTime) -> Result<i64, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT
+            (SELECT count(*) FROM posts WHERE content_hash = $1 AND created > $2)
+            + (SELECT count(*) FROM comments WHERE content_hash = $1 AND created > $2)
+            AS "duplicates!""#,
+        content_hash,
+        since
+    )
+    .fetch_one(&mut *connection().await?)
+    .await?
+    .duplicates)
+}
+
+/// Updates the bio of a user. The bio html must already be rendered and
+/// sanitized.
+#[tracing::instrument(skip_all)]
+pub async fn update_bio(user_id: UserId, bio: &Text, bio_html: &str) -> Result<(), Error> {
+    sqlx::query!(
+        "UPDATE users SET bio = $2, bio_html = $3, updated = $4 WHERE user_id = $1",
+        user_id.0,
+        bio.0,
+        bio_html,
+        chrono::Utc::now(),
+    )
+    .execute(&mut *connection().await?)
+    .await?;
+    Ok(())
+}
+
+/// Searches posts and comments, ordered by relevance. Held posts and comments,
+/// and the ones of shadow-banned users, are only returned to their authors.
+/// Comments are only returned if their post would be too.
+#[tracing::instrument(skip_all)]
+pub async fn search(
+    query: &str,
+    filters: &search::Filters,
+    after: Option<search::Cursor>,
+    viewer: Option<UserId>,
+    limit: i64,
+) -> Result<Vec<search::SearchResult>, Error> {
+    use search::{HIGHLIGHT_END, HIGHLIGHT_START};
+
+    let snippet_options = format!(
+        "StartSel={HIGHLIGHT_START}, StopSel={HIGHLIGHT_END}, MaxFragments=2, MaxWords=30, \
+         MinWords=10, FragmentDelimiter=\" … \""
+    );
+    let title_options =
+        format!("StartSel={HIGHLIGHT_START}, StopSel={HIGHLIGHT_END}, HighlightAll=true");
+
+    Ok(sqlx::query!(
+        r#"WITH query AS (
+            SELECT websearch_to_tsquery('english', $1)
+                || websearch_to_tsquery('spanish', $1)
+                || websearch_to_tsquery('german', $1)
+                || websearch_to_tsquery('french', $1) AS query
+        ), matches AS (
+            SELECT posts.post_id AS id, posts.post_id, NULL::UUID AS comment_id, posts.created,
+                posts.community_id, posts.author, posts.held, posts.search_config, posts.title,
+                posts.body, ts_rank(posts.search, query) AS rank, TRUE AS post_visible
+            FROM posts, query
+            WHERE posts.search @@ query AND posts.removed IS NULL
+            UNION ALL
+            SELECT comments.comment_id, comments.post_id, comments.comment_id, comments.created,
+                posts.community_id, comments.author, comments.held, comments.search_config,
+                posts.title, comments.body, ts_rank(comments.search, query),
+                posts.author = $11
+                    OR (NOT posts.held AND NOT is_shadow_banned(posts.author, posts.community_id))
+            FROM comments JOIN posts USING (post_id), query
+            WHERE comments.search @@ query AND comments.removed IS NULL AND posts.removed IS NULL
+        )
+        SELECT
+            post_id AS "post_id!",
+            comment_id,
+            communities.name AS "community?",
+            matches.created AS "created!",
+            username,
+            ts_headline(search_config, title, query, $8) AS "title!",
+            ts_headline(search_config, body, query, $7) AS "snippet!",
+            rank AS "rank!",
+            id AS "id!"
+        FROM matches
+        JOIN users ON author = user_id
+        LEFT JOIN communities USING (community_id),
+        query
+        WHERE ($2::TEXT IS NULL OR username = $2)
+            AND ($10::TEXT IS NULL OR lower(communities.name) = lower($10))
+            AND ($3::DATE IS NULL OR matches.created >= $3)
+            AND ($4::DATE IS NULL OR matches.created < $4 + 1)
+            AND ($5::REAL IS NULL OR (rank, id) < ($5, $6))
+            AND (author = $11 OR (NOT held AND NOT is_shadow_banned(author, community_id)))
+            AND post_visible
+        ORDER BY rank DESC, id DESC
+        LIMIT $9"#,
+        query,
+        filters.author.as_ref().map(|author| &author.0),
+        filters.from,
+        filters.to,
+        after.map(|after| after.rank),
+        after.map(|after| after.id),
+        snippet_options,
+        title_options,
+        limit,
+        filters.community.as_ref().map(|community| &community.0),
+        viewer.map(|id| id.0),
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| search::SearchResult {
+        post_id: PostId(row.post_id),
+        comment_id: row.comment_id.map(CommentId),
+        community: row.community.map(CommunityName),
+        created: row.created,
+        author: Username(row.username),
+        title_html: search::highlight(&row.title),
+        snippet_html: search::highlight(&row.snippet),
+        cursor: search::Cursor {
+            rank: row.rank,
+            id: row.id,
+        },
+    })
+    .collect())
+}
+
+/// Returns the id of a user.
+#[tracing::instrument(skip_all)]
+pub async fn user_id(username: &Username) -> Result<Option<UserId>, Error> {
+    Ok(
+        sqlx::query!("SELECT user_id FROM users WHERE username = $1", username.0)
+            .fetch_optional(&mut *connection().await?)
+            .await?
+            .map(|row| UserId(row.user_id)),
+    )
+}
+
+/// Create a community with the creator as its first moderator and subscriber.
+#[tracing::instrument(skip_all)]
+pub async fn create_community(
+    community_id: CommunityId,
+    name: &CommunityName,
+    description: &str,
+    rules: &[String],
+    creator: UserId,
+) -> Result<(), Error> {
+    let now = chrono::Utc::now();
+    let mut transaction = begin().await?;
+    sqlx::query!(
+        "INSERT INTO communities VALUES ($1, $2, $3, $4, $5, $6)",
+        community_id.0,
+        now,
+        now,
+        name.0,
+        description,
+        creator.0,
+    )
+    .execute(&mut *transaction)
+    .await?;
+    insert_rules(&mut transaction, community_id, rules).await?;
+    sqlx::query!(
+        "INSERT INTO moderators VALUES ($1, $2, $3)",
+        community_id.0,
+        creator.0,
+        now,
+    )
+    .execute(&mut *transaction)
+    .await?;
+    sqlx::query!(
+        "INSERT INTO subscriptions VALUES ($1, $2, $3)",
+        community_id.0,
+        creator.0,
+        now,
+    )
+    .execute(&mut *transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Replaces the description and rules of a community.
+#[tracing::instrument(skip_all)]
+pub async fn update_community(
+    community_id: CommunityId,
+    description: &str,
+    rules: &[String],
+) -> Result<(), Error> {
+    let mut transaction = begin().await?;
+    sqlx::query!(
+        "UPDATE communities SET description = $2, updated = $3 WHERE community_id = $1",
+        community_id.0,
+        description,
+        chrono::Utc::now(),
+    )
+    .execute(&mut *transaction)
+    .await?;
+    sqlx::query!(
+        "DELETE FROM community_rules WHERE community_id = $1",
+        community_id.0
+    )
+    .execute(&mut *transaction)
+    .await?;
+    insert_rules(&mut transaction, community_id, rules).await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+async fn insert_rules(
+    transaction: &mut Transaction<'_, Postgres>,
+    community_id: CommunityId,
+    rules: &[String],
+) -> Result<(), Error> {
+    for (position, rule) in (0..).zip(rules) {
+        sqlx::query!(
+            "INSERT INTO community_rules VALUES ($1, $2, $3)",
+            community_id.0,
+            position,
+            rule,
+        )
+        .execute(&mut **transaction)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Returns the id of a community.
+#[tracing::instrument(skip_all)]
+pub async fn community_id(name: &CommunityName) -> Result<Option<CommunityId>, Error> {
+    Ok(sqlx::query!(
+        "SELECT community_id FROM communities WHERE lower(name) = lower($1)",
+        name.0
+    )
+    .fetch_optional(&mut *connection().await?)
+    .await?
+    .map(|row| CommunityId(row.community_id)))
+}
+
+/// Returns a community and its sidebar.
+#[tracing::instrument(skip_all)]
+pub async fn community(name: &CommunityName) -> Result<Option<community::Community>, Error> {
+    let Some(row) = sqlx::query!(
+        r#"SELECT community_id, name, created, description,
+            (SELECT count(*) FROM subscriptions WHERE subscriptions.community_id = communities.community_id) AS "subscriber_count!"
+        FROM communities
+        WHERE lower(name) = lower($1)"#,
+        name.0
+    )
+    .fetch_optional(&mut *connection().await?)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let rules = sqlx::query!(
+        "SELECT rule FROM community_rules WHERE community_id = $1 ORDER BY position",
+        row.community_id
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|rule| rule.rule)
+    .collect();
+
+    let moderators = sqlx::query!(
+        "SELECT username FROM moderators JOIN users USING (user_id)
+        WHERE community_id = $1
+        ORDER BY moderators.created",
+        row.community_id
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|moderator| Username(moderator.username))
+    .collect();
+
+    Ok(Some(community::Community {
+        name: CommunityName(row.name),
+        created: row.created,
+        description: row.description,
+        rules,
+        subscriber_count: row.subscriber_count,
+        moderators,
+    }))
+}
+
+/// Returns all communities, the ones with the most subscribers first.
+#[tracing::instrument(skip_all)]
+pub async fn communities() -> Result<Vec<community::CommunitySummary>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT name, description, count(user_id) AS "subscriber_count!"
+        FROM communities LEFT JOIN subscriptions USING (community_id)
+        GROUP BY community_id
+        ORDER BY count(user_id) DESC, name"#
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| community::CommunitySummary {
+        name: CommunityName(row.name),
+        description: row.description,
+        subscriber_count: row.subscriber_count,
+    })
+    .collect())
+}
+
+/// Returns how a user relates to a community.
+#[tracing::instrument(skip_all)]
+pub async fn membership(
+    community_id: CommunityId,
+    user_id: UserId,
+) -> Result<community::Membership, Error> {
+    let row = sqlx::query!(
+        r#"SELECT
+            EXISTS (SELECT 1 FROM subscriptions WHERE community_id = $1 AND user_id = $2) AS "subscribed!",
+            moderates($2, $1) AS "moderator!""#,
+        community_id.0,
+        user_id.0
+    )
+    .fetch_one(&mut *connection().await?)
+    .await?;
+    Ok(community::Membership {
+        subscribed: row.subscribed,
+        moderator: row.moderator,
+    })
+}
+
+/// Subscribes a user to a community or unsubscribes them.
+#[tracing::instrument(skip_all)]
+pub async fn set_subscribed(
+    community_id: CommunityId,
+    user_id: UserId,
+    subscribed: bool,
+) -> Result<(), Error> {
+    if subscribed {
+        sqlx::query!(
+            "INSERT INTO subscriptions VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+            community_id.0,
+            user_id.0,
+            chrono::Utc::now(),
+        )
+        .execute(&mut *connection().await?)
+        .await?;
+    } else {
+        sqlx::query!(
+            "DELETE FROM subscriptions WHERE community_id = $1 AND user_id = $2",
+            community_id.0,
+            user_id.0,
+        )
+        .execute(&mut *connection().await?)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Makes a user a moderator of a community.
+#[tracing::instrument(skip_all)]
+pub async fn add_moderator(community_id: CommunityId, user_id: UserId) -> Result<(), Error> {
+    sqlx::query!(
+        "INSERT INTO moderators VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+        community_id.0,
+        user_id.0,
+        chrono::Utc::now(),
+    )
+    .execute(&mut *connection().await?)
+    .await?;
+    Ok(())
+}
+
+/// Create a report about the subject.
+#[tracing::instrument(skip_all)]
+pub async fn create_report(
+    report_id: ReportId,
+    reporter: UserId,
+    reason: moderation::Reason,
+    details: &str,
+    subject: &moderation::Subject,
+) -> Result<(), Error> {
+    // reports about content don't name its author so that the report is
+    // about exactly one thing
+    let user_id =
+        (subject.post_id.is_none() && subject.comment_id.is_none()).then_some(subject.user_id);
+    sqlx::query!(
+        "INSERT INTO reports
+            (report_id, created, reporter, reason, details, community_id, post_id, comment_id, user_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        report_id.0,
+        chrono::Utc::now(),
+        reporter.0,
+        reason as moderation::Reason,
+        details,
+        subject.community_id.map(|id| id.0),
+        subject.post_id.map(|id| id.0),
+        subject.comment_id.map(|id| id.0),
+        user_id.map(|id| id.0),
+    )
+    .execute(&mut *connection().await?)
+    .await?;
+    Ok(())
+}
+
+/// Returns the open reports in the communities a user moderates, or all open
+/// reports if the user moderates all communities, the oldest first.
+#[tracing::instrument(skip_all)]
+pub async fn open_reports(moderator: UserId) -> Result<Vec<moderation::Report>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT
+            report_id,
+            reports.created,
+            reporter.username AS reporter,
+            reason AS "reason: moderation::Reason",
+            details,
+            communities.name AS "community?",
+            COALESCE(reports.post_id, comments.post_id) AS post_id,
+            reports.comment_id,
+            posts.title AS "title?",
+            comments.body_html AS "body_html?",
+            target.username AS target
+        FROM reports
+        JOIN users reporter ON reporter.user_id = reports.reporter
+        LEFT JOIN communities USING (community_id)
+        LEFT JOIN posts ON posts.post_id = reports.post_id
+        LEFT JOIN comments ON comments.comment_id = reports.comment_id
+        JOIN users target ON target.user_id = COALESCE(posts.author, comments.author, reports.user_id)
+        WHERE resolved IS NULL
+            AND moderates($1, reports.community_id)
+        ORDER BY reports.created"#,
+        moderator.0
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| moderation::Report {
+        report_id: ReportId(row.report_id),
+        created: row.created,
+        reporter: Username(row.reporter),
+        reason: row.reason,
+        details: row.details,
+        community: row.community.map(CommunityName),
+        target: match (row.post_id, row.comment_id) {
+            (Some(post_id), Some(comment_id)) => moderation::Target::Comment {
+                post_id: PostId(post_id),
+                comment_id: CommentId(comment_id),
+                body_html: row.body_html.unwrap_or_default(),
+                author: Username(row.target),
+            },
+            (Some(post_id), None) => moderation::Target::Post {
+                post_id: PostId(post_id),
+                title: Title(row.title.unwrap_or_default()),
+                author: Username(row.target),
+            },
+            _ => moderation::Target::User {
+                username: Username(row.target),
+            },
+        },
+    })
+    .collect())
+}
+
+/// Returns the held posts and comments that weren't removed in the
+/// communities the moderator moderates, the oldest first, together with the
+/// verdicts of the spam filter.
+#[tracing::instrument(skip_all)]
+pub async fn held_content(moderator: UserId) -> Result<Vec<moderation::Held>, Error> {
+    Ok(sqlx::query!(
+        r#"WITH held AS (
+            SELECT post_id, NULL::UUID AS comment_id, created, community_id, author, title,
+                NULL AS body_html
+            FROM posts
+            WHERE held AND removed IS NULL
+            UNION ALL
+            SELECT comments.post_id, comments.comment_id, comments.created, posts.community_id,
+                comments.author, posts.title, comments.body_html
+            FROM comments JOIN posts USING (post_id)
+            WHERE comments.held AND comments.removed IS NULL
+        )
+        SELECT
+            held.post_id AS "post_id!",
+            held.comment_id,
+            held.created AS "created!",
+            communities.name AS "community?",
+            username,
+            held.title AS "title!",
+            held.body_html,
+            ARRAY(
+                SELECT rule FROM filter_verdicts
+                WHERE post_id = held.post_id AND held.comment_id IS NULL
+                    OR comment_id = held.comment_id
+                ORDER BY rule
+            ) AS "rules!: Vec<spam_filter::Rule>",
+            ARRAY(
+                SELECT passed FROM filter_verdicts
+                WHERE post_id = held.post_id AND held.comment_id IS NULL
+                    OR comment_id = held.comment_id
+                ORDER BY rule
+            ) AS "passed!",
+            ARRAY(
+                SELECT details FROM filter_verdicts
+                WHERE post_id = held.post_id AND held.comment_id IS NULL
+                    OR comment_id = held.comment_id
+                ORDER BY rule
+            ) AS "details!"
+        FROM held
+        JOIN users ON author = user_id
+        LEFT JOIN communities USING (community_id)
+        WHERE moderates($1, held.community_id)
+        ORDER BY held.created"#,
+        moderator.0
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| moderation::Held {
+        created: row.created,
+        community: row.community.map(CommunityName),
+        target: match row.comment_id {
+            Some(comment_id) => moderation::Target::Comment {
+                post_id: PostId(row.post_id),
+                comment_id: CommentId(comment_id),
+                body_html: row.body_html.unwrap_or_default(),
+                author: Username(row.username),
+            },
+            None => moderation::Target::Post {
+                post_id: PostId(row.post_id),
+                title: Title(row.title),
+                author: Username(row.username),
+            },
+        },
+        verdicts: row
+            .rules
+            .into_iter()
+            .zip(row.passed)
+            .zip(row.details)
+            .map(|((rule, passed), details)| spam_filter::Verdict {
+                rule,
+                passed,
+                details,
+            })
+            .collect(),
+    })
+    .collect())
+}
+
+/// Returns the subject of an open report.
+#[tracing::instrument(skip_all)]
+pub async fn open_report_subject(
+    report_id: ReportId,
+) -> Result<Option<moderation::Subject>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT
+            reports.community_id,
+            reports.post_id,
+            reports.comment_id,
+            COALESCE(posts.author, comments.author, reports.user_id) AS "user_id!"
+        FROM reports
+        LEFT JOIN posts USING (post_id)
+        LEFT JOIN comments USING (comment_id)
+        WHERE report_id = $1 AND resolved IS NULL"#,
+        report_id.0
+    )
+    .fetch_optional(&mut *connection().await?)
+    .await?
+    .map(|row| moderation::Subject {
+        community_id: row.community_id.map(CommunityId),
+        post_id: row.post_id.map(PostId),
+        comment_id: row.comment_id.map(CommentId),
+        user_id: UserId(row.user_id),
+    }))
+}
+
+/// Takes a moderation action on the subject and records it in the moderation
+/// log. If the action was taken on a report, all open reports about the same
+/// subject are resolved.
+#[tracing::instrument(skip_all)]
+pub async fn moderate(
+    moderator: UserId,
+    action: moderation::Action,
+    subject: &moderation::Subject,
+    report_id: Option<ReportId>,
+    reason: &str,
+) -> Result<(), Error> {
+    use moderation::Action;
+
+    let now = chrono::Utc::now();
+    let post_id = subject.post_id.map(|id| id.0);
+    let comment_id = subject.comment_id.map(|id| id.0);
+    let community_id = subject.community_id.map(|id| id.0);
+    let mut transaction = begin().await?;
+
+    match action {
+        Action::Remove if comment_id.is_some() => {
+            sqlx::query!(
+                "UPDATE comments SET removed = $2 WHERE comment_id = $1 AND removed IS NULL",
+                comment_id,
+                now,
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+        Action::Remove => {
+            sqlx::query!(
+                "UPDATE posts SET removed = $2, pinned = false WHERE post_id = $1 AND removed IS NULL",
+                post_id,
+                now,
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+        Action::Ban => {
+            sqlx::query!(
+                "INSERT INTO sanctions (sanction_id, created, user_id, community_id, kind, reason,
+                    issued_by)
+                VALUES ($1, $2, $3, $4, 'Ban', $5, $6)",
+                Uuid::new_v4(),
+                now,
+                subject.user_id.0,
+                community_id,
+                reason,
+                moderator.0,
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+        Action::Pin | Action::Unpin => {
+            sqlx::query!(
+                "UPDATE posts SET pinned = $2 WHERE post_id = $1",
+                post_id,
+                action == Action::Pin,
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+        Action::Lock | Action::Unlock => {
+            sqlx::query!(
+                "UPDATE posts SET locked = $2 WHERE post_id = $1",
+                post_id,
+                action == Action::Lock,
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+        Action::Approve if comment_id.is_some() => {
+            sqlx::query!(
+                "UPDATE comments SET held = false WHERE comment_id = $1",
+                comment_id,
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+        Action::Approve => {
+            sqlx::query!("UPDATE posts SET held = false WHERE post_id = $1", post_id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        Action::Warn | Action::Suspend | Action::ShadowBan | Action::Lift => {}
+    }
+
+    sqlx::query!(
+        "INSERT INTO moderation_log
+            (entry_id, created, moderator, action, community_id, target_user_id, post_id,
+                comment_id, report_id, reason)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+        Uuid::new_v4(),
+        now,
+        moderator.0,
+        action as Action,
+        community_id,
+        subject.user_id.0,
+        post_id,
+        comment_id,
+        report_id.map(|id| id.0),
+        reason,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    if let Some(report_id) = report_id {
+        sqlx::query!(
+            "UPDATE reports SET resolved = $2
+            FROM (SELECT post_id, comment_id, user_id FROM reports WHERE report_id = $1) AS subject
+            WHERE reports.resolved IS NULL
+                AND reports.post_id IS NOT DISTINCT FROM subject.post_id
+                AND reports.comment_id IS NOT DISTINCT FROM subject.comment_id
+                AND reports.user_id IS NOT DISTINCT FROM subject.user_id",
+            report_id.0,
+            now,
+        )
+        .execute(&mut *transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Returns the removals, suspensions, bans and warnings of a user, the newest
+/// first.
+#[tracing::instrument(skip_all)]
+pub async fn notices(user_id: UserId) -> Result<Vec<moderation::Notice>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT
+            moderation_log.created,
+            action AS "action: moderation::Action",
+            communities.name AS "community?",
+            post_id,
+            comment_id,
+            reason
+        FROM moderation_log LEFT JOIN communities USING (community_id)
+        WHERE target_user_id = $1 AND action IN ('Remove', 'Suspend', 'Ban', 'Warn')
+        ORDER BY moderation_log.created DESC
+        LIMIT 100"#,
+        user_id.0
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| moderation::Notice {
+        created: row.created,
+        action: row.action,
+        community: row.community.map(CommunityName),
+        post_id: row.post_id.map(PostId),
+        comment_id: row.comment_id.map(CommentId),
+        reason: row.reason,
+    })
+    .collect())
+}
+
+/// Returns whether any of a user's roles grants the permission.
+#[tracing::instrument(skip_all)]
+pub async fn has_permission(user_id: UserId, permission: Permission) -> Result<bool, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT has_permission($1, $2) AS "has_permission!""#,
+        user_id.0,
+        permission as Permission,
+    )
+    .fetch_one(&mut *connection().await?)
+    .await?
+    .has_permission)
+}
+
+/// Returns whether a user moderates the community, or the content and users
+/// outside of communities if there's none.
+#[tracing::instrument(skip_all)]
+pub async fn moderates(user_id: UserId, community_id: Option<CommunityId>) -> Result<bool, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT moderates($1, $2) AS "moderates!""#,
+        user_id.0,
+        community_id.map(|community_id| community_id.0),
+    )
+    .fetch_one(&mut *connection().await?)
+    .await?
+    .moderates)
+}
+
+/// Returns all roles with their permissions and members.
+#[tracing::instrument(skip_all)]
+pub async fn roles() -> Result<Vec<permissions::Role>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT
+            name,
+            description,
+            ARRAY(
+                SELECT permission FROM role_permissions WHERE role = name ORDER BY permission
+            ) AS "permissions!: Vec<Permission>",
+            ARRAY(
+                SELECT username FROM user_roles JOIN users USING (user_id)
+                WHERE role = name
+                ORDER BY username
+            ) AS "members!"
+        FROM roles
+        ORDER BY name"#
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| permissions::Role {
+        name: row.name,
+        description: row.description,
+        permissions: row.permissions,
+        members: row.members.into_iter().map(Username).collect(),
+    })
+    .collect())
+}
+
+/// Grants a role to a user. Granting a role the user already has does
+/// nothing.
+#[tracing::instrument(skip_all)]
+pub async fn grant_role(
+    user_id: UserId,
+    role: &str,
+    granted_by: Option<UserId>,
+) -> Result<(), Error> {
+    sqlx::query!(
+        "INSERT INTO user_roles VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+        user_id.0,
+        role,
+        chrono::Utc::now(),
+        granted_by.map(|id| id.0),
+    )
+    .execute(&mut *connection().await?)
+    .await?;
+    Ok(())
+}
+
+/// Revokes a role from a user.
+#[tracing::instrument(skip_all)]
+pub async fn revoke_role(user_id: UserId, role: &str) -> Result<(), Error> {
+    sqlx::query!(
+        "DELETE FROM user_roles WHERE user_id = $1 AND role = $2",
+        user_id.0,
+        role,
+    )
+    .execute(&mut *connection().await?)
+    .await?;
+    Ok(())
+}
+
+/// Sanctions the subject user in the subject community, or site-wide if there's
+/// none, and records it in the moderation log. Site-wide suspensions and bans
+/// also sign the user out. Returns the ids of the deleted sessions.
+#[tracing::instrument(skip_all)]
+pub async fn sanction(
+    moderator: UserId,
+    subject: &moderation::Subject,
+    kind: sanctions::Kind,
+    expires: Option<DateTime>,
+    report_id: Option<ReportId>,
+    reason: &str,
+) -> Result<Vec<Uuid>, Error> {
+    use {moderation::Action, sanctions::Kind};
+
+    let now = chrono::Utc::now();
+    let community_id = subject.community_id.map(|id| id.0);
+    let action = kind.action();
+    let mut transaction = begin().await?;
+
+    sqlx::query!(
+        "INSERT INTO sanctions
+            (sanction_id, created, user_id, community_id, kind, expires, reason, issued_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        Uuid::new_v4(),
+        now,
+        subject.user_id.0,
+        community_id,
+        kind as Kind,
+        expires,
+        reason,
+        moderator.0,
+    )
+    .execute(&mut *transaction)
     .await?;
 
+    sqlx::query!(
+        "INSERT INTO moderation_log
+            (entry_id, created, moderator, action, community_id, target_user_id, report_id, reason)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        Uuid::new_v4(),
+        now,
+        moderator.0,
+        action as Action,
+        community_id,
+        subject.user_id.0,
+        report_id.map(|id| id.0),
+        reason,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    // shadow-banned users mustn't notice they are
+    let mut deleted_sessions = Vec::new();
+    if community_id.is_none() && kind != Kind::ShadowBan {
+        deleted_sessions = sqlx::query_scalar!(
+            "DELETE FROM sessions WHERE user_id = $1 RETURNING session_id",
+            subject.user_id.0
+        )
+        .fetch_all(&mut *transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+    Ok(deleted_sessions)
+}
+
+/// Returns the user and community of a sanction that's in effect.
+#[tracing::instrument(skip_all)]
+pub async fn sanction_subject(
+    sanction_id: SanctionId,
+) -> Result<Option<moderation::Subject>, Error> {
+    Ok(sqlx::query!(
+        "SELECT user_id, community_id FROM sanctions
+        WHERE sanction_id = $1 AND lifted IS NULL AND (expires IS NULL OR expires > now())",
+        sanction_id.0
+    )
+    .fetch_optional(&mut *connection().await?)
+    .await?
+    .map(|row| moderation::Subject {
+        community_id: row.community_id.map(CommunityId),
+        post_id: None,
+        comment_id: None,
+        user_id: UserId(row.user_id),
+    }))
+}
+
+/// Lifts a sanction and records it in the moderation log.
+#[tracing::instrument(skip_all)]
+pub async fn lift_sanction(
+    sanction_id: SanctionId,
+    moderator: UserId,
+    subject: &moderation::Subject,
+) -> Result<(), Error> {
+    let now = chrono::Utc::now();
+    let mut transaction = begin().await?;
+
+    sqlx::query!(
+        "UPDATE sanctions SET lifted = $2, lifted_by = $3
+        WHERE sanction_id = $1 AND lifted IS NULL",
+        sanction_id.0,
+        now,
+        moderator.0,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query!(
+        "INSERT INTO moderation_log
+            (entry_id, created, moderator, action, community_id, target_user_id, reason)
+        VALUES ($1, $2, $3, $4, $5, $6, '')",
+        Uuid::new_v4(),
+        now,
+        moderator.0,
+        moderation::Action::Lift as moderation::Action,
+        subject.community_id.map(|id| id.0),
+        subject.user_id.0,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Returns the sanctions in effect in a community, or the site-wide ones if no
+/// community is given, the newest first.
+#[tracing::instrument(skip_all)]
+pub async fn sanctions(
+    community_id: Option<CommunityId>,
+) -> Result<Vec<sanctions::Sanction>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT
+            sanction_id,
+            sanctions.created,
+            users.username,
+            kind AS "kind: sanctions::Kind",
+            expires,
+            reason,
+            issuers.username AS "issued_by?"
+        FROM sanctions
+        JOIN users USING (user_id)
+        LEFT JOIN users AS issuers ON issued_by = issuers.user_id
+        WHERE community_id IS NOT DISTINCT FROM $1
+            AND lifted IS NULL
+            AND (expires IS NULL OR expires > now())
+        ORDER BY sanctions.created DESC"#,
+        community_id.map(|id| id.0)
+    )
+    .fetch_all(&mut *connection().await?)
+    .await?
+    .into_iter()
+    .map(|row| sanctions::Sanction {
+        sanction_id: SanctionId(row.sanction_id),
+        created: row.created,
+        username: Username(row.username),
+        kind: row.kind,
+        expires: row.expires,
+        reason: row.reason,
+        issued_by: row.issued_by.map(Username),
+    })
+    .collect())
+}
+
+/// Returns the suspension or ban blocking a user site-wide, or in the
+/// community if one is given. Bans take precedence over suspensions and longer
+/// suspensions over shorter ones.
+#[tracing::instrument(skip_all)]
+pub async fn block(
+    user_id: UserId,
+    community_id: Option<CommunityId>,
+) -> Result<Option<sanctions::Block>, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT kind AS "kind: sanctions::Kind", community_id IS NULL AS "site_wide!", expires,
+            reason
+        FROM sanctions
+        WHERE user_id = $1
+            AND (community_id IS NULL OR community_id = $2)
+            AND kind IN ('Suspension', 'Ban')
+            AND lifted IS NULL
+            AND (expires IS NULL OR expires > now())
+        ORDER BY expires DESC NULLS FIRST
+        LIMIT 1"#,
+        user_id.0,
+        community_id.map(|id| id.0)
+    )
+    .fetch_optional(&mut *connection().await?)
+    .await?
+    .map(|row| sanctions::Block {
+        kind: row.kind,
+        site_wide: row.site_wide,
+        expires: row.expires,
+        reason: row.reason,
+    }))
+}
+
+/// Returns the language of a user.
+#[tracing::instrument(skip_all)]
+pub async fn user_language(user_id: UserId) -> Result<Language, Error> {
+    Ok(sqlx::query!(
+        r#"SELECT language AS "language: Language" FROM users WHERE user_id = $1"#,
+        user_id.0
+    )
+    .fetch_one(&mut *connection().await?)
+    .await?
+    .language)
+}
+
+/// Returns the id, email address and password hash of a user.
+#[tracing::instrument(skip_all)]
+pub async fn sign_in_credentials(username: &str) -> Result<Option<sign_in::Credentials>, Error> {
+    Ok(sqlx::query!(
+        "SELECT user_id, email, phc_string FROM users WHERE username = $1",
+        username
+    )
+    .fetch_optional(&mut *connection().await?)
+    .await?
+    .map(|row| sign_in::Credentials {
+        user_id: UserId(row.user_id),
+        email: Email(row.email),
+        phc_string: row.phc_string,
+    }))
+}
+
+/// Returns when the username is locked until, if it's locked.
+#[tracing::instrument(skip_all)]
+pub async fn sign_in_locked_until(username: &str) -> Result<Option<DateTime>, Error> {
+    Ok(sqlx::query!(
+        "SELECT locked_until FROM sign_in_lockouts WHERE username = $1 AND locked_until > now()",
+        username
+    )
+    .fetch_optional(&mut *connection().await?)
+    .await?
+    .map(|row| row.locked_until))
+}
+
+/// Returns the failed sign-in attempts on the username and from the ip address
+/// since the given time.
+#[tracing::instrument(skip_all)]
+pub async fn sign_in_failures(
+    username: &str,
+    ip_address: &str,
+    since: DateTime,
+) -> Result<sign_in::Failures, Error> {
+    let row = sqlx::query!(
+        r#"SELECT
+            count(*) FILTER (WHERE username = $1) AS "username!",
+            count(*) FILTER (WHERE ip_address = $2) AS "ip_address!",
+            max(attempted) AS last
+        FROM sign_in_failures
+        WHERE (username = $1 OR ip_address = $2) AND attempted > $3"#,
+        username,
+        ip_address,
+        since
+    )
+    .fetch_one(&mut *connection().await?)
+    .await?;
+    Ok(sign_in::Failures {
+        username: row.username,
+        ip_address: row.ip_address,
+        last: row.last,
+    })
+}
+
+/// Records a failed sign-in attempt.
+#[tracing::instrument(skip_all)]
+pub async fn record_sign_in_failure(username: &str, ip_address: &str) -> Result<(), Error> {
+    sqlx::query!(
+        "INSERT INTO sign_in_failures VALUES ($1, $2, $3)",
+        username,
+        ip_address,
+        chrono::Utc::now()
+    )
+    .execute(&mut *connection().await?)
+    .await?;
+    Ok(())
+}
+
+/// Drops the failed sign-in attempts that are no longer counted and the
+/// expired lockouts.
+#[tracing::instrument(skip_all)]
+pub async fn prune_sign_in_failures(counted_since: DateTime) -> Result<(), Error> {
+    let mut transaction = begin().await?;
+    sqlx::query!(
+        "DELETE FROM sign_in_failures WHERE attempted <= $1",
+        counted_since
+    )
+    .execute(&mut *transaction)
+    .await?;
+    sqlx::query!("DELETE FROM sign_in_lockouts WHERE locked_until <= now()")
+        .execute(&mut *transaction)
+        .await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Locks the username until the given time and stops counting its failed
+/// sign-in attempts against it. They still count against their ip addresses.
+#[tracing::instrument(skip_all)]
+pub async fn lock_sign_in(username: &str, locked_until: DateTime) -> Result<(), Error> {
+    let mut transaction = begin().await?;
+    sqlx::query!(
+        "INSERT INTO sign_in_lockouts VALUES ($1, $2)
+        ON CONFLICT (username) DO UPDATE SET locked_until = excluded.locked_until",
+        username,
+        locked_until
+    )
+    .execute(&mut *transaction)
+    .await?;
+    sqlx::query!(
+        "UPDATE sign_in_failures SET username = NULL WHERE username = $1",
+        username
+    )
+    .execute(&mut *transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Stops counting the failed sign-in attempts on the username against it.
+/// They still count against their ip addresses.
+#[tracing::instrument(skip_all)]
+pub async fn clear_sign_in_failures(username: &str) -> Result<(), Error> {
+    sqlx::query!(
+        "UPDATE sign_in_failures SET username = NULL WHERE username = $1",
+        username
+    )
+    .execute(&mut *connection().await?)
+    .await?;
     Ok(())
 }
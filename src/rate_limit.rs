@@ -0,0 +1,411 @@
+//! Named rate limit policies.
+//!
+//! The limiter layer limits all requests per ip address. On top of it each
+//! action has a named policy limiting how often it can be taken, keyed by the
+//! user id when a user is signed in and by the ip address otherwise, so users
+//! sharing an ip address behind a NAT don't share their limits. Policies are
+//! checked by the server functions taking the action, and per route by the
+//! routes' middleware. The middleware keys policies by the ip address only, so
+//! it doesn't look up the session of every request. Policies missing from the
+//! config don't limit anything.
+//!
+//! The state of the limits is either kept in memory, so every server instance
+//! allows the full rate, or in keydb where it's shared by all instances. Keydb
+//! limits use the same algorithm as the in-memory ones, the generic cell rate
+//! algorithm, and fall back to them while keydb is unavailable.
+//!
+//! The limits can be reloaded with a new config. Limits whose quota didn't
+//! change keep their state.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::{
+        address,
+        config::{RateLimitQuota, RateLimitStore, RateLimiterConfig},
+        keydb, metrics, postgres, session,
+        types::UserId,
+    },
+    actix_governor::governor::{
+        clock::{Clock, DefaultClock},
+        middleware::StateInformationMiddleware,
+        state::keyed::DefaultKeyedStateStore,
+        Quota, RateLimiter,
+    },
+    actix_web::HttpRequest,
+    derive_more::Display,
+    leptos::*,
+    serde::Deserialize,
+    std::{
+        collections::HashMap,
+        net::IpAddr,
+        sync::{Arc, RwLock},
+        time::Duration,
+    },
+};
+
+/// An action limited by a rate limit policy.
+#[derive(Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy, Display)]
+pub enum Policy {
+    /// Every call of a server function, limited per ip address.
+    Api,
+
+    Signin,
+    Signup,
+    Post,
+    Comment,
+    Report,
+    Search,
+}
+
+/// Whom a rate limit applies to.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Display)]
+pub enum Key {
+    #[display(fmt = "user:{}", _0)]
+    User(UserId),
+
+    #[display(fmt = "ip:{}", _0)]
+    Ip(IpAddr),
+}
+
+/// The state of a rate limit after a request was allowed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Allowed {
+    /// The number of requests allowed in a burst.
+    pub burst_size: u32,
+
+    /// The number of requests that can still be made right away.
+    pub remaining: u32,
+}
+
+/// The error type for rate limiting.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// The rate limit was exceeded.
+    #[error("too many requests, retry after {wait_seconds} seconds")]
+    Exceeded { wait_seconds: u64, burst_size: u32 },
+
+    /// The client ip address couldn't be determined.
+    #[error(transparent)]
+    Address(#[from] address::Error),
+
+    /// The session couldn't be looked up.
+    #[error(transparent)]
+    Postgres(#[from] postgres::Error),
+}
+
+/// The interval at which the state of keys that are back to their full quota
+/// is dropped.
+const RETAIN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Applies the generic cell rate algorithm atomically: the key holds the
+/// theoretical arrival time of the next request in milliseconds, and a request
+/// is allowed unless it arrives more than the burst earlier than that. Returns
+/// the number of milliseconds to wait, or 0 if the request is allowed, and the
+/// number of requests that can still be made right away. The time of the keydb
+/// server is used so all instances agree on it.
+const GCRA_SCRIPT: &str = r"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local interval = tonumber(ARGV[1])
+local burst = tonumber(ARGV[2])
+local tat = math.max(tonumber(redis.call('GET', KEYS[1]) or now), now)
+local allowed_at = tat - interval * (burst - 1)
+if now < allowed_at then
+    return {allowed_at - now, 0}
+end
+tat = tat + interval
+redis.call('SET', KEYS[1], tat, 'PX', tat - now)
+return {0, math.floor((now + interval * burst - tat) / interval)}
+";
+
+type Limiter =
+    RateLimiter<Key, DefaultKeyedStateStore<Key>, DefaultClock, StateInformationMiddleware>;
+
+/// A rate limit kept in memory and, if configured, in keydb.
+struct Limit {
+    /// The prefix of the limit's keydb keys.
+    name: String,
+    interval: Duration,
+    burst_size: u32,
+    memory: Limiter,
+    script: redis::Script,
+}
+
+impl Limit {
+    fn new(name: String, quota: &RateLimitQuota) -> Self {
+        let interval = Duration::from_secs(quota.replenish_interval_seconds.get());
+        let memory_quota = Quota::with_period(interval)
+            .expect("the replenish interval is non-zero")
+            .allow_burst(quota.burst_size);
+        Self {
+            name,
+            interval,
+            burst_size: quota.burst_size.get(),
+            memory: RateLimiter::keyed(memory_quota).with_middleware(),
+            script: redis::Script::new(GCRA_SCRIPT),
+        }
+    }
+
+    /// Returns true if the limit enforces the quota.
+    fn has_quota(&self, quota: &RateLimitQuota) -> bool {
+        self.burst_size == quota.burst_size.get()
+            && self.interval.as_secs() == quota.replenish_interval_seconds.get()
+    }
+
+    /// Counts a request of the key against the limit.
+    async fn check(&self, store: RateLimitStore, key: Key) -> Result<Allowed, Error> {
+        let result = self.check_quota(store, key).await;
+        if let Err(Error::Exceeded { .. }) = result {
+            metrics::metrics()
+                .rate_limit_rejections
+                .with_label_values(&[&self.name])
+                .inc();
+        }
+        result
+    }
+
+    async fn check_quota(&self, store: RateLimitStore, key: Key) -> Result<Allowed, Error> {
+        if store == RateLimitStore::Keydb {
+            let keydb_key = format!("rate_limit:{}:{key}", self.name);
+            let interval = self.interval.as_millis() as u64;
+            let result = keydb::run(|mut connection| async move {
+                self.script
+                    .key(keydb_key)
+                    .arg(interval)
+                    .arg(self.burst_size)
+                    .invoke_async::<_, (u64, u32)>(&mut connection)
+                    .await
+            })
+            .await;
+            // fall back to the in-memory limit while keydb is unavailable
+            if let Ok((wait_milliseconds, remaining)) = result {
+                return match wait_milliseconds {
+                    0 => Ok(Allowed {
+                        burst_size: self.burst_size,
+                        remaining,
+                    }),
+                    wait_milliseconds => Err(Error::Exceeded {
+                        wait_seconds: wait_seconds(Duration::from_millis(wait_milliseconds)),
+                        burst_size: self.burst_size,
+                    }),
+                };
+            }
+        }
+
+        self.memory
+            .check_key(&key)
+            .map(|snapshot| Allowed {
+                burst_size: self.burst_size,
+                remaining: snapshot.remaining_burst_capacity(),
+            })
+            .map_err(|not_until| Error::Exceeded {
+                wait_seconds: wait_seconds(not_until.wait_time_from(DefaultClock::default().now())),
+                burst_size: self.burst_size,
+            })
+    }
+}
+
+struct Limits {
+    store: RateLimitStore,
+
+    /// The limit of all requests per ip address.
+    ip_address: Arc<Limit>,
+
+    policies: HashMap<Policy, Arc<Limit>>,
+}
+
+impl Limits {
+    /// Creates the limits of the config, keeping the current limits whose
+    /// quota didn't change.
+    fn new(config: &RateLimiterConfig, current: Option<&Limits>) -> Self {
+        let limit =
+            |name: String, quota: &RateLimitQuota, current: Option<&Arc<Limit>>| match current {
+                Some(limit) if limit.has_quota(quota) => Arc::clone(limit),
+                Some(limit) => {
+                    tracing::info!(
+                        name,
+                        old_burst_size = limit.burst_size,
+                        old_replenish_interval_seconds = limit.interval.as_secs(),
+                        burst_size = quota.burst_size,
+                        replenish_interval_seconds = quota.replenish_interval_seconds,
+                        "rate limit changed"
+                    );
+                    Arc::new(Limit::new(name, quota))
+                }
+                None => Arc::new(Limit::new(name, quota)),
+            };
+        let ip_address = RateLimitQuota {
+            burst_size: config.burst_size,
+            replenish_interval_seconds: config.replenish_interval_seconds,
+        };
+        let limits = Self {
+            store: config.store,
+            ip_address: limit(
+                "ip_address".to_owned(),
+                &ip_address,
+                current.map(|current| &current.ip_address),
+            ),
+            policies: config
+                .policies
+                .iter()
+                .map(|(policy, quota)| {
+                    let current = current.and_then(|current| current.policies.get(policy));
+                    (*policy, limit(policy.to_string(), quota, current))
+                })
+                .collect(),
+        };
+        if limits.store == RateLimitStore::Keydb && !keydb::is_configured() {
+            tracing::warn!("rate limits are stored in keydb but keydb isn't configured");
+        }
+        if let Some(current) = current {
+            if current.store != limits.store {
+                tracing::info!(store = ?limits.store, "rate limit store changed");
+            }
+            for policy in current.policies.keys() {
+                if !limits.policies.contains_key(policy) {
+                    tracing::info!(%policy, "rate limit removed");
+                }
+            }
+        }
+        limits
+    }
+}
+
+static LIMITS: RwLock<Option<Arc<Limits>>> = RwLock::new(None);
+
+/// Returns the current limits, if initialized.
+fn limits() -> Option<Arc<Limits>> {
+    LIMITS.read().expect("poisoned lock").clone()
+}
+
+/// Creates the rate limits and periodically drops the in-memory state of keys
+/// that are back to their full quota.
+pub fn init(config: &RateLimiterConfig) {
+    {
+        let mut limits = LIMITS.write().expect("poisoned lock");
+        if limits.is_some() {
+            panic!("rate limiters already initialized");
+        }
+        *limits = Some(Arc::new(Limits::new(config, None)));
+    }
+
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(RETAIN_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Some(limits) = limits() {
+                limits.ip_address.memory.retain_recent();
+                for limit in limits.policies.values() {
+                    limit.memory.retain_recent();
+                }
+            }
+        }
+    });
+}
+
+/// Replaces the rate limits with the ones of the config.
+/// Does nothing if the server hasn't set up the limits yet.
+pub fn reload(config: &RateLimiterConfig) {
+    let mut limits = LIMITS.write().expect("poisoned lock");
+    if limits.is_none() {
+        return;
+    }
+    let new = Limits::new(config, limits.as_deref());
+    *limits = Some(Arc::new(new));
+}
+
+/// Returns the whole seconds to wait, rounded up so clients never retry too
+/// early.
+fn wait_seconds(wait: Duration) -> u64 {
+    wait.as_secs() + u64::from(wait.subsec_nanos() > 0)
+}
+
+/// Returns an error if the client's ip address exceeded the rate limit of all
+/// requests, or the state of the limit. Returns None if there's no limit.
+pub async fn check_ip_address(request: &HttpRequest) -> Result<Option<Allowed>, Error> {
+    let Some(limits) = limits() else {
+        return Ok(None);
+    };
+    let key = Key::Ip(address::parse(request)?);
+    limits.ip_address.check(limits.store, key).await.map(Some)
+}
+
+/// Returns an error if the signed in user, or the client's ip address if no
+/// user is signed in, exceeded the rate limit of the policy.
+pub async fn check(cx: Scope, policy: Policy) -> Result<(), ServerFnError> {
+    let request = use_context::<HttpRequest>(cx)
+        .ok_or_else(|| ServerFnError::ServerError("missing request".to_owned()))?;
+    let key = match session::request_user_id(&request).await? {
+        Some(user_id) => Key::User(user_id),
+        None => Key::Ip(address::parse(&request)?),
+    };
+    check_key(policy, key)
+        .await
+        .map(|_| ())
+        .map_err(|error| ServerFnError::ServerError(error.to_string()))
+}
+
+/// Returns an error if the client's ip address exceeded the rate limit of the
+/// policy, or the state of the limit. Returns None if the policy has no limit.
+/// Used by the middleware, which doesn't look up the session.
+pub async fn check_request(
+    request: &HttpRequest,
+    policy: Policy,
+) -> Result<Option<Allowed>, Error> {
+    check_key(policy, Key::Ip(address::parse(request)?)).await
+}
+
+/// Counts a request of the key against the limit of the policy.
+async fn check_key(policy: Policy, key: Key) -> Result<Option<Allowed>, Error> {
+    let Some(limits) = limits() else {
+        return Ok(None);
+    };
+    let Some(limit) = limits.policies.get(&policy) else {
+        return Ok(None);
+    };
+    limit.check(limits.store, key).await.map(Some)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        std::num::{NonZeroU32, NonZeroU64},
+    };
+
+    #[tokio::test]
+    async fn test_memory_limit() {
+        let limit = Limit::new(
+            "test".to_owned(),
+            &RateLimitQuota {
+                burst_size: NonZeroU32::new(2).unwrap(),
+                replenish_interval_seconds: NonZeroU64::new(60).unwrap(),
+            },
+        );
+        let key = Key::Ip(IpAddr::from([203, 0, 113, 7]));
+        for remaining in [1, 0] {
+            assert_eq!(
+                limit.check(RateLimitStore::Memory, key).await.unwrap(),
+                Allowed {
+                    burst_size: 2,
+                    remaining
+                }
+            );
+        }
+        // just under a minute is left, which rounds up
+        assert!(matches!(
+            limit.check(RateLimitStore::Memory, key).await,
+            Err(Error::Exceeded {
+                wait_seconds: 60,
+                burst_size: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_wait_seconds() {
+        assert_eq!(wait_seconds(Duration::from_millis(1)), 1);
+        assert_eq!(wait_seconds(Duration::from_millis(59_999)), 60);
+        assert_eq!(wait_seconds(Duration::from_secs(60)), 60);
+    }
+}
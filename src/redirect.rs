@@ -11,8 +11,19 @@ use {
 };
 
 /// Middleware to redirect http requests to https if the connection is not
-/// already secure. Enabled only if tls is configured.
-pub struct HttpToHttps;
+/// already secure. Enabled only if a listener serves https, whose port the
+/// requests are redirected to. The challenges of the ACME server are answered
+/// over http.
+pub struct HttpToHttps {
+    https_port: Option<u16>,
+}
+
+impl HttpToHttps {
+    /// Redirects to the port of the listener serving https, if there's one.
+    pub fn new(https_port: Option<u16>) -> Self {
+        Self { https_port }
+    }
+}
 
 impl<S, B> Transform<S, ServiceRequest> for HttpToHttps
 where
@@ -27,12 +38,16 @@ where
     type Transform = RedirectMiddleware<S>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ok(RedirectMiddleware { service })
+        ok(RedirectMiddleware {
+            service,
+            https_port: self.https_port,
+        })
     }
 }
 
 pub struct RedirectMiddleware<S> {
     service: S,
+    https_port: Option<u16>,
 }
 
 impl<S, B> Service<ServiceRequest> for RedirectMiddleware<S>
@@ -48,21 +63,67 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        if req.connection_info().scheme() == "https" || cfg!(debug_assertions) {
-            let res = self.service.call(req);
+        let https_port = match self.https_port {
+            Some(port)
+                if req.connection_info().scheme() != "https"
+                    && !req.path().starts_with(marzichat::acme::CHALLENGE_PATH)
+                    && !cfg!(debug_assertions) =>
+            {
+                port
+            }
+            _ => {
+                let res = self.service.call(req);
+
+                return Box::pin(async move { res.await.map(ServiceResponse::map_into_left_body) });
+            }
+        };
+
+        let url = https_url(req.connection_info().host(), https_port, req.uri());
+        let response = HttpResponse::MovedPermanently()
+            .append_header((http::header::LOCATION, url))
+            .finish()
+            .map_into_right_body();
 
-            Box::pin(async move { res.await.map(ServiceResponse::map_into_left_body) })
-        } else {
-            let host = req.connection_info().host().to_owned();
-            let uri = req.uri().to_owned();
-            let url = format!("https://{host}{uri}");
+        Box::pin(async { Ok(req.into_response(response)) })
+    }
+}
+
+/// Returns the url of the request on the https port, replacing the port of the
+/// host.
+fn https_url(host: &str, port: u16, uri: &http::Uri) -> String {
+    let host = match host.strip_prefix('[') {
+        // ipv6 addresses are in brackets
+        Some(address) => match address.split_once(']') {
+            Some((address, _)) => format!("[{address}]"),
+            None => host.to_owned(),
+        },
+        None => host.split(':').next().unwrap_or(host).to_owned(),
+    };
+    if port == 443 {
+        format!("https://{host}{uri}")
+    } else {
+        format!("https://{host}:{port}{uri}")
+    }
+}
 
-            let response = HttpResponse::MovedPermanently()
-                .append_header((http::header::LOCATION, url))
-                .finish()
-                .map_into_right_body();
+#[cfg(test)]
+mod test {
+    use super::*;
 
-            Box::pin(async { Ok(req.into_response(response)) })
-        }
+    #[test]
+    fn test_https_url() {
+        let uri = "/posts?page=2".parse().unwrap();
+        assert_eq!(
+            https_url("marzichat.localhost", 443, &uri),
+            "https://marzichat.localhost/posts?page=2"
+        );
+        assert_eq!(
+            https_url("marzichat.localhost:8080", 8443, &uri),
+            "https://marzichat.localhost:8443/posts?page=2"
+        );
+        assert_eq!(
+            https_url("[::1]:8080", 443, &uri),
+            "https://[::1]/posts?page=2"
+        );
     }
 }
@@ -0,0 +1,81 @@
+//! Reloading of the config on SIGHUP.
+#![cfg(feature = "ssr")]
+
+use {
+    anyhow::Context,
+    marzichat::{
+        config::{self, Severity},
+        rate_limit, tls,
+    },
+    std::path::{Path, PathBuf},
+    tokio::signal::unix::{signal, SignalKind},
+};
+
+/// The settings applied when the config is reloaded. Changes of any other
+/// setting are only applied after a restart.
+const RELOADED: [&str; 3] = ["logging.directives", "server.rate_limiter", "server.tls"];
+
+/// Reloads the config file whenever the process receives SIGHUP. The current
+/// settings are the ones the config was loaded with.
+pub fn spawn(path: PathBuf, mut current: config::Settings) -> anyhow::Result<()> {
+    let mut hangups = signal(SignalKind::hangup()).context("failed to listen for SIGHUP")?;
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            tracing::info!(?path, "reloading config");
+            match reload(&path, &current) {
+                Ok(new) => current = new,
+                Err(error) => tracing::error!(
+                    error = %format_args!("{error:#}"),
+                    "rejected the new config, keeping the current one"
+                ),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Applies the reloadable settings of the config file and returns its
+/// settings. The settings set by environment variables and secret files are
+/// compared too, so renewed secrets are reported as changes. Nothing is applied
+/// unless the config passes the [checks](config::check), so a certificate
+/// whose private key hasn't been replaced yet is never served.
+fn reload(path: &Path, current: &config::Settings) -> anyhow::Result<config::Settings> {
+    let (config, settings) = config::parse_from_file(path)?;
+    let problems = config::check(&config, chrono::Utc::now());
+    let errors = problems
+        .iter()
+        .filter(|problem| problem.severity == Severity::Error)
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    anyhow::ensure!(errors.is_empty(), "{}", errors.join("; "));
+    for problem in problems {
+        tracing::warn!(%problem, "config problem");
+    }
+    let changes = config::changes(current, &settings);
+
+    crate::logger::reload(config.logging.directives).context("failed to reload directives")?;
+    rate_limit::reload(&config.server.rate_limiter);
+    // the certificate files may have been renewed without changing the config
+    if let Some(certificate) = config.server.tls {
+        if !tls::reload(certificate) {
+            tracing::warn!("tls can't be enabled without a restart");
+        }
+    }
+
+    if changes.is_empty() {
+        tracing::info!("config unchanged");
+    }
+    for setting in changes {
+        let is_reloaded = RELOADED.iter().any(|reloaded| {
+            setting
+                .strip_prefix(reloaded)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        });
+        if is_reloaded {
+            tracing::info!(setting, "setting changed");
+        } else {
+            tracing::warn!(setting, "setting changed, restart to apply it");
+        }
+    }
+    Ok(settings)
+}
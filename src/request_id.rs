@@ -0,0 +1,133 @@
+//! Middleware tagging every request with a correlation id.
+//!
+//! The id is taken from the `X-Request-Id` header set by a trusted proxy in
+//! front of the server, or generated if there's none. Everything logged while
+//! handling the request, including in server functions, is recorded on a span
+//! carrying the id, and the id is sent back in the response.
+#![cfg(feature = "ssr")]
+
+use {
+    actix_web::{
+        dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+        http::header::{HeaderName, HeaderValue},
+        Error,
+    },
+    futures_util::future::{ok, LocalBoxFuture, Ready},
+    std::{rc::Rc, time::Instant},
+    tracing::Instrument,
+    tracing_opentelemetry::OpenTelemetrySpanExt,
+    uuid::Uuid,
+};
+
+/// The header carrying the request id.
+const REQUEST_ID: &str = "x-request-id";
+
+/// The maximum length of a request id taken from a request.
+const MAX_LENGTH: usize = 128;
+
+/// Returns the request id in the header value, if it's safe to log and send
+/// back.
+fn propagated(value: &HeaderValue) -> Option<&str> {
+    let value = value.to_str().ok()?;
+    let is_valid = !value.is_empty()
+        && value.len() <= MAX_LENGTH
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"-_.:".contains(&byte));
+    is_valid.then_some(value)
+}
+
+/// Middleware tagging every request with a correlation id.
+pub struct RequestId;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestId
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Error = Error;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+    type InitError = ();
+    type Response = ServiceResponse<B>;
+    type Transform = RequestIdMiddleware<S>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequestIdMiddleware {
+            service: Rc::new(service),
+        })
+    }
+}
+
+pub struct RequestIdMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = ServiceResponse<B>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        // clients could otherwise pick the ids their requests are logged with
+        let request_id = marzichat::address::from_trusted_proxy(req.request())
+            .then(|| req.headers().get(REQUEST_ID).and_then(propagated))
+            .flatten()
+            .map(str::to_owned)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let client = marzichat::address::parse(req.request()).ok();
+        let span = tracing::info_span!(
+            "request",
+            request_id,
+            method = %req.method(),
+            path = req.path(),
+            client = ?client,
+        );
+        span.set_parent(marzichat::telemetry::parent_context(req.headers()));
+
+        Box::pin(
+            async move {
+                let start = Instant::now();
+                let mut response = service.call(req).await?;
+                tracing::info!(
+                    status = response.status().as_u16(),
+                    seconds = start.elapsed().as_secs_f64(),
+                    "handled request"
+                );
+                if let Ok(request_id) = HeaderValue::from_str(&request_id) {
+                    response
+                        .headers_mut()
+                        .insert(HeaderName::from_static(REQUEST_ID), request_id);
+                }
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_propagated() {
+        let propagated =
+            |value: &str| propagated(&HeaderValue::from_str(value).unwrap()).map(str::to_owned);
+        let uuid = "0b6f5a51-4a9c-4d3c-9a4e-1c4f0e9bd2a7";
+        assert_eq!(propagated(uuid).as_deref(), Some(uuid));
+        assert_eq!(propagated("abc.def:1_2").as_deref(), Some("abc.def:1_2"));
+        assert_eq!(propagated(""), None);
+        assert_eq!(propagated("a b"), None);
+        assert_eq!(propagated("a\"b"), None);
+        assert_eq!(propagated(&"a".repeat(MAX_LENGTH + 1)), None);
+    }
+}
@@ -0,0 +1,101 @@
+use {
+    crate::{
+        components::Sanctions,
+        internationalization::Translations,
+        permissions::{self, GrantRole, RevokeRole, Role},
+        types::Username,
+    },
+    leptos::*,
+    leptos_router::*,
+};
+
+/// The admin pages, only served to users with the
+/// [`AccessAdmin`](permissions::Permission::AccessAdmin) permission.
+#[component]
+pub fn Admin(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+    let grant_role = create_server_action::<GrantRole>(cx);
+    let revoke_role = create_server_action::<RevokeRole>(cx);
+    let roles = create_resource(
+        cx,
+        move || (grant_role.version().get(), revoke_role.version().get()),
+        move |_| permissions::get_roles(cx),
+    );
+    let error = move || {
+        grant_role
+            .value()
+            .get()
+            .and_then(Result::err)
+            .or_else(|| revoke_role.value().get().and_then(Result::err))
+            .map(|error| error.to_string())
+    };
+
+    view! { cx,
+        <main>
+            <h1 class="h3 mb-3">{t.admin()}</h1>
+            <Transition fallback=|| ()>
+                {move || roles.read(cx).map(|roles| match roles {
+                    Err(_) => view! { cx, <div class="flash flash-error">{t.forbidden()}</div> }.into_view(cx),
+                    Ok(roles) => {
+                        let names = roles.iter().map(|role| role.name.clone()).collect::<Vec<_>>();
+                        view! { cx,
+                            {move || error().map(|error| view! { cx, <div class="flash flash-error mb-3">{error}</div> })}
+                            <h2 class="h4">{t.roles()}</h2>
+                            <ul class="list-style-none mb-4">
+                                {roles.into_iter().map(|role| view! { cx, <RoleItem role revoke_role/> }).collect_view(cx)}
+                            </ul>
+                            <ActionForm action=grant_role class="d-flex">
+                                <input class="form-control mr-2" type="text" name="username" required
+                                    placeholder=t.username() aria-label=t.username()/>
+                                <select class="form-select mr-2" name="role" aria-label=t.role()>
+                                    {names.into_iter().map(|name| view! { cx, <option value=name.clone()>{name}</option> }).collect_view(cx)}
+                                </select>
+                                <button type="submit" class="btn btn-primary">{t.grant_role()}</button>
+                            </ActionForm>
+                            <div class="mt-4">
+                                <Sanctions community=None/>
+                            </div>
+                        }.into_view(cx)
+                    }
+                })}
+            </Transition>
+        </main>
+    }
+}
+
+/// A role with its permissions and members.
+#[component]
+fn RoleItem(
+    cx: Scope,
+    role: Role,
+    revoke_role: Action<RevokeRole, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let t = Translations::default();
+    let name = role.name;
+    view! { cx,
+        <li class="py-2 border-bottom">
+            <span class="text-bold">{name.clone()}</span>
+            <span class="color-fg-muted ml-2">{role.description}</span>
+            <div class="f6">
+                {role.permissions.into_iter().map(|permission| view! { cx,
+                    <span class="Label mr-1">{permission.label(t)}</span>
+                }).collect_view(cx)}
+            </div>
+            <ul class="list-style-none ml-3">
+                {role.members.into_iter().map(|Username(username)| {
+                    let role = name.clone();
+                    view! { cx,
+                        <li class="d-flex flex-items-center">
+                            <span class="flex-auto">{username.clone()}</span>
+                            <ActionForm action=revoke_role>
+                                <input type="hidden" name="username" value=username/>
+                                <input type="hidden" name="role" value=role/>
+                                <button type="submit" class="btn-link">{t.revoke()}</button>
+                            </ActionForm>
+                        </li>
+                    }
+                }).collect_view(cx)}
+            </ul>
+        </li>
+    }
+}
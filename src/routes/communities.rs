@@ -0,0 +1,105 @@
+use {
+    crate::{
+        community::{self, CreateCommunity, MAX_DESCRIPTION_BYTES},
+        internationalization::Translations,
+        routes::CREATE_COMMUNITY,
+        scroll_to_top,
+        types::CommunityName,
+    },
+    leptos::*,
+    leptos_router::*,
+};
+
+/// All communities, the ones with the most subscribers first.
+#[component]
+pub fn Communities(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+    let communities = create_resource(cx, || (), |_| community::get_communities());
+
+    view! { cx,
+        <main>
+            <div class="d-flex flex-items-center mb-3">
+                <h1 class="h3 flex-auto">{t.communities()}</h1>
+                <A href=CREATE_COMMUNITY class="btn btn-primary">{t.create_community()}</A>
+            </div>
+            <Transition fallback=|| ()>
+                {move || communities.read(cx).and_then(Result::ok).map(|communities| view! { cx,
+                    <ul class="list-style-none">
+                        {communities.into_iter().map(|community| view! { cx,
+                            <li class="py-2 border-bottom">
+                                <A href=format!("/c/{}", community.name) class="Link--primary text-bold">
+                                    {format!("c/{}", community.name)}
+                                </A>
+                                <span class="color-fg-muted f6 ml-2">
+                                    {format!("{} {}", community.subscriber_count, t.subscribers())}
+                                </span>
+                                <p class="mb-0">{community.description}</p>
+                            </li>
+                        }).collect_view(cx)}
+                    </ul>
+                })}
+            </Transition>
+        </main>
+    }
+}
+
+/// The form to create a community.
+#[component]
+pub fn NewCommunity(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+    let create_community = create_server_action::<CreateCommunity>(cx);
+    let error = move || {
+        create_community
+            .value()
+            .get()
+            .and_then(Result::err)
+            .map(|error| error.to_string())
+    };
+
+    scroll_to_top();
+    view! { cx,
+        <main class="container-md my-4">
+            <div class="Box Box--spacious">
+                <div class="Box-header">
+                    <h1 class="Box-title">{t.create_community()}</h1>
+                </div>
+                <div class="Box-body">
+                    {move || error().map(|error| view! { cx, <div class="flash flash-error mb-3">{error}</div> })}
+                    <ActionForm action=create_community>
+                        <div class="form-group">
+                            <div class="form-group-header">
+                                <label for="name">{t.name()}</label>
+                            </div>
+                            <div class="form-group-body">
+                                <input class="form-control width-full" type="text" id="name" name="name" required
+                                    minlength=CommunityName::MIN_BYTES maxlength=CommunityName::MAX_BYTES
+                                    pattern="[A-Za-z][A-Za-z0-9_]*"/>
+                            </div>
+                        </div>
+                        <div class="form-group">
+                            <div class="form-group-header">
+                                <label for="description">{t.description()}</label>
+                            </div>
+                            <div class="form-group-body">
+                                <textarea class="form-control width-full" id="description" name="description"
+                                    maxlength=MAX_DESCRIPTION_BYTES></textarea>
+                            </div>
+                        </div>
+                        <div class="form-group">
+                            <div class="form-group-header">
+                                <label for="rules">{t.rules()}</label>
+                            </div>
+                            <div class="form-group-body">
+                                <textarea class="form-control width-full" id="rules" name="rules"></textarea>
+                                <p class="note">{t.one_rule_per_line()}</p>
+                            </div>
+                        </div>
+                        <div class="text-right mt-4">
+                            <button type="submit" class="btn btn-primary">{t.create_community()}</button>
+                        </div>
+                    </ActionForm>
+                </div>
+            </div>
+        </main>
+    }
+}
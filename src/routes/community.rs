@@ -0,0 +1,207 @@
+use {
+    crate::{
+        community::{self, AddModerator, SetSubscribed, UpdateCommunity},
+        components::{PostItem, Sanctions},
+        content::POSTS_PER_PAGE,
+        internationalization::Translations,
+        routes::SUBMIT,
+        types::{CommunityName, DateTime},
+    },
+    chrono::SecondsFormat,
+    leptos::*,
+    leptos_meta::*,
+    leptos_router::*,
+};
+
+/// A community's front page with its posts and a sidebar with its
+/// description, rules and moderators.
+#[component]
+pub fn Community(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+    let params = use_params_map(cx);
+    let query = use_query_map(cx);
+    let name = move || params.with(|params| params.get("name").cloned().map(CommunityName));
+    let before = move || {
+        query.with(|query| {
+            query
+                .get("before")
+                .and_then(|before| before.parse::<DateTime>().ok())
+        })
+    };
+
+    let update_community = create_server_action::<UpdateCommunity>(cx);
+    let add_moderator = create_server_action::<AddModerator>(cx);
+    let set_subscribed = create_server_action::<SetSubscribed>(cx);
+    let community = create_resource(
+        cx,
+        move || {
+            (
+                name(),
+                update_community.version().get(),
+                add_moderator.version().get(),
+                set_subscribed.version().get(),
+            )
+        },
+        |(name, ..)| async move {
+            match name {
+                Some(name) => community::get_community(name).await.ok().flatten(),
+                None => None,
+            }
+        },
+    );
+    let membership = create_resource(
+        cx,
+        move || (name(), set_subscribed.version().get()),
+        move |(name, _)| async move {
+            match name {
+                Some(name) => community::get_membership(cx, name)
+                    .await
+                    .unwrap_or_default(),
+                None => Default::default(),
+            }
+        },
+    );
+    let posts = create_resource(
+        cx,
+        move || (name(), before()),
+        move |(name, before)| async move {
+            match name {
+                Some(name) => community::get_community_posts(cx, name, before)
+                    .await
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            }
+        },
+    );
+
+    view! { cx,
+        <Suspense fallback=|| ()>
+            {move || community.read(cx).map(|community| match community {
+                None => view! { cx, <h1>{t.community_not_found()}</h1> }.into_view(cx),
+                Some(community) => {
+                    let name = community.name.clone();
+                    let stored = store_value(cx, community.clone());
+                    view! { cx,
+                        <Title text=format!("c/{name}")/>
+                        <div class="d-flex flex-items-center mb-3">
+                            <h1 class="h3 flex-auto">{format!("c/{name}")}</h1>
+                            <A href=format!("{SUBMIT}?community={name}") class="btn mr-2">{t.submit()}</A>
+                            <Suspense fallback=|| ()>
+                            {move || membership.read(cx).map(|membership| view! { cx,
+                                <ActionForm action=set_subscribed>
+                                    <input type="hidden" name="name" value=stored.with_value(|community| community.name.to_string())/>
+                                    <input type="hidden" name="subscribed" value=(!membership.subscribed).to_string()/>
+                                    <button type="submit" class="btn btn-primary">
+                                        {if membership.subscribed { t.unsubscribe() } else { t.subscribe() }}
+                                    </button>
+                                </ActionForm>
+                            })}
+                            </Suspense>
+                        </div>
+                        <div class="d-flex flex-column flex-md-row">
+                            <main class="flex-auto mr-md-4">
+                                <Transition fallback=|| ()>
+                                    {move || posts.read(cx).map(|posts| {
+                                        let more = (posts.len() as i64 == POSTS_PER_PAGE)
+                                            .then(|| posts.last().map(|post| post.created.to_rfc3339_opts(SecondsFormat::Micros, true)))
+                                            .flatten();
+                                        view! { cx,
+                                            <ul class="list-style-none">
+                                                {posts.into_iter().map(|post| view! { cx, <PostItem post/> }).collect_view(cx)}
+                                            </ul>
+                                            {more.map(|before| view! { cx,
+                                                <A href=format!("?before={before}") class="d-inline-block mt-3">{t.more()}</A>
+                                            })}
+                                        }
+                                    })}
+                                </Transition>
+                            </main>
+                            <aside class="col-md-4">
+                                <Sidebar community/>
+                                <Suspense fallback=|| ()>
+                                    {move || membership.read(cx).filter(|membership| membership.moderator).map(|_| view! { cx,
+                                        <ModeratorForms community=stored.get_value() update_community add_moderator/>
+                                    })}
+                                </Suspense>
+                            </aside>
+                        </div>
+                    }.into_view(cx)
+                }
+            })}
+        </Suspense>
+    }
+}
+
+/// The description, rules, subscriber count and moderators of a community.
+#[component]
+fn Sidebar(cx: Scope, community: community::Community) -> impl IntoView {
+    let t = Translations::default();
+    view! { cx,
+        <div class="Box mb-3">
+            <div class="Box-body">
+                <p>{community.description}</p>
+                <p class="color-fg-muted f6">{format!("{} {}", community.subscriber_count, t.subscribers())}</p>
+            </div>
+            {(!community.rules.is_empty()).then(|| view! { cx,
+                <div class="Box-body">
+                    <h2 class="h5">{t.rules()}</h2>
+                    <ol class="ml-3">
+                        {community.rules.into_iter().map(|rule| view! { cx, <li>{rule}</li> }).collect_view(cx)}
+                    </ol>
+                </div>
+            })}
+            <div class="Box-body">
+                <h2 class="h5">{t.moderators()}</h2>
+                <ul class="list-style-none">
+                    {community.moderators.into_iter().map(|moderator| view! { cx, <li>{moderator.0}</li> }).collect_view(cx)}
+                </ul>
+            </div>
+        </div>
+    }
+}
+
+/// Forms to edit the sidebar, add moderators and sanction users, shown to
+/// moderators.
+#[component]
+fn ModeratorForms(
+    cx: Scope,
+    community: community::Community,
+    update_community: Action<UpdateCommunity, Result<(), ServerFnError>>,
+    add_moderator: Action<AddModerator, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let t = Translations::default();
+    let name = community.name.to_string();
+    let moderator_name = name.clone();
+    let sanctions_community = community.name.clone();
+    let error = move || {
+        update_community
+            .value()
+            .get()
+            .and_then(Result::err)
+            .or_else(|| add_moderator.value().get().and_then(Result::err))
+            .map(|error| error.to_string())
+    };
+
+    view! { cx,
+        {move || error().map(|error| view! { cx, <div class="flash flash-error mb-3">{error}</div> })}
+        <ActionForm action=update_community class="mb-3">
+            <input type="hidden" name="name" value=name/>
+            <label for="description">{t.description()}</label>
+            <textarea class="form-control width-full" id="description" name="description"
+                maxlength=community::MAX_DESCRIPTION_BYTES>{community.description}</textarea>
+            <label for="rules">{t.rules()}</label>
+            <textarea class="form-control width-full" id="rules" name="rules">{community.rules.join("\n")}</textarea>
+            <p class="note">{t.one_rule_per_line()}</p>
+            <button type="submit" class="btn btn-sm">{t.save()}</button>
+        </ActionForm>
+        <ActionForm action=add_moderator class="d-flex">
+            <input type="hidden" name="name" value=moderator_name/>
+            <input class="form-control input-sm flex-auto mr-2" type="text" name="username" required
+                aria-label=t.add_moderator()/>
+            <button type="submit" class="btn btn-sm">{t.add_moderator()}</button>
+        </ActionForm>
+        <div class="mt-3">
+            <Sanctions community=Some(sanctions_community)/>
+        </div>
+    }
+}
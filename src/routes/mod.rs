@@ -1,15 +1,31 @@
+pub mod admin;
+pub mod communities;
+pub mod community;
+pub mod moderation;
 pub mod not_found;
+pub mod post;
+pub mod posts;
 pub mod privacy;
+pub mod search;
 pub mod signin;
 pub mod signup;
 pub mod stories;
 pub mod story;
+pub mod submit;
 pub mod terms;
 pub mod users;
 
 pub const HOME: &str = "/";
+pub const ADMIN: &str = "/admin";
+pub const COMMUNITIES: &str = "/c";
+pub const CREATE_COMMUNITY: &str = "/create-community";
+pub const MODERATION_QUEUE: &str = "/moderation";
+pub const MODERATION_NOTICES: &str = "/moderation/notices";
+pub const POSTS: &str = "/posts";
+pub const SEARCH: &str = "/search";
 pub const SIGNIN: &str = "/signin";
 pub const SIGNUP: &str = "/signup";
+pub const SUBMIT: &str = "/submit";
 pub const ABOUT: &str = "/about";
 pub const HELP_AND_SAFETY: &str = "/help-and-safety";
 pub const PRIVACY_POLICY: &str = "/privacy-policy";
@@ -0,0 +1,228 @@
+use {
+    crate::{
+        internationalization::{Language, Translations},
+        moderation::{
+            self, Action, Held, Report, ResolveReport, ReviewHeld, Target, MAX_REASON_BYTES,
+            REPORT_ACTIONS, REVIEW_ACTIONS,
+        },
+        types::datetime,
+    },
+    leptos::*,
+    leptos_router::*,
+};
+
+/// The open reports and the content held by the spam filter in the
+/// communities the signed in user moderates.
+#[component]
+pub fn ModerationQueue(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+    let resolve_report = create_server_action::<ResolveReport>(cx);
+    let review_held = create_server_action::<ReviewHeld>(cx);
+    let reports = create_resource(
+        cx,
+        move || resolve_report.version().get(),
+        move |_| moderation::get_reports(cx),
+    );
+    let held = create_resource(
+        cx,
+        move || review_held.version().get(),
+        move |_| moderation::get_held(cx),
+    );
+    let error = move || {
+        resolve_report
+            .value()
+            .get()
+            .and_then(Result::err)
+            .or_else(|| review_held.value().get().and_then(Result::err))
+            .map(|error| error.to_string())
+    };
+
+    view! { cx,
+        <main>
+            <h1 class="h3 mb-3">{t.moderation_queue()}</h1>
+            {move || error().map(|error| view! { cx, <div class="flash flash-error mb-3">{error}</div> })}
+            <Transition fallback=|| ()>
+                {move || reports.read(cx).map(|reports| match reports {
+                    Err(error) => view! { cx, <div class="flash flash-error">{error.to_string()}</div> }.into_view(cx),
+                    Ok(reports) if reports.is_empty() => view! { cx,
+                        <p class="color-fg-muted">{t.no_open_reports()}</p>
+                    }.into_view(cx),
+                    Ok(reports) => view! { cx,
+                        <ul class="list-style-none">
+                            {reports.into_iter().map(|report| view! { cx, <ReportItem report resolve_report/> }).collect_view(cx)}
+                        </ul>
+                    }.into_view(cx),
+                })}
+            </Transition>
+            <h2 class="h4 mt-4">{t.held_content()}</h2>
+            <Transition fallback=|| ()>
+                {move || held.read(cx).and_then(Result::ok).map(|held| if held.is_empty() {
+                    view! { cx, <p class="color-fg-muted">{t.no_held_content()}</p> }.into_view(cx)
+                } else {
+                    view! { cx,
+                        <ul class="list-style-none">
+                            {held.into_iter().map(|held| view! { cx, <HeldItem held review_held/> }).collect_view(cx)}
+                        </ul>
+                    }.into_view(cx)
+                })}
+            </Transition>
+        </main>
+    }
+}
+
+/// A report with a form to resolve it.
+#[component]
+fn ReportItem(
+    cx: Scope,
+    report: Report,
+    resolve_report: leptos::Action<ResolveReport, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let t = Translations::default();
+    view! { cx,
+        <li class="py-3 border-bottom">
+            <div class="color-fg-muted f6">
+                {report.community.map(|community| view! { cx,
+                    <A href=format!("/c/{community}")>{format!("c/{community}")}</A>{" | "}
+                })}
+                <span class="Label Label--attention mr-1">{report.reason.label(t)}</span>
+                {t.reported_by()}{" "}{report.reporter.0}{" "}
+                {datetime::ago(&report.created, Language::default())}
+            </div>
+            {(!report.details.is_empty()).then(|| view! { cx, <p class="my-1">{report.details}</p> })}
+            <TargetBox target=report.target/>
+            <ActionForm action=resolve_report class="d-flex">
+                <input type="hidden" name="report_id" value=report.report_id.to_string()/>
+                <select class="form-select mr-2" name="action" aria-label=t.moderation_queue()>
+                    {REPORT_ACTIONS.iter().map(|action| view! { cx,
+                        <option value=format!("{action:?}")>{action.label(t)}</option>
+                    }).collect_view(cx)}
+                </select>
+                <input class="form-control flex-auto mr-2" type="text" name="reason" placeholder=t.reason()
+                    aria-label=t.reason() maxlength=MAX_REASON_BYTES/>
+                <button type="submit" class="btn btn-sm">{t.save()}</button>
+            </ActionForm>
+        </li>
+    }
+}
+
+/// The moderation actions taken on the signed in user's content or account
+/// and the reasons given for them.
+#[component]
+pub fn ModerationNotices(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+    let notices = create_resource(cx, || (), move |_| moderation::get_notices(cx));
+
+    view! { cx,
+        <main>
+            <h1 class="h3 mb-3">{t.moderation_notices()}</h1>
+            <Transition fallback=|| ()>
+                {move || notices.read(cx).map(|notices| match notices {
+                    Err(error) => view! { cx, <div class="flash flash-error">{error.to_string()}</div> }.into_view(cx),
+                    Ok(notices) if notices.is_empty() => view! { cx,
+                        <p class="color-fg-muted">{t.no_moderation_notices()}</p>
+                    }.into_view(cx),
+                    Ok(notices) => view! { cx,
+                        <ul class="list-style-none">
+                            {notices.into_iter().map(|notice| {
+                                let community = notice.community.map(|community| view! { cx,
+                                    {" "}<A href=format!("/c/{community}")>{format!("c/{community}")}</A>
+                                });
+                                let summary = match (notice.action, notice.post_id, notice.comment_id) {
+                                    (Action::Remove, _, Some(_)) => t.comment_was_removed().into_view(cx),
+                                    (Action::Remove, Some(post_id), None) => view! { cx,
+                                        <A href=format!("/posts/{post_id}")>{t.post_was_removed()}</A>
+                                    }.into_view(cx),
+                                    (Action::Suspend, ..) => match community {
+                                        Some(community) => view! { cx, {t.you_were_suspended_from()}{community} }.into_view(cx),
+                                        None => t.your_account_was_suspended().into_view(cx),
+                                    },
+                                    (Action::Ban, ..) => match community {
+                                        Some(community) => view! { cx, {t.you_were_banned()}{community} }.into_view(cx),
+                                        None => t.your_account_was_banned().into_view(cx),
+                                    },
+                                    _ => t.you_were_warned().into_view(cx),
+                                };
+                                view! { cx,
+                                    <li class="py-2 border-bottom">
+                                        <div class="text-bold">{summary}</div>
+                                        <div class="color-fg-muted f6">{datetime::ago(&notice.created, Language::default())}</div>
+                                        <p class="mb-0">{t.reason()}": "{notice.reason}</p>
+                                    </li>
+                                }
+                            }).collect_view(cx)}
+                        </ul>
+                    }.into_view(cx),
+                })}
+            </Transition>
+        </main>
+    }
+}
+
+/// A post or comment held by the spam filter with the rules it broke and a
+/// form to approve or remove it.
+#[component]
+fn HeldItem(
+    cx: Scope,
+    held: Held,
+    review_held: leptos::Action<ReviewHeld, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let t = Translations::default();
+    let hidden_id = match &held.target {
+        Target::Comment { comment_id, .. } => ("comment_id", comment_id.to_string()),
+        Target::Post { post_id, .. } => ("post_id", post_id.to_string()),
+        Target::User { .. } => unreachable!("users aren't held"),
+    };
+    view! { cx,
+        <li class="py-3 border-bottom">
+            <div class="color-fg-muted f6">
+                {held.community.map(|community| view! { cx,
+                    <A href=format!("/c/{community}")>{format!("c/{community}")}</A>{" | "}
+                })}
+                {datetime::ago(&held.created, Language::default())}
+            </div>
+            <ul class="list-style-none my-1">
+                {held.verdicts.into_iter().filter(|verdict| !verdict.passed).map(|verdict| view! { cx,
+                    <li>
+                        <span class="Label Label--attention mr-1">{verdict.rule.label(t)}</span>
+                        <span class="f6">{verdict.details}</span>
+                    </li>
+                }).collect_view(cx)}
+            </ul>
+            <TargetBox target=held.target/>
+            <ActionForm action=review_held class="d-flex">
+                <input type="hidden" name=hidden_id.0 value=hidden_id.1/>
+                <select class="form-select mr-2" name="action" aria-label=t.held_content()>
+                    {REVIEW_ACTIONS.iter().map(|action| view! { cx,
+                        <option value=format!("{action:?}")>{action.label(t)}</option>
+                    }).collect_view(cx)}
+                </select>
+                <input class="form-control flex-auto mr-2" type="text" name="reason" placeholder=t.reason()
+                    aria-label=t.reason() maxlength=MAX_REASON_BYTES/>
+                <button type="submit" class="btn btn-sm">{t.save()}</button>
+            </ActionForm>
+        </li>
+    }
+}
+
+/// The reported or held post, comment or user.
+#[component]
+fn TargetBox(cx: Scope, target: Target) -> impl IntoView {
+    let t = Translations::default();
+    view! { cx,
+        <div class="Box p-2 my-2">
+            {match target {
+                Target::Post { post_id, title, author } => view! { cx,
+                    <A href=format!("/posts/{post_id}") class="text-bold">{title.0}</A>
+                    <div class="color-fg-muted f6">{t.by()}{" "}{author.0}</div>
+                }.into_view(cx),
+                Target::Comment { post_id, comment_id, body_html, author } => view! { cx,
+                    <div class="color-fg-muted f6">
+                        <A href=format!("/posts/{post_id}#comment-{comment_id}")>{t.by()}{" "}{author.0}</A>
+                    </div>
+                    <div inner_html=body_html></div>
+                }.into_view(cx),
+                Target::User { username } => view! { cx, <span class="text-bold">{username.0}</span> }.into_view(cx),
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,231 @@
+use {
+    crate::{
+        community::{self, RemovePost, SetLocked, SetPinned},
+        components::ReportForm,
+        content::{self, SubmitComment},
+        internationalization::{Language, Translations},
+        moderation::MAX_REASON_BYTES,
+        types::{datetime, CommentId, CommunityName, PostId},
+    },
+    leptos::*,
+    leptos_meta::*,
+    leptos_router::*,
+};
+
+#[component]
+pub fn Post(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+    let params = use_params_map(cx);
+    let post_id = move || {
+        params()
+            .get("id")
+            .and_then(|id| id.parse().ok())
+            .map(PostId)
+    };
+    let set_pinned = create_server_action::<SetPinned>(cx);
+    let set_locked = create_server_action::<SetLocked>(cx);
+    let remove_post = create_server_action::<RemovePost>(cx);
+    let post = create_resource(
+        cx,
+        move || {
+            (
+                post_id(),
+                set_pinned.version().get(),
+                set_locked.version().get(),
+                remove_post.version().get(),
+            )
+        },
+        move |(post_id, ..)| async move {
+            match post_id {
+                Some(post_id) => content::get_post(cx, post_id).await.ok().flatten(),
+                None => None,
+            }
+        },
+    );
+
+    let submit_comment = create_server_action::<SubmitComment>(cx);
+    let comments = create_resource(
+        cx,
+        move || (post_id(), submit_comment.version().get()),
+        move |(post_id, _)| async move {
+            match post_id {
+                Some(post_id) => content::get_comments(cx, post_id).await.unwrap_or_default(),
+                None => Vec::new(),
+            }
+        },
+    );
+
+    view! { cx,
+        <Suspense fallback=|| ()>
+            {move || post.read(cx).map(|post| match post {
+                None => view! { cx, <h1>{t.post_not_found()}</h1> }.into_view(cx),
+                Some(post) => view! { cx,
+                    <Title text=post.title.0.clone()/>
+                    <main>
+                        <h1 class="h3">
+                            {match post.url {
+                                Some(url) => view! { cx, <a href=url rel=crate::markdown::LINK_REL target="_blank">{post.title.0}</a> }.into_view(cx),
+                                None => post.title.0.into_view(cx),
+                            }}
+                            {post.domain.map(|domain| view! { cx, <span class="color-fg-muted f4 ml-1">"("{domain}")"</span> })}
+                        </h1>
+                        <p class="color-fg-muted">
+                            {post.community.clone().map(|community| view! { cx,
+                                <A href=format!("/c/{community}")>{format!("c/{community}")}</A>{" | "}
+                            })}
+                            {t.by()}{" "}{post.author.0.clone()}{" "}
+                            {datetime::ago(&post.created, Language::default())}{" "}
+                            <ReportForm post_id=post.post_id/>
+                            <ReportForm username=post.author community=post.community.clone()/>
+                        </p>
+                        {post.community.clone().map(|community| view! { cx,
+                            <ModeratorTools community post_id=post.post_id pinned=post.pinned locked=post.locked
+                                removed=post.removed set_pinned set_locked remove_post/>
+                        })}
+                        {post.removed.then(|| view! { cx, <div class="flash flash-warn mb-3">{t.post_removed()}</div> })}
+                        {post.held.then(|| view! { cx, <div class="flash flash-warn mb-3">{t.held_for_review()}</div> })}
+                        {post.locked.then(|| view! { cx, <div class="flash mb-3">{t.post_locked()}</div> })}
+                        <div class="markdown-body" inner_html=post.body_html></div>
+                        {(!post.locked).then(|| view! { cx,
+                            <CommentForm post_id=post.post_id parent_id=None action=submit_comment/>
+                        })}
+                        <Transition fallback=|| ()>
+                            {move || comments.read(cx).map(|comments| if comments.is_empty() {
+                                view! { cx, <p class="color-fg-muted">{t.no_comments_yet()}</p> }.into_view(cx)
+                            } else {
+                                view! { cx,
+                                    <Comments comments post_id=post.post_id locked=post.locked action=submit_comment/>
+                                }.into_view(cx)
+                            })}
+                        </Transition>
+                    </main>
+                }.into_view(cx),
+            })}
+        </Suspense>
+    }
+}
+
+/// A list of comments and their replies.
+#[component]
+fn Comments(
+    cx: Scope,
+    comments: Vec<content::Comment>,
+    post_id: PostId,
+    locked: bool,
+    action: Action<SubmitComment, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let t = Translations::default();
+    view! { cx,
+        <ul class="list-style-none">
+            {comments.into_iter().map(|comment| {
+                let (replying, set_replying) = create_signal(cx, false);
+                view! { cx,
+                    <li id=format!("comment-{}", comment.comment_id) class="my-3 ml-3">
+                        <div class="color-fg-muted f6">
+                            {comment.author.0}{" "}
+                            {datetime::ago(&comment.created, Language::default())}{" "}
+                            {(!locked).then(|| view! { cx,
+                                <a href="#" on:click=move |event| {
+                                    event.prevent_default();
+                                    set_replying.update(|replying| *replying = !*replying);
+                                }>{t.reply()}</a>
+                            })}
+                            {(!comment.removed).then(|| view! { cx, <ReportForm comment_id=comment.comment_id/> })}
+                            {comment.held.then(|| view! { cx,
+                                <span class="Label Label--attention ml-1" title=t.held_for_review()>{t.held()}</span>
+                            })}
+                        </div>
+                        {if comment.removed {
+                            view! { cx, <div class="color-fg-muted">{t.comment_removed()}</div> }.into_view(cx)
+                        } else {
+                            view! { cx, <div inner_html=comment.body_html></div> }.into_view(cx)
+                        }}
+                        <Show when=replying fallback=|_| ()>
+                            <CommentForm post_id parent_id=Some(comment.comment_id) action/>
+                        </Show>
+                        {(!comment.replies.is_empty()).then(|| view! { cx,
+                            <Comments comments=comment.replies post_id locked action/>
+                        })}
+                    </li>
+                }
+            }).collect_view(cx)}
+        </ul>
+    }
+}
+
+/// Buttons to pin, lock and remove a post, shown to the moderators of its
+/// community.
+#[component]
+#[allow(clippy::too_many_arguments)]
+fn ModeratorTools(
+    cx: Scope,
+    community: CommunityName,
+    post_id: PostId,
+    pinned: bool,
+    locked: bool,
+    removed: bool,
+    set_pinned: Action<SetPinned, Result<(), ServerFnError>>,
+    set_locked: Action<SetLocked, Result<(), ServerFnError>>,
+    remove_post: Action<RemovePost, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let t = Translations::default();
+    let membership = create_resource(
+        cx,
+        move || community.clone(),
+        move |community| async move {
+            community::get_membership(cx, community)
+                .await
+                .unwrap_or_default()
+        },
+    );
+
+    view! { cx,
+        <Suspense fallback=|| ()>
+            {move || membership.read(cx).filter(|membership| membership.moderator).map(|_| view! { cx,
+                <div class="d-flex mb-3">
+                    <ActionForm action=set_pinned class="mr-2">
+                        <input type="hidden" name="post_id" value=post_id.to_string()/>
+                        <input type="hidden" name="pinned" value=(!pinned).to_string()/>
+                        <button type="submit" class="btn btn-sm">{if pinned { t.unpin() } else { t.pin() }}</button>
+                    </ActionForm>
+                    <ActionForm action=set_locked class="mr-2">
+                        <input type="hidden" name="post_id" value=post_id.to_string()/>
+                        <input type="hidden" name="locked" value=(!locked).to_string()/>
+                        <button type="submit" class="btn btn-sm">{if locked { t.unlock() } else { t.lock() }}</button>
+                    </ActionForm>
+                    {(!removed).then(|| view! { cx,
+                        <ActionForm action=remove_post class="d-flex">
+                            <input type="hidden" name="post_id" value=post_id.to_string()/>
+                            <input class="form-control input-sm mr-2" type="text" name="reason" required
+                                placeholder=t.reason() aria-label=t.reason() maxlength=MAX_REASON_BYTES/>
+                            <button type="submit" class="btn btn-sm btn-danger">{t.remove()}</button>
+                        </ActionForm>
+                    })}
+                </div>
+            })}
+        </Suspense>
+    }
+}
+
+/// A form to comment on a post or reply to a comment.
+#[component]
+fn CommentForm(
+    cx: Scope,
+    post_id: PostId,
+    parent_id: Option<CommentId>,
+    action: Action<SubmitComment, Result<(), ServerFnError>>,
+) -> impl IntoView {
+    let t = Translations::default();
+    view! { cx,
+        <ActionForm action class="my-3">
+            <input type="hidden" name="post_id" value=post_id.to_string()/>
+            {parent_id.map(|parent_id| view! { cx,
+                <input type="hidden" name="parent_id" value=parent_id.to_string()/>
+            })}
+            <textarea class="form-control width-full" name="body" required></textarea>
+            <button type="submit" class="btn btn-sm mt-2">
+                {if parent_id.is_some() { t.reply() } else { t.add_comment() }}
+            </button>
+        </ActionForm>
+    }
+}
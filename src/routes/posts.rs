@@ -0,0 +1,49 @@
+use {
+    crate::{
+        components::PostItem,
+        content::{self, POSTS_PER_PAGE},
+        internationalization::Translations,
+        types::DateTime,
+    },
+    chrono::SecondsFormat,
+    leptos::*,
+    leptos_router::*,
+};
+
+/// The newest posts, paginated by the creation time of the last post on the
+/// previous page.
+#[component]
+pub fn Posts(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+    let query = use_query_map(cx);
+    let before = move || {
+        query.with(|query| {
+            query
+                .get("before")
+                .and_then(|before| before.parse::<DateTime>().ok())
+        })
+    };
+    let posts = create_resource(cx, before, move |before| {
+        content::get_newest_posts(cx, before)
+    });
+
+    view! { cx,
+        <main>
+            <Transition fallback=|| ()>
+                {move || posts.read(cx).and_then(Result::ok).map(|posts| {
+                    let more = (posts.len() as i64 == POSTS_PER_PAGE)
+                        .then(|| posts.last().map(|post| post.created.to_rfc3339_opts(SecondsFormat::Micros, true)))
+                        .flatten();
+                    view! { cx,
+                        <ul class="list-style-none">
+                            {posts.into_iter().map(|post| view! { cx, <PostItem post/> }).collect_view(cx)}
+                        </ul>
+                        {more.map(|before| view! { cx,
+                            <A href=format!("?before={before}") class="d-inline-block mt-3">{t.more()}</A>
+                        })}
+                    }
+                })}
+            </Transition>
+        </main>
+    }
+}
@@ -0,0 +1,111 @@
+use {
+    crate::{
+        internationalization::{Language, Translations},
+        routes::SEARCH,
+        search::{self, Cursor, Filters, SearchResult, MAX_QUERY_BYTES, RESULTS_PER_PAGE},
+        types::{datetime, CommunityName, Username},
+    },
+    leptos::*,
+    leptos_router::*,
+};
+
+#[component]
+pub fn Search(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+    let query_map = use_query_map(cx);
+    let param = move |name: &str| {
+        query_map.with(|query_map| {
+            query_map
+                .get(name)
+                .map(|value| value.trim().to_owned())
+                .filter(|value| !value.is_empty())
+        })
+    };
+    let query = move || param("q").unwrap_or_default();
+    let filters = move || Filters {
+        author: param("author").map(Username),
+        community: param("community").map(CommunityName),
+        from: param("from").and_then(|from| from.parse().ok()),
+        to: param("to").and_then(|to| to.parse().ok()),
+    };
+    let after = move || param("after").and_then(|after| after.parse::<Cursor>().ok());
+
+    let results = create_resource(
+        cx,
+        move || (query(), filters(), after()),
+        move |(query, filters, after)| search::search(cx, query, filters, after),
+    );
+
+    // the link to the next page keeps the query and filters
+    let next_page = move |cursor: Cursor| {
+        let mut query_map = query_map.get();
+        query_map.insert("after".to_owned(), cursor.to_string());
+        format!("{SEARCH}{}", query_map.to_query_string())
+    };
+
+    view! { cx,
+        <main>
+            <Form method="GET" action=SEARCH class="d-flex flex-wrap flex-items-end mb-4">
+                <input class="form-control flex-auto mr-2 mb-2" type="search" name="q" aria-label=t.search()
+                    maxlength=MAX_QUERY_BYTES prop:value=query/>
+                <input class="form-control mr-2 mb-2" type="text" name="author" placeholder=t.author()
+                    aria-label=t.author() prop:value=move || param("author").unwrap_or_default()/>
+                <input class="form-control mr-2 mb-2" type="text" name="community" placeholder=t.community()
+                    aria-label=t.community() prop:value=move || param("community").unwrap_or_default()/>
+                <label class="mr-2 mb-2">
+                    {t.from()}{" "}
+                    <input class="form-control" type="date" name="from" prop:value=move || param("from").unwrap_or_default()/>
+                </label>
+                <label class="mr-2 mb-2">
+                    {t.to()}{" "}
+                    <input class="form-control" type="date" name="to" prop:value=move || param("to").unwrap_or_default()/>
+                </label>
+                <button type="submit" class="btn btn-primary mb-2">{t.search()}</button>
+            </Form>
+            <Transition fallback=|| ()>
+                {move || results.read(cx).map(|results| match results {
+                    Err(error) => view! { cx, <div class="flash flash-error">{error.to_string()}</div> }.into_view(cx),
+                    Ok(results) if results.is_empty() => (!query().is_empty()).then(|| view! { cx,
+                        <p class="color-fg-muted">{t.no_results()}</p>
+                    }).into_view(cx),
+                    Ok(results) => {
+                        let more = (results.len() as i64 == RESULTS_PER_PAGE)
+                            .then(|| results.last().map(|result| result.cursor))
+                            .flatten();
+                        view! { cx,
+                            <ul class="list-style-none">
+                                {results.into_iter().map(|result| view! { cx, <ResultItem result/> }).collect_view(cx)}
+                            </ul>
+                            {more.map(|cursor| view! { cx,
+                                <A href=next_page(cursor) class="d-inline-block mt-3">{t.more()}</A>
+                            })}
+                        }.into_view(cx)
+                    }
+                })}
+            </Transition>
+        </main>
+    }
+}
+
+/// A search result with the matches highlighted.
+#[component]
+fn ResultItem(cx: Scope, result: SearchResult) -> impl IntoView {
+    let t = Translations::default();
+    let href = match result.comment_id {
+        Some(comment_id) => format!("/posts/{}#comment-{comment_id}", result.post_id),
+        None => format!("/posts/{}", result.post_id),
+    };
+    view! { cx,
+        <li class="py-2 border-bottom">
+            <A href class="Link--primary text-bold"><span inner_html=result.title_html></span></A>
+            <div class="color-fg-muted f6">
+                {result.community.map(|community| view! { cx,
+                    <A href=format!("/c/{community}")>{format!("c/{community}")}</A>{" | "}
+                })}
+                {t.by()}{" "}{result.author.0}{" "}
+                {datetime::ago(&result.created, Language::default())}
+            </div>
+            <p class="mb-0" inner_html=result.snippet_html></p>
+        </li>
+    }
+}
@@ -1,53 +1,71 @@
 use {
-    crate::{
-        api::{self, User},
-        scroll_to_top,
-    },
+    crate::{internationalization::Translations, routes::*, scroll_to_top, sign_in::SignIn},
     leptos::*,
     leptos_router::*,
 };
 
 #[component]
 pub fn Signin(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+
+    let sign_in = create_server_action::<SignIn>(cx);
+    let error = move || {
+        sign_in
+            .value()
+            .get()
+            .and_then(Result::err)
+            .map(|error| error.to_string())
+    };
+
     scroll_to_top();
-    let params = use_params_map(cx);
-    let user = create_resource(
-        cx,
-        move || params().get("id").cloned().unwrap_or_default(),
-        move |id| async move {
-            if id.is_empty() {
-                None
-            } else {
-                api::fetch_api::<User>(cx, &api::user(&id)).await
-            }
-        },
-    );
     view! { cx,
-        <div class="user-view">
-            <Suspense fallback=|| view! { cx, "Loading..." }>
-                {move || user.read(cx).map(|user| match user {
-                    None => view! { cx,  <h1>"User not found."</h1> }.into_any(),
-                    Some(user) => view! { cx,
-                        <div>
-                            <h1>"User: " {&user.id}</h1>
-                            <ul class="meta">
-                                <li>
-                                    <span class="label">"Created: "</span> {user.created}
-                                </li>
-                                <li>
-                                <span class="label">"Karma: "</span> {user.karma}
-                                </li>
-                                <li inner_html={user.about} class="about"></li>
-                            </ul>
-                            <p class="links">
-                                <a href=format!("https://news.ycombinator.com/submitted?id={}", user.id)>"submissions"</a>
-                                " | "
-                                <a href=format!("https://news.ycombinator.com/threads?id={}", user.id)>"comments"</a>
-                            </p>
+        <main class="container-sm my-4">
+            <div class="Box Box--spacious">
+                <div class="Box-header">
+                    <h1 class="Box-title">
+                        {t.sign_in()}
+                    </h1>
+                </div>
+                <div class="Box-body">
+                    {move || error().map(|error| view! { cx, <div class="flash flash-error mb-3">{error}</div> })}
+                    <ActionForm action=sign_in>
+                        // username
+                        <div class="form-group">
+                            <div class="form-group-header">
+                                <label for="username">{t.username()}</label>
+                            </div>
+                            <div class="form-group-body">
+                                <input class="form-control width-full" type="text" id="username" name="username" />
+                            </div>
+                        </div>
+
+                        // password
+                        <div class="form-group">
+                            <div class="form-group-header">
+                                <label for="password">{t.password()}</label>
+                            </div>
+                            <div class="form-group-body">
+                                <input class="form-control width-full" type="password" id="password" name="password" />
+                            </div>
+                        </div>
+                        <div class=" text-right mt-4">
+                            <button type="submit" class="btn btn-primary">{t.sign_in()}</button>
                         </div>
-                    }.into_any()
-                })}
-            </Suspense>
-        </div>
+                    </ActionForm>
+                </div>
+            </div>
+            <div class="Box mt-4">
+                <div class="Box-body text-center">
+                    <div class="blankslate color-fg-default">
+                        <A href=SIGNUP>{t.create_an_account()}</A>{"."}
+                    </div>
+                </div>
+            </div>
+            <div class="Box mt-4">
+                <div class="Box-body text-center color-fg-subtle">
+                    {crate::copyright()}
+                </div>
+            </div>
+        </main>
     }
 }
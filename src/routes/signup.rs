@@ -1,13 +1,7 @@
 use {
-    crate::{
-        internationalization::Translations,
-        routes::*,
-        scroll_to_top,
-        types::{self, password},
-    },
+    crate::{internationalization::Translations, routes::*, scroll_to_top},
     leptos::*,
     leptos_router::*,
-    leptos_use::use_throttle_fn,
 };
 
 #[component]
@@ -15,32 +9,21 @@ pub fn Signup(cx: Scope) -> impl IntoView {
     let t = Translations::default();
 
     let (username, set_username) = create_signal(cx, String::new());
-    let (username_violations, set_username_violations) = create_signal(cx, Ok(()));
-    let on_username_change = move |e| {
-        let u = event_target_value(&e);
-        set_username_violations(types::username::validate(&u));
-        set_username(u);
-    };
+    let on_username_change = move |e| set_username(event_target_value(&e));
 
-    let email_input = create_node_ref::<html::Input>(cx);
-    let password_input = create_node_ref::<html::Input>(cx);
-    let password_again_input = create_node_ref::<html::Input>(cx);
+    let create_account = create_server_action::<CreateAccount>(cx);
+    let error = move || {
+        create_account
+            .value()
+            .get()
+            .and_then(Result::err)
+            .map(|error| error.to_string())
+    };
 
     let is_password_warning = || false;
     let is_password_errored = || false;
     let is_email_errored = || false;
 
-    let check_username_availability = use_throttle_fn(
-        move || {
-            //
-        },
-        2000.0,
-    );
-
-    let submit = move |_| {
-        //
-    };
-
     scroll_to_top();
     view! { cx,
         <main class="container-sm my-4">
@@ -51,14 +34,15 @@ pub fn Signup(cx: Scope) -> impl IntoView {
                     </h1>
                 </div>
                 <div class="Box-body">
-                    <form>
+                    {move || error().map(|error| view! { cx, <div class="flash flash-error mb-3">{error}</div> })}
+                    <ActionForm action=create_account>
                         // username
                         <div class="form-group">
                             <div class="form-group-header">
                                 <label for="username">{t.username()}</label>
                             </div>
                             <div class="form-group-body">
-                                <input class="form-control width-full" type="text" id="username"
+                                <input class="form-control width-full" type="text" id="username" name="username"
                                     on:input=on_username_change
                                     prop:value=username
                                 />
@@ -71,7 +55,7 @@ pub fn Signup(cx: Scope) -> impl IntoView {
                                 <label for="email">{t.email()}</label>
                             </div>
                             <div class="form-group-body">
-                                <input class="form-control width-full" type="text" id="email" node_ref=email_input />
+                                <input class="form-control width-full" type="email" id="email" name="email" />
                                 <p class="note error">
                                     // <Show when=move || !email().is_empty() fallback=move |_| t.please_enter_an_email() >
                                     //     <span class="text-bold">{email()}</span>
@@ -87,7 +71,7 @@ pub fn Signup(cx: Scope) -> impl IntoView {
                                 <label for="password">{t.password()}</label>
                             </div>
                             <div class="form-group-body">
-                                <input class="form-control width-full" type="text" id="password" node_ref=password_input />
+                                <input class="form-control width-full" type="password" id="password" name="password" />
                                 <p class="note" class:warning=is_password_warning class:error=is_password_errored>
                                     // <Show when=move || !password().is_empty() fallback=move |_| t.please_enter_a_password() >
                                     //     {"password has issues"}
@@ -102,19 +86,20 @@ pub fn Signup(cx: Scope) -> impl IntoView {
                                 <label for="password_again">{t.retype_password()}</label>
                             </div>
                             <div class="form-group-body">
-                                <input class="form-control width-full" type="text" id="password_again" node_ref=password_again_input />
+                                <input class="form-control width-full" type="password" id="password_again"
+                                    name="password_again" />
                             </div>
                         </div>
-                    </form>
-                    <p class="color-fg-default">{t.terms_and_privacy_disclaimer_1()}
-                        <A href=TERMS_AND_CONDITIONS>{t.terms_and_conditions()}</A>
-                        {t.terms_and_privacy_disclaimer_2()}
-                        <A href=PRIVACY_POLICY>{t.privacy_policy()}</A>
-                        {t.terms_and_privacy_disclaimer_3()}
-                    </p>
-                    <div class=" text-right mt-4">
-                        <button class="btn btn-primary" on:click=submit>{t.create_free_account()}</button>
-                    </div>
+                        <p class="color-fg-default">{t.terms_and_privacy_disclaimer_1()}
+                            <A href=TERMS_AND_CONDITIONS>{t.terms_and_conditions()}</A>
+                            {t.terms_and_privacy_disclaimer_2()}
+                            <A href=PRIVACY_POLICY>{t.privacy_policy()}</A>
+                            {t.terms_and_privacy_disclaimer_3()}
+                        </p>
+                        <div class=" text-right mt-4">
+                            <button type="submit" class="btn btn-primary">{t.create_free_account()}</button>
+                        </div>
+                    </ActionForm>
                 </div>
             </div>
             <div class="Box mt-4">
@@ -133,6 +118,68 @@ pub fn Signup(cx: Scope) -> impl IntoView {
     }
 }
 
+/// Creates an account, records that the user accepted the current versions of
+/// the legal documents and signs the user in. The password has to be typed
+/// twice, so a typo doesn't create an account the user can't sign in to.
+#[tracing::instrument(skip_all)]
+#[server(CreateAccount, "/api")]
+pub async fn create_account(
+    cx: Scope,
+    username: String,
+    email: String,
+    password: String,
+    password_again: String,
+) -> Result<(), ServerFnError> {
+    use crate::{
+        internationalization::Language,
+        legal, postgres,
+        rate_limit::{self, Policy},
+        session,
+        types::{password, validation::Validate, Email, Password, UserId, Username},
+    };
+
+    rate_limit::check(cx, Policy::Signup).await?;
+
+    let username = Username(username);
+    let email = Email(email);
+    let password = Password(password);
+    if password != Password(password_again) {
+        return Err(ServerFnError::Args("the passwords don't match".to_owned()));
+    }
+
+    username
+        .validate()
+        .map_err(|violations| ServerFnError::Args(violations.to_string()))?;
+    email
+        .validate()
+        .map_err(|violations| ServerFnError::Args(violations.to_string()))?;
+    password::validate(&password.0, &[&username.0, &email.0])
+        .map_err(|violations| ServerFnError::Args(violations.to_string()))?;
+
+    if !postgres::is_username_available(&username).await? {
+        return Err(ServerFnError::Args("username is taken".to_owned()));
+    }
+    if !postgres::is_email_available(&email).await? {
+        return Err(ServerFnError::Args("email is taken".to_owned()));
+    }
+
+    let user_id = UserId(uuid::Uuid::new_v4());
+    postgres::create_user(
+        user_id,
+        &username,
+        &email,
+        &password.hash().await?,
+        Language::default(),
+        &legal::current_versions(),
+    )
+    .await?;
+
+    session::start(cx, user_id).await?;
+    leptos_actix::redirect(cx, HOME);
+    Ok(())
+}
+
+#[tracing::instrument(skip_all)]
 #[server(CheckUsernameAvailability, "/api")]
 pub async fn check_username_availability() -> Result<bool, ServerFnError> {
     // tokio::time::sleep(std::time::Duration::from_secs(1)).await;
@@ -1,23 +1,15 @@
-use crate::api;
-use leptos::*;
-use leptos_meta::*;
-use leptos_router::*;
+use {crate::api, leptos::*, leptos_meta::*, leptos_router::*};
 
 #[component]
 pub fn Story(cx: Scope) -> impl IntoView {
     let params = use_params_map(cx);
     let story = create_resource(
         cx,
-        move || params().get("id").cloned().unwrap_or_default(),
+        move || params().get("id").and_then(|id| id.parse().ok()),
         move |id| async move {
-            if id.is_empty() {
-                None
-            } else {
-                api::fetch_api::<api::Story>(
-                    cx,
-                    &api::story(&format!("item/{id}")),
-                )
-                .await
+            match id {
+                None => None,
+                Some(id) => api::get_story(cx, id).await.ok().flatten(),
             }
         },
     );
@@ -0,0 +1,74 @@
+use {
+    crate::{content::SubmitPost, internationalization::Translations, scroll_to_top},
+    leptos::*,
+    leptos_router::*,
+};
+
+#[component]
+pub fn Submit(cx: Scope) -> impl IntoView {
+    let t = Translations::default();
+    let query = use_query_map(cx);
+    let community =
+        query.with_untracked(|query| query.get("community").cloned().unwrap_or_default());
+    let submit_post = create_server_action::<SubmitPost>(cx);
+    let error = move || {
+        submit_post
+            .value()
+            .get()
+            .and_then(Result::err)
+            .map(|error| error.to_string())
+    };
+
+    scroll_to_top();
+    view! { cx,
+        <main class="container-md my-4">
+            <div class="Box Box--spacious">
+                <div class="Box-header">
+                    <h1 class="Box-title">{t.submit_a_post()}</h1>
+                </div>
+                <div class="Box-body">
+                    {move || error().map(|error| view! { cx, <div class="flash flash-error mb-3">{error}</div> })}
+                    <ActionForm action=submit_post>
+                        <div class="form-group">
+                            <div class="form-group-header">
+                                <label for="community">{t.community()}</label>
+                            </div>
+                            <div class="form-group-body">
+                                <input class="form-control width-full" type="text" id="community" name="community"
+                                    value=community required />
+                            </div>
+                        </div>
+                        <div class="form-group">
+                            <div class="form-group-header">
+                                <label for="title">{t.title()}</label>
+                            </div>
+                            <div class="form-group-body">
+                                <input class="form-control width-full" type="text" id="title" name="title" required />
+                            </div>
+                        </div>
+                        <div class="form-group">
+                            <div class="form-group-header">
+                                <label for="url">{t.url()}</label>
+                            </div>
+                            <div class="form-group-body">
+                                <input class="form-control width-full" type="url" id="url" name="url" />
+                            </div>
+                        </div>
+                        <div class="form-group">
+                            <div class="form-group-header">
+                                <label for="body">{t.text()}</label>
+                            </div>
+                            <div class="form-group-body">
+                                <textarea class="form-control width-full" id="body" name="body"></textarea>
+                                <p class="note">{t.markdown_supported()}</p>
+                            </div>
+                        </div>
+                        <div class="text-right mt-4">
+                            <button type="submit" class="btn btn-primary">{t.submit()}</button>
+                        </div>
+                    </ActionForm>
+                </div>
+            </div>
+        </main>
+    }
+}
@@ -1,4 +1,4 @@
-use crate::api::{self, User};
+use crate::api;
 use leptos::*;
 use leptos_router::*;
 
@@ -12,7 +12,7 @@ pub fn User(cx: Scope) -> impl IntoView {
             if id.is_empty() {
                 None
             } else {
-                api::fetch_api::<User>(cx, &api::user(&id)).await
+                api::get_user(cx, id).await.ok().flatten()
             }
         },
     );
@@ -31,7 +31,7 @@ pub fn User(cx: Scope) -> impl IntoView {
                                 <li>
                                 <span class="label">"Karma: "</span> {user.karma}
                                 </li>
-                                <li inner_html={user.about} class="about"></li>
+                                <li class="about" inner_html=user.about></li>
                             </ul>
                             <p class="links">
                                 <a href=format!("https://news.ycombinator.com/submitted?id={}", user.id)>"submissions"</a>
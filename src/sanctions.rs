@@ -0,0 +1,310 @@
+//! User sanctions module.
+//!
+//! Moderators suspend users for a number of days, ban them permanently or
+//! shadow-ban them, either in a community or site-wide. Site-wide suspensions
+//! and bans sign the user out and block signing in, in a community they block
+//! posting and commenting there. Both are explained to the user in their
+//! language. Shadow-banned users can keep posting but their posts and comments
+//! are only shown to themselves. Sanctions are recorded in the moderation log.
+
+use {
+    crate::{internationalization::Translations, moderation::Action, types::*},
+    leptos::*,
+    serde::{Deserialize, Serialize},
+};
+
+/// The maximum number of days a user can be suspended for.
+pub const MAX_SUSPENSION_DAYS: u32 = 365;
+
+/// A kind of sanction.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(type_name = "sanction_kind"))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize, Hash)]
+pub enum Kind {
+    /// Blocks the user for a number of days.
+    Suspension,
+
+    /// Blocks the user until it's lifted.
+    Ban,
+
+    /// Hides the user's posts and comments from everyone else.
+    ShadowBan,
+}
+
+/// A list of all kinds of sanctions.
+pub const KINDS: &[Kind] = &[Kind::Suspension, Kind::Ban, Kind::ShadowBan];
+
+impl Kind {
+    /// The localized label of the kind.
+    pub fn label(self, t: Translations) -> &'static str {
+        match self {
+            Kind::Suspension => t.suspension(),
+            Kind::Ban => t.permanent_ban(),
+            Kind::ShadowBan => t.shadow_ban(),
+        }
+    }
+
+    /// The moderation action recorded when the sanction is issued.
+    pub const fn action(self) -> Action {
+        match self {
+            Kind::Suspension => Action::Suspend,
+            Kind::Ban => Action::Ban,
+            Kind::ShadowBan => Action::ShadowBan,
+        }
+    }
+}
+
+/// A sanction that's in effect.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Sanction {
+    pub sanction_id: SanctionId,
+    pub created: DateTime,
+    pub username: Username,
+    pub kind: Kind,
+
+    /// When a suspension ends.
+    pub expires: Option<DateTime>,
+
+    pub reason: String,
+
+    /// The moderator who issued the sanction, if they still exist.
+    pub issued_by: Option<Username>,
+}
+
+/// Returns the sanctions in effect in a community, or site-wide if no
+/// community is given.
+#[tracing::instrument(skip_all)]
+#[server(GetSanctions, "/api")]
+pub async fn get_sanctions(
+    cx: Scope,
+    community: Option<CommunityName>,
+) -> Result<Vec<Sanction>, ServerFnError> {
+    let community_id = require_sanction_permission(cx, community.as_ref()).await?.1;
+    Ok(crate::postgres::sanctions(community_id).await?)
+}
+
+/// Sanctions a user in a community, or site-wide if no community is given.
+/// Suspensions last the given number of days, bans and shadow-bans until
+/// they're lifted.
+#[tracing::instrument(skip_all)]
+#[server(SanctionUser, "/api")]
+pub async fn sanction_user(
+    cx: Scope,
+    username: Username,
+    community: Option<CommunityName>,
+    kind: Kind,
+    days: Option<u32>,
+    reason: String,
+) -> Result<(), ServerFnError> {
+    use crate::{
+        moderation::{validate_reason, Subject},
+        postgres,
+    };
+
+    let (moderator, community_id) = require_sanction_permission(cx, community.as_ref()).await?;
+    let expires = expiry(kind, days, chrono::Utc::now())?;
+    let reason = validate_reason(kind.action(), reason)?;
+    let user_id = postgres::user_id(&username)
+        .await?
+        .ok_or_else(|| ServerFnError::Args(format!("the user {username} doesn't exist")))?;
+    if user_id == moderator {
+        return Err(ServerFnError::Args(
+            "you can't sanction yourself".to_owned(),
+        ));
+    }
+
+    let subject = Subject {
+        community_id,
+        post_id: None,
+        comment_id: None,
+        user_id,
+    };
+    let deleted_sessions =
+        postgres::sanction(moderator, &subject, kind, expires, None, &reason).await?;
+    crate::cache::invalidate_sessions(user_id, &deleted_sessions).await;
+    crate::cache::invalidate_front_page().await;
+    Ok(())
+}
+
+/// Returns when a sanction issued now ends, or an error if a suspension
+/// doesn't last between 1 and [`MAX_SUSPENSION_DAYS`] days. Bans and
+/// shadow-bans don't end.
+#[cfg(feature = "ssr")]
+fn expiry(kind: Kind, days: Option<u32>, now: DateTime) -> Result<Option<DateTime>, ServerFnError> {
+    match (kind, days) {
+        (Kind::Suspension, Some(days @ 1..=MAX_SUSPENSION_DAYS)) => {
+            Ok(Some(now + chrono::Duration::days(days.into())))
+        }
+        (Kind::Suspension, _) => Err(ServerFnError::Args(format!(
+            "suspensions last between 1 and {MAX_SUSPENSION_DAYS} days"
+        ))),
+        _ => Ok(None),
+    }
+}
+
+/// Lifts a sanction before it ends.
+#[tracing::instrument(skip_all)]
+#[server(LiftSanction, "/api")]
+pub async fn lift_sanction(cx: Scope, sanction_id: SanctionId) -> Result<(), ServerFnError> {
+    use crate::{permissions, postgres, session};
+
+    let subject = postgres::sanction_subject(sanction_id)
+        .await?
+        .ok_or_else(|| ServerFnError::Args("the sanction doesn't exist".to_owned()))?;
+    let moderator = match subject.community_id {
+        Some(community_id) => {
+            let moderator = session::require_user_id(cx).await?;
+            permissions::require_moderator(moderator, Some(community_id)).await?;
+            moderator
+        }
+        None => permissions::require_permission(cx, permissions::Permission::SanctionUsers).await?,
+    };
+    postgres::lift_sanction(sanction_id, moderator, &subject).await?;
+    crate::cache::invalidate_front_page().await;
+    Ok(())
+}
+
+/// Returns the id of the signed in user and the community or an error if the
+/// user isn't allowed to sanction users in the community, or site-wide if no
+/// community is given.
+#[cfg(feature = "ssr")]
+async fn require_sanction_permission(
+    cx: Scope,
+    community: Option<&CommunityName>,
+) -> Result<(UserId, Option<CommunityId>), ServerFnError> {
+    use crate::{community, permissions};
+
+    match community {
+        Some(community) => {
+            let (community_id, moderator) = community::require_moderator(cx, community).await?;
+            Ok((moderator, Some(community_id)))
+        }
+        None => Ok((
+            permissions::require_permission(cx, permissions::Permission::SanctionUsers).await?,
+            None,
+        )),
+    }
+}
+
+/// A suspension or ban blocking a user.
+#[cfg(feature = "ssr")]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Block {
+    pub kind: Kind,
+    pub site_wide: bool,
+    pub expires: Option<DateTime>,
+    pub reason: String,
+}
+
+#[cfg(feature = "ssr")]
+impl Block {
+    /// Explains the block to the user in their language.
+    pub fn explain(&self, t: Translations) -> String {
+        let summary = match (self.kind, self.site_wide) {
+            (Kind::Suspension, true) => t.account_suspended(),
+            (Kind::Suspension, false) => t.community_suspended(),
+            (_, true) => t.account_banned(),
+            (_, false) => t.community_banned(),
+        };
+        let mut explanation = summary.to_owned();
+        if let Some(expires) = &self.expires {
+            explanation.push_str(&format!(
+                " {}: {}.",
+                t.until(),
+                expires.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+        if !self.reason.is_empty() {
+            explanation.push_str(&format!(" {}: {}", t.reason(), self.reason));
+        }
+        explanation
+    }
+}
+
+/// Returns an error explaining the block if the user is suspended or banned
+/// site-wide.
+#[cfg(feature = "ssr")]
+pub async fn require_not_blocked(user_id: UserId) -> Result<(), ServerFnError> {
+    require_not_blocked_in(user_id, None).await
+}
+
+/// Returns an error explaining the block if the user is suspended or banned
+/// site-wide or in the community.
+#[cfg(feature = "ssr")]
+pub async fn require_not_blocked_in(
+    user_id: UserId,
+    community_id: Option<CommunityId>,
+) -> Result<(), ServerFnError> {
+    use crate::postgres;
+
+    match postgres::block(user_id, community_id).await? {
+        None => Ok(()),
+        Some(block) => {
+            let language = postgres::user_language(user_id).await?;
+            Err(ServerFnError::ServerError(
+                block.explain(Translations::for_language(language)),
+            ))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod test {
+    use {super::*, chrono::TimeZone};
+
+    #[test]
+    fn test_expiry() {
+        let now = chrono::Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            expiry(Kind::Suspension, Some(1), now).unwrap(),
+            Some(now + chrono::Duration::days(1))
+        );
+        assert_eq!(
+            expiry(Kind::Suspension, Some(MAX_SUSPENSION_DAYS), now).unwrap(),
+            Some(now + chrono::Duration::days(MAX_SUSPENSION_DAYS.into()))
+        );
+        assert!(expiry(Kind::Suspension, Some(0), now).is_err());
+        assert!(expiry(Kind::Suspension, Some(MAX_SUSPENSION_DAYS + 1), now).is_err());
+        assert!(expiry(Kind::Suspension, None, now).is_err());
+        assert_eq!(expiry(Kind::Ban, Some(7), now).unwrap(), None);
+        assert_eq!(expiry(Kind::ShadowBan, None, now).unwrap(), None);
+    }
+
+    #[test]
+    fn test_explain() {
+        let t = Translations::default();
+        let block = |kind, site_wide, expires, reason: &str| Block {
+            kind,
+            site_wide,
+            expires,
+            reason: reason.to_owned(),
+        };
+        let expires = chrono::Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 0).unwrap();
+
+        assert_eq!(
+            block(Kind::Suspension, true, Some(expires), "spam").explain(t),
+            format!(
+                "{} {}: 2026-01-02 03:04 UTC. {}: spam",
+                t.account_suspended(),
+                t.until(),
+                t.reason()
+            )
+        );
+        assert_eq!(
+            block(Kind::Suspension, false, Some(expires), "").explain(t),
+            format!(
+                "{} {}: 2026-01-02 03:04 UTC.",
+                t.community_suspended(),
+                t.until()
+            )
+        );
+        assert_eq!(
+            block(Kind::Ban, true, None, "").explain(t),
+            t.account_banned()
+        );
+        assert_eq!(
+            block(Kind::Ban, false, None, "spam").explain(t),
+            format!("{} {}: spam", t.community_banned(), t.reason())
+        );
+    }
+}
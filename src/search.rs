@@ -0,0 +1,160 @@
+//! Full-text search module.
+//!
+//! Posts and comments are indexed with the text search configuration of their
+//! author's language. Since a query can be written in any language, it's
+//! parsed with the configuration of every supported language and a result
+//! matches if any of them match.
+
+use {
+    crate::types::*,
+    chrono::NaiveDate,
+    leptos::*,
+    serde::{Deserialize, Serialize},
+    std::{fmt, str::FromStr},
+    uuid::Uuid,
+};
+
+/// The maximum number of bytes a search query can be.
+pub const MAX_QUERY_BYTES: usize = 256;
+
+/// The number of results per page.
+pub const RESULTS_PER_PAGE: i64 = 20;
+
+/// Marks the start of a highlighted match in the text returned by Postgres.
+/// A private use character so it can't be confused with html.
+pub const HIGHLIGHT_START: char = '\u{E000}';
+
+/// Marks the end of a highlighted match.
+pub const HIGHLIGHT_END: char = '\u{E001}';
+
+/// The filters of a search.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Filters {
+    /// Only return posts and comments by this user.
+    pub author: Option<Username>,
+
+    /// Only return posts and comments in this community.
+    pub community: Option<CommunityName>,
+
+    /// Only return posts and comments created on or after this day.
+    pub from: Option<NaiveDate>,
+
+    /// Only return posts and comments created on or before this day.
+    pub to: Option<NaiveDate>,
+}
+
+/// A post or comment matching a search.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub post_id: PostId,
+
+    /// Set if the match is a comment on the post.
+    pub comment_id: Option<CommentId>,
+
+    pub community: Option<CommunityName>,
+    pub created: DateTime,
+    pub author: Username,
+
+    /// The post title with the matches highlighted with `<mark>`.
+    pub title_html: String,
+
+    /// Excerpts of the post or comment body with the matches highlighted with
+    /// `<mark>`.
+    pub snippet_html: String,
+
+    /// Where the result is in the ranking, used to get the next page.
+    pub cursor: Cursor,
+}
+
+/// The position of a result in the ranking.
+///
+/// Results are ordered by relevance and then id, so the next page starts after
+/// the last result of the previous one.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct Cursor {
+    pub rank: f32,
+    pub id: Uuid,
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.rank, self.id)
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = ();
+
+    fn from_str(cursor: &str) -> Result<Self, Self::Err> {
+        let (rank, id) = cursor.split_once('_').ok_or(())?;
+        Ok(Self {
+            rank: rank.parse().map_err(drop)?,
+            id: id.parse().map_err(drop)?,
+        })
+    }
+}
+
+/// Searches posts and comments, returning the page of results after the
+/// cursor if any.
+#[tracing::instrument(skip_all)]
+#[server(Search, "/api")]
+pub async fn search(
+    cx: Scope,
+    query: String,
+    filters: Filters,
+    after: Option<Cursor>,
+) -> Result<Vec<SearchResult>, ServerFnError> {
+    use crate::rate_limit::{self, Policy};
+
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    rate_limit::check(cx, Policy::Search).await?;
+    if query.len() > MAX_QUERY_BYTES {
+        return Err(ServerFnError::Args("search query too long".to_owned()));
+    }
+    let viewer = crate::session::user_id(cx).await?;
+    Ok(crate::postgres::search(&query, &filters, after, viewer, RESULTS_PER_PAGE).await?)
+}
+
+/// Escapes text returned by `ts_headline` and turns the highlight markers
+/// into `<mark>` elements.
+#[cfg(feature = "ssr")]
+pub fn highlight(text: &str) -> String {
+    let mut html = String::with_capacity(text.len() + 32);
+    for c in text.chars() {
+        match c {
+            HIGHLIGHT_START => html.push_str("<mark>"),
+            HIGHLIGHT_END => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_highlight() {
+        assert_eq!(
+            highlight("a \u{E000}<b>cat</b>\u{E001} & \"dog\""),
+            "a <mark>&lt;b&gt;cat&lt;/b&gt;</mark> &amp; &quot;dog&quot;"
+        );
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor {
+            rank: 0.060_792_71,
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(cursor.to_string().parse(), Ok(cursor));
+    }
+}
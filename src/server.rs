@@ -2,29 +2,89 @@
 #![cfg(feature = "ssr")]
 
 use {
-    crate::config::ServerConfig,
+    crate::listener::{Listener, Passed},
     actix_files::Files,
-    actix_web::*,
-    anyhow::Result,
+    actix_web::{dev::Service, *},
+    anyhow::{Context, Result},
+    ipnet::IpNet,
     leptos::*,
     leptos_actix::LeptosRoutes,
+    marzichat::{config::ServerConfig, permissions::Permission, rate_limit::Policy},
     std::{
-        net::{Ipv4Addr, Ipv6Addr, SocketAddr},
         path::Path,
-        time::Duration,
+        time::{Duration, Instant},
     },
 };
 
+/// Liveness check, kept for existing deployments.
 #[get("/health")]
 async fn health() -> impl Responder {
     HttpResponse::NoContent()
 }
 
+/// Liveness check: the server is up and handling requests.
+#[get("/health/live")]
+async fn live() -> impl Responder {
+    HttpResponse::NoContent()
+}
+
+/// Readiness check: the services the server depends on can be reached.
+#[get("/health/ready")]
+async fn ready() -> impl Responder {
+    let readiness = marzichat::health::readiness().await;
+    if readiness.ready {
+        HttpResponse::Ok().json(readiness)
+    } else {
+        HttpResponse::ServiceUnavailable().json(readiness)
+    }
+}
+
 #[get("/info")]
 async fn info() -> impl Responder {
     HttpResponse::Ok()
         .content_type(http::header::ContentType::plaintext())
-        .body(marzichat::summary())
+        .body(format!(
+            "{}\n{}",
+            marzichat::summary(),
+            marzichat::monitor::summary()
+        ))
+}
+
+/// The networks of the peers allowed to scrape `/metrics`.
+struct MetricsNetworks(Vec<IpNet>);
+
+#[get("/metrics")]
+async fn metrics(request: HttpRequest, networks: web::Data<MetricsNetworks>) -> impl Responder {
+    // the peer, not a forwarded address, since proxies may pass on anyone
+    let allowed = request.peer_addr().map_or(false, |peer| {
+        networks
+            .0
+            .iter()
+            .any(|network| network.contains(&peer.ip()))
+    });
+    if !allowed {
+        return HttpResponse::NotFound().finish();
+    }
+    match marzichat::metrics::encode() {
+        Ok(metrics) => HttpResponse::Ok()
+            .content_type(marzichat::metrics::CONTENT_TYPE)
+            .body(metrics),
+        Err(error) => {
+            tracing::error!(?error, "couldn't encode metrics");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Answers the HTTP-01 challenges of the ACME server.
+#[get("/.well-known/acme-challenge/{token}")]
+async fn acme_challenge(token: web::Path<String>) -> impl Responder {
+    match marzichat::acme::key_authorization(&token) {
+        Some(key_authorization) => HttpResponse::Ok()
+            .content_type(http::header::ContentType::plaintext())
+            .body(key_authorization),
+        None => HttpResponse::NotFound().finish(),
+    }
 }
 
 #[get("/favicon.ico")]
@@ -34,7 +94,7 @@ async fn favicon(leptos_options: web::Data<LeptosOptions>) -> impl Responder {
 }
 
 /// Run the backend server with the given configs.
-pub async fn run(config: ServerConfig) -> Result<()> {
+pub async fn run(config: ServerConfig, passed: Passed) -> Result<()> {
     let leptos_options = {
         let mut opt = leptos_config::get_config_from_env()
             .expect("failed to get leptos config")
@@ -47,54 +107,104 @@ pub async fn run(config: ServerConfig) -> Result<()> {
         opt
     };
 
-    let site_addr = leptos_options.site_addr;
+    let certificate = match (config.tls, config.acme) {
+        (Some(_), Some(_)) => anyhow::bail!("tls and acme can't both be configured"),
+        (Some(certificate), None) => Some(certificate),
+        (None, Some(acme)) => Some(marzichat::acme::init(acme)?),
+        (None, None) => None,
+    };
+    let tls = certificate.map(marzichat::tls::server_config);
+    let listeners = crate::listener::open(&config.listeners, passed)?;
+    let https_port = listeners.iter().find_map(|listener| match listener {
+        Listener::Tcp {
+            listener,
+            tls: true,
+        } => listener.local_addr().ok().map(|address| address.port()),
+        _ => None,
+    });
 
     // Generate the list of routes in your Leptos App
     let routes = leptos_actix::generate_route_list(marzichat::App);
     let output_dir = marzichat::OUT_DIR;
-    let server = HttpServer::new(move || {
+    let limiter = crate::limiter::layer(config.rate_limiter);
+    let metrics_networks = web::Data::new(MetricsNetworks(config.metrics_networks));
+    let mut server = HttpServer::new(move || {
         App::new()
             .service(health)
+            .service(live)
+            .service(ready)
             .service(info)
+            .service(metrics)
             .service(favicon)
+            .service(acme_challenge)
             .service(Files::new(
                 output_dir,
                 format!("{}/{output_dir}", &leptos_options.site_root),
             ))
-            .route("/api/{tail:.*}", leptos_actix::handle_server_fns())
+            .service(
+                web::scope("/api")
+                    .wrap(crate::limiter::policy(Policy::Api))
+                    .route("/{tail:.*}", leptos_actix::handle_server_fns()),
+            )
             .leptos_routes(leptos_options.to_owned(), routes.to_owned(), marzichat::App)
-            .wrap(crate::limiter::layer(config.rate_limiter.clone()))
-            .wrap(middleware::Logger::new("%s for %U %a in %Ts"))
+            .wrap(crate::gate::RequirePermission::new(
+                marzichat::routes::ADMIN,
+                Permission::AccessAdmin,
+            ))
+            .wrap(limiter.clone())
+            .wrap_fn(|request, service| {
+                let start = Instant::now();
+                let method = request.method().clone();
+                let response = service.call(request);
+                async move {
+                    let response = response.await;
+                    let (route, status) = match &response {
+                        Ok(response) => (response.request().match_pattern(), response.status()),
+                        // the request is gone with the error, and with it the
+                        // matched route
+                        Err(error) => (None, error.as_response_error().status_code()),
+                    };
+                    marzichat::metrics::observe_request(
+                        route.as_deref(),
+                        method.as_str(),
+                        status.as_u16(),
+                        start.elapsed(),
+                    );
+                    response
+                }
+            })
+            .wrap(crate::request_id::RequestId)
             .wrap(sentry_actix::Sentry::new())
             .wrap(middleware::Compress::default())
-            .wrap(crate::redirect::HttpToHttps)
+            .wrap(crate::redirect::HttpToHttps::new(https_port))
             .wrap(middleware::NormalizePath::new(
                 middleware::TrailingSlash::Trim,
             ))
             .app_data(web::Data::new(leptos_options.to_owned()))
+            .app_data(metrics_networks.clone())
     });
 
-    let server = if let Some(tls) = config.tls {
-        let http = [
-            SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 80),
-            SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 80),
-        ];
-        let https = [
-            SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 443),
-            SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 443),
-        ];
-        server
-            .bind(http.as_ref())
-            .expect("couldn't bind to port 80")
-            .bind_rustls(https.as_ref(), tls)
-            .expect("couldn't bind to port 443")
-    } else {
-        tracing::warn!("Tls not configured");
-        server.bind(&site_addr).expect("couldn't bind port")
-    };
+    for listener in listeners {
+        server = match listener {
+            Listener::Tcp {
+                listener,
+                tls: false,
+            } => server.listen(listener)?,
+            Listener::Tcp {
+                listener,
+                tls: true,
+            } => {
+                let tls = tls
+                    .clone()
+                    .context("https listeners require tls or acme to be configured")?;
+                server.listen_rustls(listener, tls)?
+            }
+            Listener::Unix(listener) => server.listen_uds(listener)?,
+        };
+    }
 
     let workers = config.os_threads_per_bind_address.get();
-    tracing::info!(socket_addrs = ?server.addrs(), threads_per_addr = workers, "binding");
+    tracing::info!(socket_addrs = ?server.addrs(), ?https_port, threads_per_addr = workers, "binding");
 
     server
         .workers(workers)
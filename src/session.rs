@@ -0,0 +1,70 @@
+//! Cookie based user sessions.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::{postgres, types::UserId},
+    actix_web::{
+        cookie::{time, Cookie, SameSite},
+        http::header::{HeaderValue, SET_COOKIE},
+        HttpRequest,
+    },
+    leptos::*,
+    leptos_actix::ResponseOptions,
+    uuid::Uuid,
+};
+
+/// The name of the session cookie.
+const COOKIE: &str = "session";
+
+/// The number of days a session lasts before the user has to sign in again.
+const LIFETIME_DAYS: i64 = 30;
+
+/// Starts a new session for the user and sets the session cookie on the
+/// response. Users suspended or banned site-wide can't start a session.
+pub async fn start(cx: Scope, user_id: UserId) -> Result<(), ServerFnError> {
+    crate::sanctions::require_not_blocked(user_id).await?;
+
+    let session_id = Uuid::new_v4();
+    let expires = chrono::Utc::now() + chrono::Duration::days(LIFETIME_DAYS);
+    postgres::create_session(session_id, user_id, expires).await?;
+
+    let cookie = Cookie::build(COOKIE, session_id.to_string())
+        .path("/")
+        .http_only(true)
+        .secure(!cfg!(debug_assertions))
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::days(LIFETIME_DAYS))
+        .finish();
+
+    let response = use_context::<ResponseOptions>(cx)
+        .ok_or_else(|| ServerFnError::ServerError("missing response options".to_owned()))?;
+    response.append_header(SET_COOKIE, HeaderValue::from_str(&cookie.to_string())?);
+    Ok(())
+}
+
+/// Returns the id of the signed in user, if any.
+pub async fn user_id(cx: Scope) -> Result<Option<UserId>, ServerFnError> {
+    match use_context::<HttpRequest>(cx) {
+        Some(request) => Ok(request_user_id(&request).await?),
+        None => Ok(None),
+    }
+}
+
+/// Returns the id of the user signed in with the request's session cookie, if
+/// any.
+pub async fn request_user_id(request: &HttpRequest) -> Result<Option<UserId>, postgres::Error> {
+    let Some(session_id) = request
+        .cookie(COOKIE)
+        .and_then(|cookie| cookie.value().parse::<Uuid>().ok())
+    else {
+        return Ok(None);
+    };
+    crate::cache::session_user(session_id).await
+}
+
+/// Returns the id of the signed in user or an error if no user is signed in.
+pub async fn require_user_id(cx: Scope) -> Result<UserId, ServerFnError> {
+    user_id(cx)
+        .await?
+        .ok_or_else(|| ServerFnError::ServerError("not signed in".to_owned()))
+}
@@ -0,0 +1,264 @@
+//! Signing in with brute-force protection.
+//!
+//! Failed attempts are recorded per username and per ip address. After a few
+//! of them every further attempt has to wait exponentially longer, and after
+//! many failed attempts on the same username it's locked for a while and the
+//! owner of the account is notified by email. Usernames that don't exist are
+//! treated exactly like the ones that do, including verifying the password
+//! against a dummy hash, so responses don't reveal whether a username exists.
+//! Signing in and locking the username only reset the failures counted against
+//! the username, not the ones counted against the ip address.
+
+use leptos::*;
+#[cfg(feature = "ssr")]
+use {
+    crate::{config::SignInConfig, types::*},
+    std::{sync::OnceLock, time::Duration},
+};
+
+/// The id, email address and password hash of a user signing in.
+#[cfg(feature = "ssr")]
+#[derive(Debug)]
+pub struct Credentials {
+    pub user_id: UserId,
+    pub email: Email,
+    pub phc_string: String,
+}
+
+/// The failed sign-in attempts counted against an attempt.
+#[cfg(feature = "ssr")]
+#[derive(Debug, Default)]
+pub struct Failures {
+    /// The number of failed attempts on the username.
+    pub username: i64,
+
+    /// The number of failed attempts from the ip address.
+    pub ip_address: i64,
+
+    /// When the last of them happened.
+    pub last: Option<DateTime>,
+}
+
+/// The error type for signing in.
+#[cfg(feature = "ssr")]
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("invalid username or password")]
+    InvalidCredentials,
+
+    #[error("too many failed sign-in attempts, try again in {wait_seconds} seconds")]
+    Backoff { wait_seconds: u64 },
+
+    #[error("too many failed sign-in attempts, the account is locked until {0}")]
+    Locked(DateTime),
+}
+
+#[cfg(feature = "ssr")]
+static CONFIG: OnceLock<SignInConfig> = OnceLock::new();
+
+/// The hash unknown usernames' passwords are verified against, so signing in
+/// takes as long as for a username that exists. It's hashed in [`init`], so no
+/// attempt pays for hashing it.
+#[cfg(feature = "ssr")]
+static DUMMY_PHC_STRING: OnceLock<String> = OnceLock::new();
+
+/// How often the failed attempts that are no longer counted are dropped.
+#[cfg(feature = "ssr")]
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Sets the limits of failed sign-in attempts, hashes the dummy password and
+/// periodically drops the attempts that are no longer counted.
+#[cfg(feature = "ssr")]
+pub async fn init(config: SignInConfig) -> Result<(), argon2::password_hash::Error> {
+    let window = chrono::Duration::minutes(config.failure_window_minutes.get() as i64);
+    if CONFIG.set(config).is_err() {
+        panic!("sign in already initialized");
+    }
+    let dummy = Password(uuid::Uuid::new_v4().to_string()).hash().await?;
+    if DUMMY_PHC_STRING.set(dummy).is_err() {
+        panic!("sign in already initialized");
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(error) =
+                crate::postgres::prune_sign_in_failures(chrono::Utc::now() - window).await
+            {
+                tracing::error!(?error, "failed to prune sign-in failures");
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+fn config() -> &'static SignInConfig {
+    CONFIG.get().expect("sign in is not initialized")
+}
+
+/// Returns how long to wait after the last failed attempt, given the number of
+/// failed attempts.
+#[cfg(feature = "ssr")]
+fn backoff(failures: i64, free_failures: u32, base: Duration, max: Duration) -> Duration {
+    let Some(exponent) = failures
+        .checked_sub(i64::from(free_failures))
+        .filter(|exponent| *exponent >= 0)
+    else {
+        return Duration::ZERO;
+    };
+    u32::try_from(exponent)
+        .ok()
+        .and_then(|exponent| 2_u32.checked_pow(exponent))
+        .and_then(|factor| base.checked_mul(factor))
+        .map_or(max, |backoff| backoff.min(max))
+}
+
+/// Returns the username as failed attempts on it are recorded, cut to the
+/// longest username that can exist so attempts can't store arbitrarily long
+/// strings.
+#[cfg(feature = "ssr")]
+fn failure_key(username: &str) -> &str {
+    let mut end = username.len().min(Username::MAX_BYTES);
+    while !username.is_char_boundary(end) {
+        end -= 1;
+    }
+    &username[..end]
+}
+
+/// Signs the user in with the username and password.
+#[tracing::instrument(skip_all)]
+#[server(SignIn, "/api")]
+pub async fn sign_in(cx: Scope, username: String, password: String) -> Result<(), ServerFnError> {
+    use {
+        crate::{
+            address,
+            rate_limit::{self, Policy},
+            routes::HOME,
+            session,
+            types::Password,
+        },
+        actix_web::HttpRequest,
+    };
+
+    rate_limit::check(cx, Policy::Signin).await?;
+    let request = use_context::<HttpRequest>(cx)
+        .ok_or_else(|| ServerFnError::ServerError("missing request".to_owned()))?;
+    let ip_address = address::parse(&request)
+        .map_err(|error| ServerFnError::ServerError(error.to_string()))?
+        .to_string();
+
+    match authenticate(username.trim(), &Password(password), &ip_address).await? {
+        Ok(user_id) => {
+            session::start(cx, user_id).await?;
+            leptos_actix::redirect(cx, HOME);
+            Ok(())
+        }
+        Err(error) => Err(ServerFnError::ServerError(error.to_string())),
+    }
+}
+
+/// Returns the id of the user if the password is theirs and the attempt isn't
+/// throttled, or the reason why the user can't sign in.
+#[cfg(feature = "ssr")]
+async fn authenticate(
+    username: &str,
+    password: &Password,
+    ip_address: &str,
+) -> Result<Result<UserId, Error>, ServerFnError> {
+    use crate::{mailer, postgres};
+
+    let config = config();
+    let now = chrono::Utc::now();
+    let failure_key = failure_key(username);
+
+    if let Some(locked_until) = postgres::sign_in_locked_until(failure_key).await? {
+        return Ok(Err(Error::Locked(locked_until)));
+    }
+
+    let window = chrono::Duration::minutes(config.failure_window_minutes.get() as i64);
+    let failures = postgres::sign_in_failures(failure_key, ip_address, now - window).await?;
+    if let Some(last) = failures.last {
+        let backoff = backoff(
+            failures.username.max(failures.ip_address),
+            config.free_failures,
+            Duration::from_secs(config.backoff_base_seconds.get()),
+            Duration::from_secs(config.max_backoff_seconds.get()),
+        );
+        let retry = last + chrono::Duration::from_std(backoff)?;
+        if retry > now {
+            let wait_seconds = (retry - now).num_seconds().max(1) as u64;
+            return Ok(Err(Error::Backoff { wait_seconds }));
+        }
+    }
+
+    let credentials = postgres::sign_in_credentials(username).await?;
+    let phc_string = match &credentials {
+        Some(credentials) => credentials.phc_string.clone(),
+        None => DUMMY_PHC_STRING
+            .get()
+            .expect("sign in is not initialized")
+            .clone(),
+    };
+    let verified = password.verify(phc_string).await;
+
+    if let (true, Some(credentials)) = (verified, &credentials) {
+        postgres::clear_sign_in_failures(failure_key).await?;
+        return Ok(Ok(credentials.user_id));
+    }
+
+    postgres::record_sign_in_failure(failure_key, ip_address).await?;
+    if failures.username + 1 < i64::from(config.lockout_failures.get()) {
+        return Ok(Err(Error::InvalidCredentials));
+    }
+
+    let locked_until = now + chrono::Duration::minutes(config.lockout_minutes.get() as i64);
+    postgres::lock_sign_in(failure_key, locked_until).await?;
+    tracing::warn!(username = failure_key, ip_address, %locked_until, "locked sign in");
+    if let Some(credentials) = credentials {
+        mailer::send(
+            &credentials.email,
+            &format!("Your {} account was locked", crate::PRODUCT_NAME),
+            format!(
+                "There were {} failed attempts to sign in to your account, so it's locked \
+                 until {locked_until}. If it wasn't you, someone may be trying to guess your \
+                 password.\n\nIf you need help, contact us at {}.",
+                config.lockout_failures,
+                crate::SUPPORT_EMAIL,
+            ),
+        );
+    }
+    Ok(Err(Error::Locked(locked_until)))
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_failure_key() {
+        assert_eq!(failure_key("alice"), "alice");
+        assert_eq!(
+            failure_key(&"a".repeat(100)),
+            "a".repeat(Username::MAX_BYTES)
+        );
+        // cut before the character that would be split
+        let username = format!("{}é", "a".repeat(Username::MAX_BYTES - 1));
+        assert_eq!(failure_key(&username), "a".repeat(Username::MAX_BYTES - 1));
+    }
+
+    #[test]
+    fn test_backoff() {
+        let backoff =
+            |failures| backoff(failures, 3, Duration::from_secs(2), Duration::from_secs(60));
+        assert_eq!(backoff(0), Duration::ZERO);
+        assert_eq!(backoff(2), Duration::ZERO);
+        assert_eq!(backoff(3), Duration::from_secs(2));
+        assert_eq!(backoff(4), Duration::from_secs(4));
+        assert_eq!(backoff(7), Duration::from_secs(32));
+        assert_eq!(backoff(8), Duration::from_secs(60));
+        assert_eq!(backoff(100), Duration::from_secs(60));
+        assert_eq!(backoff(i64::MAX), Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,373 @@
+//! Spam filter module.
+//!
+//! Posts and comments run through a pipeline of filters before they're
+//! stored. Each filter checks one rule: banned words and patterns, the number
+//! of links posted by new accounts, the same content being posted over and
+//! over, and the reputation of the account. The verdict of every filter is
+//! stored with the content. Content breaking any rule is held: only its author
+//! sees it until a moderator approves it, and the moderator sees which rules it
+//! broke and why.
+
+use {
+    crate::internationalization::Translations,
+    serde::{Deserialize, Serialize},
+};
+#[cfg(feature = "ssr")]
+use {
+    crate::{config::SpamFilterConfig, postgres, types::UserId},
+    regex::{Regex, RegexBuilder},
+    sha2::{Digest, Sha256},
+    std::sync::OnceLock,
+};
+
+/// A rule checked by the spam filter.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(type_name = "filter_rule"))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize, Hash)]
+pub enum Rule {
+    BannedWord,
+    BannedPattern,
+
+    /// New accounts can only post a few links at once.
+    LinkLimit,
+
+    /// The same content was posted recently.
+    Duplicate,
+
+    /// The reputation of the account is too low.
+    Reputation,
+}
+
+impl Rule {
+    /// The localized label of the rule.
+    pub fn label(self, t: Translations) -> &'static str {
+        match self {
+            Rule::BannedWord => t.banned_word(),
+            Rule::BannedPattern => t.banned_pattern(),
+            Rule::LinkLimit => t.link_limit(),
+            Rule::Duplicate => t.duplicate_content(),
+            Rule::Reputation => t.low_reputation(),
+        }
+    }
+}
+
+/// The verdict of a filter on a post or comment.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Verdict {
+    pub rule: Rule,
+    pub passed: bool,
+
+    /// Why the rule was broken, empty if it passed.
+    pub details: String,
+}
+
+/// What the filters know about a post or comment and its author.
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone)]
+pub struct Content<'a> {
+    /// The title, url and body of a post or the body of a comment.
+    pub text: &'a str,
+
+    pub account_age: chrono::Duration,
+    pub reputation: i64,
+
+    /// The number of identical posts and comments posted recently.
+    pub duplicates: i64,
+}
+
+/// A filter checking a post or comment against a rule.
+#[cfg(feature = "ssr")]
+pub trait Filter: Send + Sync {
+    /// The rule the filter checks.
+    fn rule(&self) -> Rule;
+
+    /// Returns why the content breaks the rule, if it does.
+    fn check(&self, content: &Content<'_>) -> Option<String>;
+}
+
+/// Holds content containing any of the banned words.
+#[cfg(feature = "ssr")]
+pub struct BannedWords(Option<Regex>);
+
+#[cfg(feature = "ssr")]
+impl BannedWords {
+    /// Creates the filter of the words. Blank words are skipped, as they'd
+    /// match every text.
+    pub fn new(words: &[String]) -> Self {
+        let words = words
+            .iter()
+            .map(|word| word.trim())
+            .filter(|word| !word.is_empty())
+            .map(regex::escape)
+            .collect::<Vec<_>>();
+        Self((!words.is_empty()).then(|| {
+            // not \b so words starting or ending with punctuation match too
+            let words = words.join("|");
+            RegexBuilder::new(&format!(r"(?:^|\W)({words})(?:\W|$)"))
+                .case_insensitive(true)
+                .build()
+                .expect("escaped words are a valid regex")
+        }))
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl Filter for BannedWords {
+    fn rule(&self) -> Rule {
+        Rule::BannedWord
+    }
+
+    fn check(&self, content: &Content<'_>) -> Option<String> {
+        let captures = self.0.as_ref()?.captures(content.text)?;
+        Some(format!("contains \"{}\"", &captures[1]))
+    }
+}
+
+/// Holds content matching any of the banned patterns.
+#[cfg(feature = "ssr")]
+pub struct BannedPatterns(pub Vec<Regex>);
+
+#[cfg(feature = "ssr")]
+impl Filter for BannedPatterns {
+    fn rule(&self) -> Rule {
+        Rule::BannedPattern
+    }
+
+    fn check(&self, content: &Content<'_>) -> Option<String> {
+        let pattern = self
+            .0
+            .iter()
+            .find(|pattern| pattern.is_match(content.text))?;
+        Some(format!("matches {pattern}"))
+    }
+}
+
+/// Holds content of new accounts with too many links.
+#[cfg(feature = "ssr")]
+pub struct LinkLimit {
+    pub new_account_days: u32,
+    pub max_links: usize,
+}
+
+#[cfg(feature = "ssr")]
+impl Filter for LinkLimit {
+    fn rule(&self) -> Rule {
+        Rule::LinkLimit
+    }
+
+    fn check(&self, content: &Content<'_>) -> Option<String> {
+        if content.account_age >= chrono::Duration::days(self.new_account_days.into()) {
+            return None;
+        }
+        let links = count_links(content.text);
+        (links > self.max_links).then(|| {
+            format!(
+                "{links} links from an account younger than {} days",
+                self.new_account_days
+            )
+        })
+    }
+}
+
+/// Holds content that was posted recently.
+#[cfg(feature = "ssr")]
+pub struct Duplicates {
+    pub window_hours: u64,
+}
+
+#[cfg(feature = "ssr")]
+impl Filter for Duplicates {
+    fn rule(&self) -> Rule {
+        Rule::Duplicate
+    }
+
+    fn check(&self, content: &Content<'_>) -> Option<String> {
+        (content.duplicates > 0).then(|| {
+            format!(
+                "{} identical posts or comments in the last {} hours",
+                content.duplicates, self.window_hours
+            )
+        })
+    }
+}
+
+/// Holds all content of accounts with a low reputation.
+#[cfg(feature = "ssr")]
+pub struct MinReputation(pub i64);
+
+#[cfg(feature = "ssr")]
+impl Filter for MinReputation {
+    fn rule(&self) -> Rule {
+        Rule::Reputation
+    }
+
+    fn check(&self, content: &Content<'_>) -> Option<String> {
+        (content.reputation < self.0)
+            .then(|| format!("reputation {} is below {}", content.reputation, self.0))
+    }
+}
+
+/// The filters and the settings for looking up duplicates.
+#[cfg(feature = "ssr")]
+struct Pipeline {
+    filters: Vec<Box<dyn Filter>>,
+    duplicate_window: chrono::Duration,
+    duplicate_min_bytes: usize,
+}
+
+#[cfg(feature = "ssr")]
+static PIPELINE: OnceLock<Pipeline> = OnceLock::new();
+
+/// Builds the filter pipeline from the config.
+#[cfg(feature = "ssr")]
+pub fn init(config: SpamFilterConfig) {
+    let window_hours = config.duplicate_window_hours.get();
+    let pipeline = Pipeline {
+        filters: vec![
+            Box::new(BannedWords::new(&config.banned_words)),
+            Box::new(BannedPatterns(config.banned_patterns)),
+            Box::new(LinkLimit {
+                new_account_days: config.new_account_days,
+                max_links: config.new_account_max_links,
+            }),
+            Box::new(Duplicates { window_hours }),
+            Box::new(MinReputation(config.min_reputation)),
+        ],
+        duplicate_window: chrono::Duration::hours(window_hours as i64),
+        duplicate_min_bytes: config.duplicate_min_bytes,
+    };
+    if PIPELINE.set(pipeline).is_err() {
+        panic!("spam filter already initialized");
+    }
+}
+
+/// The result of running a post or comment through the spam filter.
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The hash of the normalized text, stored to find duplicates.
+    pub content_hash: Vec<u8>,
+
+    pub verdicts: Vec<Verdict>,
+}
+
+#[cfg(feature = "ssr")]
+impl Outcome {
+    /// Whether the content broke any rule and has to be reviewed.
+    pub fn held(&self) -> bool {
+        self.verdicts.iter().any(|verdict| !verdict.passed)
+    }
+}
+
+/// Runs a post or comment by the author through all filters.
+#[cfg(feature = "ssr")]
+pub async fn check(author: UserId, text: &str) -> Result<Outcome, postgres::Error> {
+    let pipeline = PIPELINE.get().expect("spam filter not initialized");
+
+    let normalized = normalize(text);
+    let content_hash = Sha256::digest(normalized.as_bytes()).to_vec();
+    let (created, reputation) = postgres::author_standing(author).await?;
+    let duplicates = if normalized.len() >= pipeline.duplicate_min_bytes {
+        postgres::duplicates(
+            &content_hash,
+            chrono::Utc::now() - pipeline.duplicate_window,
+        )
+        .await?
+    } else {
+        0
+    };
+
+    let content = Content {
+        text,
+        account_age: chrono::Utc::now() - created,
+        reputation,
+        duplicates,
+    };
+    let verdicts = pipeline
+        .filters
+        .iter()
+        .map(|filter| {
+            let details = filter.check(&content);
+            Verdict {
+                rule: filter.rule(),
+                passed: details.is_none(),
+                details: details.unwrap_or_default(),
+            }
+        })
+        .collect::<Vec<_>>();
+    if verdicts.iter().any(|verdict| !verdict.passed) {
+        tracing::info!(?author, ?verdicts, "holding content");
+    }
+
+    Ok(Outcome {
+        content_hash,
+        verdicts,
+    })
+}
+
+/// Lowercases the text and collapses whitespace so trivial variations of the
+/// same text hash the same.
+#[cfg(feature = "ssr")]
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Counts the http and https links in the text.
+#[cfg(feature = "ssr")]
+fn count_links(text: &str) -> usize {
+    let text = text.to_lowercase();
+    text.matches("http://").count() + text.matches("https://").count()
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod test {
+    use super::*;
+
+    fn content(text: &str) -> Content<'_> {
+        Content {
+            text,
+            account_age: chrono::Duration::days(1),
+            reputation: 0,
+            duplicates: 0,
+        }
+    }
+
+    #[test]
+    fn test_banned_words() {
+        let filter = BannedWords::new(&["viagra".to_owned(), "c++".to_owned()]);
+        assert!(filter.check(&content("Buy VIAGRA now")).is_some());
+        assert!(filter.check(&content("I like c++ too")).is_some());
+        assert!(filter.check(&content("viagrafalls")).is_none());
+        assert!(BannedWords::new(&[]).check(&content("anything")).is_none());
+        let blank = BannedWords::new(&["".to_owned(), " ".to_owned(), "viagra".to_owned()]);
+        assert!(blank.check(&content("anything")).is_none());
+        assert!(blank.check(&content("Buy VIAGRA now")).is_some());
+        assert!(BannedWords::new(&[" ".to_owned()])
+            .check(&content("anything"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_link_limit() {
+        let filter = LinkLimit {
+            new_account_days: 3,
+            max_links: 1,
+        };
+        let links = "https://a.example HTTP://b.example";
+        assert!(filter.check(&content(links)).is_some());
+        assert!(filter.check(&content("https://a.example")).is_none());
+        let old_account = Content {
+            account_age: chrono::Duration::days(30),
+            ..content(links)
+        };
+        assert!(filter.check(&old_account).is_none());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("  Hello\n\tWORLD  "), "hello world");
+    }
+}
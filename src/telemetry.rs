@@ -0,0 +1,176 @@
+//! OpenTelemetry trace export.
+//!
+//! Spans are exported over OTLP/HTTP when an OTLP config is given. Requests
+//! carrying a W3C `traceparent` header continue the caller's trace.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::config::OtlpConfig,
+    actix_web::http::header::HeaderMap,
+    opentelemetry::{
+        global,
+        propagation::Extractor,
+        runtime,
+        sdk::{
+            trace::{self, Sampler, TracerProvider},
+            Resource,
+        },
+        trace::TraceError,
+        Context, KeyValue,
+    },
+    opentelemetry_otlp::WithExportConfig,
+};
+
+/// Returns a tracer provider exporting spans in batches to the collector.
+/// Must be called within a tokio runtime.
+pub fn tracer_provider(config: &OtlpConfig) -> Result<TracerProvider, TraceError> {
+    let exporter = opentelemetry_otlp::new_exporter()
+        .http()
+        .with_endpoint(config.endpoint.clone());
+    let exporter = opentelemetry_otlp::SpanExporterBuilder::from(exporter).build_span_exporter()?;
+    Ok(TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_config(
+            trace::config()
+                .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                    config.sample_ratio,
+                ))))
+                .with_resource(Resource::new([KeyValue::new(
+                    "service.name",
+                    config.service_name.clone(),
+                )])),
+        )
+        .build())
+}
+
+/// Returns the trace context propagated in the headers of a request, if any.
+pub fn parent_context(headers: &HeaderMap) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)))
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::{internationalization::Language, legal::Document},
+        actix_web::http::header::{HeaderName, HeaderValue},
+        opentelemetry::{
+            sdk::propagation::TraceContextPropagator,
+            trace::{TraceContextExt, TraceId, TracerProvider as _},
+        },
+        tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::{TcpListener, TcpStream},
+        },
+        tracing_subscriber::layer::SubscriberExt,
+    };
+
+    #[test]
+    fn test_parent_context() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            HeaderName::from_static("traceparent"),
+            HeaderValue::from_static("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"),
+        );
+        assert_eq!(
+            parent_context(&headers).span().span_context().trace_id(),
+            TraceId::from_hex("0af7651916cd43dd8448eb211c80319c").unwrap()
+        );
+        assert!(!parent_context(&HeaderMap::new())
+            .span()
+            .span_context()
+            .is_valid());
+    }
+
+    /// Reads a whole http request, up to the length of its body.
+    async fn read_request(stream: &mut TcpStream) -> Vec<u8> {
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+        loop {
+            let length = stream.read(&mut buffer).await.unwrap();
+            assert!(length > 0, "the request ended early");
+            request.extend_from_slice(&buffer[..length]);
+            let text = String::from_utf8_lossy(&request).to_ascii_lowercase();
+            if let Some(end) = text.find("\r\n\r\n") {
+                let content_length = text[..end]
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map_or(0, |length| length.trim().parse().unwrap());
+                if request.len() >= end + 4 + content_length {
+                    return request;
+                }
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_export() {
+        // a stand-in for a collector, accepting every export since the spans
+        // may be split into more than one batch on a busy machine
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/v1/traces", listener.local_addr().unwrap());
+        let (requests, mut received) = tokio::sync::mpsc::unbounded_channel();
+        let collector = tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let request = read_request(&mut stream).await;
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                    .await
+                    .unwrap();
+                requests
+                    .send(String::from_utf8_lossy(&request).into_owned())
+                    .unwrap();
+            }
+        });
+
+        let provider = tracer_provider(&OtlpConfig {
+            endpoint,
+            service_name: "marzichat-test".to_owned(),
+            sample_ratio: 1.0,
+        })
+        .unwrap();
+        {
+            let _subscriber = tracing::subscriber::set_default(
+                tracing_subscriber::registry()
+                    .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test"))),
+            );
+            // a server function and a query, which fails without postgres
+            crate::legal::get_legal_document(Document::TermsAndConditions, Language::English)
+                .await
+                .unwrap();
+            crate::postgres::init_lazy("postgres://127.0.0.1:1/marzichat");
+            assert!(crate::postgres::ping().await.is_err());
+        }
+        let results = tokio::task::spawn_blocking(move || provider.force_flush())
+            .await
+            .unwrap();
+        assert!(results.iter().all(Result::is_ok), "{results:?}");
+        collector.abort();
+
+        let mut exported = String::new();
+        while let Ok(request) = received.try_recv() {
+            assert!(request.starts_with("POST /v1/traces HTTP/1.1\r\n"));
+            assert!(request
+                .to_ascii_lowercase()
+                .contains("content-type: application/x-protobuf"));
+            exported.push_str(&request);
+        }
+        // the span names are plain strings in the protobuf bodies
+        assert!(exported.contains("get_legal_document"));
+        assert!(exported.contains("ping"));
+    }
+}
@@ -0,0 +1,52 @@
+//! Tls certificate of the server, replaceable while the server is running so
+//! renewed certificates are picked up without dropping connections.
+#![cfg(feature = "ssr")]
+
+use {
+    crate::{config::TlsCertificate, types::DateTime},
+    chrono::{TimeZone, Utc},
+    rustls::server::{ClientHello, ResolvesServerCert},
+    std::sync::{Arc, OnceLock, RwLock},
+    x509_parser::{certificate::X509Certificate, prelude::FromDer},
+};
+
+/// Resolves every handshake to the current certificate.
+struct CertificateResolver(RwLock<Arc<TlsCertificate>>);
+
+impl ResolvesServerCert for CertificateResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<TlsCertificate>> {
+        Some(Arc::clone(&self.0.read().expect("poisoned lock")))
+    }
+}
+
+static RESOLVER: OnceLock<Arc<CertificateResolver>> = OnceLock::new();
+
+/// Returns the tls config of the server, serving the certificate until it's
+/// replaced with [`reload`].
+pub fn server_config(certificate: TlsCertificate) -> rustls::ServerConfig {
+    let resolver = Arc::new(CertificateResolver(RwLock::new(Arc::new(certificate))));
+    if RESOLVER.set(Arc::clone(&resolver)).is_err() {
+        panic!("tls already initialized");
+    }
+    rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_cert_resolver(resolver)
+}
+
+/// Replaces the certificate used for new connections. Returns false if tls
+/// isn't enabled.
+pub fn reload(certificate: TlsCertificate) -> bool {
+    let Some(resolver) = RESOLVER.get() else {
+        return false;
+    };
+    *resolver.0.write().expect("poisoned lock") = Arc::new(certificate);
+    true
+}
+
+/// Returns the end of the validity period of a certificate.
+pub fn not_after(certificate: &rustls::Certificate) -> Option<DateTime> {
+    let (_, certificate) = X509Certificate::from_der(&certificate.0).ok()?;
+    Utc.timestamp_opt(certificate.validity().not_after.timestamp(), 0)
+        .single()
+}
@@ -0,0 +1,50 @@
+use {
+    crate::types::validation::{Validate, Validator, Violations},
+    derive_more::{Display, From},
+    serde::{Deserialize, Serialize},
+};
+
+/// The name of a community, e.g. `rust` for `/c/rust`.
+///
+/// Names are unique regardless of case.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type), sqlx(transparent))]
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash, Serialize, Deserialize, From, Display,
+)]
+#[from(forward)]
+pub struct CommunityName(pub String);
+
+impl CommunityName {
+    /// The maximum number of bytes a community name can be.
+    // Don't change without updating the communities table contraints.
+    pub const MAX_BYTES: usize = 21;
+    /// The minimum number of bytes a community name can be.
+    // Don't change without updating the communities table contraints.
+    pub const MIN_BYTES: usize = 3;
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Display, Hash)]
+pub enum Violation {
+    TooLong,
+    TooShort,
+    Invalid,
+}
+
+/// Validate a community name.
+pub fn validate(name: &str) -> Result<(), Violations<Violation>> {
+    let all_chars_valid = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let starts_with_letter = name.starts_with(|c: char| c.is_ascii_alphabetic());
+    Validator::new()
+        .invalid_if(name.len() > CommunityName::MAX_BYTES, Violation::TooLong)
+        .invalid_if(name.len() < CommunityName::MIN_BYTES, Violation::TooShort)
+        .invalid_if(!all_chars_valid || !starts_with_letter, Violation::Invalid)
+        .into()
+}
+
+impl Validate for CommunityName {
+    type Violation = Violation;
+
+    fn validate(&self) -> Result<(), Violations<Self::Violation>> {
+        validate(&self.0)
+    }
+}
@@ -6,12 +6,18 @@ use {
     uuid::Uuid,
 };
 
+pub mod community_name;
 pub mod datetime;
 pub mod email;
 pub mod password;
+pub mod text;
+pub mod title;
 pub mod username;
 
-pub use {datetime::DateTime, email::Email, password::Password, username::Username};
+pub use {
+    community_name::CommunityName, datetime::DateTime, email::Email, password::Password,
+    text::Text, title::Title, username::Username,
+};
 
 /// A user id.
 #[cfg_attr(feature = "sqlx", derive(sqlx::Type), sqlx(transparent))]
@@ -21,6 +27,46 @@ pub use {datetime::DateTime, email::Email, password::Password, username::Usernam
 #[from(forward)]
 pub struct UserId(pub Uuid);
 
+/// A post id.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type), sqlx(transparent))]
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize, From, Display, Hash,
+)]
+#[from(forward)]
+pub struct PostId(pub Uuid);
+
+/// A community id.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type), sqlx(transparent))]
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize, From, Display, Hash,
+)]
+#[from(forward)]
+pub struct CommunityId(pub Uuid);
+
+/// A report id.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type), sqlx(transparent))]
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize, From, Display, Hash,
+)]
+#[from(forward)]
+pub struct ReportId(pub Uuid);
+
+/// A sanction id.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type), sqlx(transparent))]
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize, From, Display, Hash,
+)]
+#[from(forward)]
+pub struct SanctionId(pub Uuid);
+
+/// A comment id.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type), sqlx(transparent))]
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize, From, Display, Hash,
+)]
+#[from(forward)]
+pub struct CommentId(pub Uuid);
+
 pub mod validation {
     //! # Validation module.
     //!
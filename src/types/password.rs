@@ -17,6 +17,46 @@ pub struct Password(pub String);
 impl Password {
     pub const MAX_BYTES: usize = 64;
     pub const MIN_BYTES: usize = 8;
+
+    /// Hashes the password with Argon2 on the cpu-bound thread pool and
+    /// returns the hash as a PHC string.
+    #[cfg(feature = "ssr")]
+    pub async fn hash(&self) -> Result<String, argon2::password_hash::Error> {
+        use argon2::{
+            password_hash::{rand_core::OsRng, SaltString},
+            Argon2, PasswordHasher,
+        };
+
+        let password = self.clone();
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        rayon::spawn(move || {
+            let salt = SaltString::generate(&mut OsRng);
+            let phc_string = Argon2::default()
+                .hash_password(password.0.as_bytes(), &salt)
+                .map(|hash| hash.to_string());
+            drop(sender.send(phc_string));
+        });
+        receiver.await.expect("password hashing task panicked")
+    }
+
+    /// Verifies the password against a PHC string with Argon2 on the cpu-bound
+    /// thread pool. Returns false if the PHC string can't be parsed.
+    #[cfg(feature = "ssr")]
+    pub async fn verify(&self, phc_string: String) -> bool {
+        use argon2::{Argon2, PasswordHash, PasswordVerifier};
+
+        let password = self.clone();
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        rayon::spawn(move || {
+            let verified = PasswordHash::new(&phc_string).is_ok_and(|hash| {
+                Argon2::default()
+                    .verify_password(password.0.as_bytes(), &hash)
+                    .is_ok()
+            });
+            let _ = sender.send(verified);
+        });
+        receiver.await.expect("password verification task panicked")
+    }
 }
 
 impl fmt::Debug for Password {
@@ -31,11 +71,16 @@ pub enum Violation {
     TooLong,
     TooShort,
     TooSimple,
+
+    /// The password appeared in a data breach.
+    Breached,
 }
 
-/// Validate a password.
-pub fn validate(password: &str) -> Result<(), Violations<Violation>> {
-    let entropy_too_low = match zxcvbn::zxcvbn(password, &[]) {
+/// Validate a password. The user inputs, like the username and email address,
+/// make passwords built from them count as simpler. Passwords are only checked
+/// against the breached password list on the server.
+pub fn validate(password: &str, user_inputs: &[&str]) -> Result<(), Violations<Violation>> {
+    let entropy_too_low = match zxcvbn::zxcvbn(password, user_inputs) {
         Ok(entropy) => entropy.score() < MIN_ENTROPY_SCORE,
         Err(_) => false, // if we get any errors here, the entropy is still too low
     };
@@ -43,13 +88,24 @@ pub fn validate(password: &str) -> Result<(), Violations<Violation>> {
         .invalid_if(password.len() > Password::MAX_BYTES, Violation::TooLong)
         .invalid_if(password.len() < Password::MIN_BYTES, Violation::TooShort)
         .invalid_if(entropy_too_low, Violation::TooSimple)
+        .invalid_if(is_breached(password), Violation::Breached)
         .into()
 }
 
+#[cfg(feature = "ssr")]
+fn is_breached(password: &str) -> bool {
+    crate::breached_passwords::contains(password)
+}
+
+#[cfg(not(feature = "ssr"))]
+fn is_breached(_password: &str) -> bool {
+    false
+}
+
 impl Validate for Password {
     type Violation = Violation;
 
     fn validate(&self) -> Result<(), Violations<Self::Violation>> {
-        validate(&self.0)
+        validate(&self.0, &[])
     }
 }
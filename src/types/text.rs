@@ -0,0 +1,42 @@
+use {
+    crate::types::validation::{Validate, Validator, Violations},
+    derive_more::{Display, From},
+    serde::{Deserialize, Serialize},
+};
+
+/// Markdown text written by a user, e.g. the body of a post or comment or a
+/// bio.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type), sqlx(transparent))]
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash, Serialize, Deserialize, From, Display,
+)]
+#[from(forward)]
+pub struct Text(pub String);
+
+impl Text {
+    /// The maximum number of bytes a text can be.
+    // Don't change without updating the posts and comments table contraints.
+    pub const MAX_BYTES: usize = 40_000;
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Display, Hash)]
+pub enum Violation {
+    Empty,
+    TooLong,
+}
+
+/// Validate a text.
+pub fn validate(text: &str) -> Result<(), Violations<Violation>> {
+    Validator::new()
+        .invalid_if(text.trim().is_empty(), Violation::Empty)
+        .invalid_if(text.len() > Text::MAX_BYTES, Violation::TooLong)
+        .into()
+}
+
+impl Validate for Text {
+    type Violation = Violation;
+
+    fn validate(&self) -> Result<(), Violations<Self::Violation>> {
+        validate(&self.0)
+    }
+}
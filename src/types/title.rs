@@ -0,0 +1,41 @@
+use {
+    crate::types::validation::{Validate, Validator, Violations},
+    derive_more::{Display, From},
+    serde::{Deserialize, Serialize},
+};
+
+/// The title of a post.
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type), sqlx(transparent))]
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash, Serialize, Deserialize, From, Display,
+)]
+#[from(forward)]
+pub struct Title(pub String);
+
+impl Title {
+    /// The maximum number of bytes a title can be.
+    // Don't change without updating the posts table contraints.
+    pub const MAX_BYTES: usize = 300;
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Display, Hash)]
+pub enum Violation {
+    Empty,
+    TooLong,
+}
+
+/// Validate a title.
+pub fn validate(title: &str) -> Result<(), Violations<Violation>> {
+    Validator::new()
+        .invalid_if(title.trim().is_empty(), Violation::Empty)
+        .invalid_if(title.len() > Title::MAX_BYTES, Violation::TooLong)
+        .into()
+}
+
+impl Validate for Title {
+    type Violation = Violation;
+
+    fn validate(&self) -> Result<(), Violations<Self::Violation>> {
+        validate(&self.0)
+    }
+}